@@ -0,0 +1,265 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use std::collections::HashSet;
+use std::fmt;
+use syn::ext::IdentExt;
+use syn::parse::{ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+
+/// ABI type given by `#[abi(type = "...")]`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AbiType {
+    Uint(usize),
+    Bytes,
+    FixedBytes(usize),
+    Address,
+    Bool,
+}
+
+impl AbiType {
+    fn parse(lit: &syn::LitStr) -> syn::Result<Self> {
+        let value = lit.value();
+        let abi_type = match value.as_str() {
+            "uint" => Some(AbiType::Uint(256)),
+            "bytes" => Some(AbiType::Bytes),
+            "address" => Some(AbiType::Address),
+            "bool" => Some(AbiType::Bool),
+            _ if value.starts_with("uint") => value[4..]
+                .parse::<usize>()
+                .ok()
+                .filter(|size| *size > 0 && *size <= 256 && size % 8 == 0)
+                .map(AbiType::Uint),
+            _ if value.starts_with("bytes") => value[5..]
+                .parse::<usize>()
+                .ok()
+                .filter(|size| *size > 0 && *size <= 32)
+                .map(AbiType::FixedBytes),
+            _ => None,
+        };
+        abi_type.ok_or_else(|| syn::Error::new(lit.span(), format!("unknown ABI type `{}`", value)))
+    }
+}
+
+impl fmt::Display for AbiType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AbiType::Uint(size) => write!(f, "uint{}", size),
+            AbiType::Bytes => write!(f, "bytes"),
+            AbiType::FixedBytes(size) => write!(f, "bytes{}", size),
+            AbiType::Address => write!(f, "address"),
+            AbiType::Bool => write!(f, "bool"),
+        }
+    }
+}
+
+/// Attributes of a struct or an enum
+pub struct ContainerAttrs {
+    tag: Option<syn::Path>,
+}
+
+impl ContainerAttrs {
+    pub fn from_ast(ast: &syn::DeriveInput) -> syn::Result<Self> {
+        let is_enum = match ast.data {
+            syn::Data::Enum(_) => true,
+            _ => false,
+        };
+        let mut tag = None;
+        for meta in parse_abi_attrs(&ast.attrs)? {
+            match meta.key.to_string().as_str() {
+                "tag" if is_enum => tag = Some(meta.expect_value()?.parse()?),
+                "tag" => {
+                    return Err(syn::Error::new(
+                        meta.key.span(),
+                        "`tag` is only supported on enums",
+                    ))
+                }
+                _ => return Err(meta.unknown()),
+            }
+        }
+        Ok(ContainerAttrs { tag })
+    }
+
+    /// Returns the VariantTag given by `#[abi(tag = "...")]`, UintTag is used by default
+    pub fn get_tag(&self) -> TokenStream2 {
+        match &self.tag {
+            Some(tag) => quote! { #tag },
+            None => quote! { plasma_core::data_structure::abi::UintTag },
+        }
+    }
+}
+
+/// Attributes of a field
+#[derive(Default)]
+pub struct FieldAttrs {
+    pub skip: bool,
+    pub abi_type: Option<AbiType>,
+    pub rename: Option<String>,
+    pub default: Option<syn::Expr>,
+}
+
+impl FieldAttrs {
+    pub fn from_field(field: &syn::Field) -> syn::Result<Self> {
+        let mut attrs = FieldAttrs::default();
+        let mut has_default = false;
+        for meta in parse_abi_attrs(&field.attrs)? {
+            match meta.key.to_string().as_str() {
+                "skip" => {
+                    meta.expect_no_value()?;
+                    attrs.skip = true;
+                }
+                "type" => attrs.abi_type = Some(AbiType::parse(meta.expect_value()?)?),
+                "rename" => {
+                    let lit = meta.expect_value()?;
+                    syn::parse_str::<syn::Ident>(&lit.value()).map_err(|_| {
+                        syn::Error::new(lit.span(), "`rename` must be an identifier")
+                    })?;
+                    attrs.rename = Some(lit.value());
+                }
+                "default" => {
+                    has_default = true;
+                    if let Some(lit) = &meta.value {
+                        attrs.default = Some(lit.parse()?);
+                    }
+                }
+                _ => return Err(meta.unknown()),
+            }
+        }
+        if has_default && !attrs.skip {
+            return Err(syn::Error::new(
+                field.span(),
+                "`default` can only be used with `skip`",
+            ));
+        }
+        if attrs.skip && (attrs.abi_type.is_some() || attrs.rename.is_some()) {
+            return Err(syn::Error::new(
+                field.span(),
+                "`type` and `rename` can't be used with `skip`",
+            ));
+        }
+        Ok(attrs)
+    }
+}
+
+/// Attributes of a variant
+pub struct VariantAttrs {
+    pub other: bool,
+}
+
+impl VariantAttrs {
+    fn from_variant(variant: &syn::Variant) -> syn::Result<Self> {
+        let mut other = false;
+        for meta in parse_abi_attrs(&variant.attrs)? {
+            match meta.key.to_string().as_str() {
+                "other" => {
+                    meta.expect_no_value()?;
+                    other = true;
+                }
+                _ => return Err(meta.unknown()),
+            }
+        }
+        if other {
+            match &variant.fields {
+                syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => (),
+                _ => {
+                    return Err(syn::Error::new(
+                        variant.span(),
+                        "`other` variant must have exactly one unnamed field",
+                    ))
+                }
+            }
+        }
+        Ok(VariantAttrs { other })
+    }
+}
+
+/// Parses attributes of all variants, only one variant can be `other`
+pub fn parse_variant_attrs(data_enum: &syn::DataEnum) -> syn::Result<Vec<VariantAttrs>> {
+    let mut has_other = false;
+    data_enum
+        .variants
+        .iter()
+        .map(|variant| {
+            let attrs = VariantAttrs::from_variant(variant)?;
+            if attrs.other && has_other {
+                return Err(syn::Error::new(
+                    variant.span(),
+                    "only one variant can be `other`",
+                ));
+            }
+            has_other |= attrs.other;
+            Ok(attrs)
+        })
+        .collect()
+}
+
+/// An item of `#[abi(...)]`, `key` or `key = "value"`
+struct AbiMeta {
+    key: syn::Ident,
+    value: Option<syn::LitStr>,
+}
+
+impl AbiMeta {
+    fn expect_value(&self) -> syn::Result<&syn::LitStr> {
+        self.value.as_ref().ok_or_else(|| {
+            syn::Error::new(
+                self.key.span(),
+                format!(
+                    "`{}` requires a value like `{} = \"...\"`",
+                    self.key, self.key
+                ),
+            )
+        })
+    }
+
+    fn expect_no_value(&self) -> syn::Result<()> {
+        match &self.value {
+            Some(value) => Err(syn::Error::new(
+                value.span(),
+                format!("`{}` doesn't take a value", self.key),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn unknown(&self) -> syn::Error {
+        syn::Error::new(
+            self.key.span(),
+            format!("unknown abi attribute `{}`", self.key),
+        )
+    }
+}
+
+fn parse_meta(input: ParseStream) -> syn::Result<AbiMeta> {
+    let key = input.call(syn::Ident::parse_any)?;
+    let value = if input.peek(syn::Token![=]) {
+        input.parse::<syn::Token![=]>()?;
+        Some(input.parse()?)
+    } else {
+        None
+    };
+    Ok(AbiMeta { key, value })
+}
+
+/// Collects items of all `#[abi(...)]` attributes, other attributes are left to other derives
+fn parse_abi_attrs(attrs: &[syn::Attribute]) -> syn::Result<Vec<AbiMeta>> {
+    let mut metas: Vec<AbiMeta> = vec![];
+    let mut keys = HashSet::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("abi")) {
+        let parser = |input: ParseStream| {
+            let content;
+            syn::parenthesized!(content in input);
+            Punctuated::<AbiMeta, syn::Token![,]>::parse_terminated_with(&content, parse_meta)
+        };
+        for meta in parser.parse2(attr.tokens.clone())? {
+            if !keys.insert(meta.key.to_string()) {
+                return Err(syn::Error::new(
+                    meta.key.span(),
+                    format!("duplicate abi attribute `{}`", meta.key),
+                ));
+            }
+            metas.push(meta);
+        }
+    }
+    Ok(metas)
+}
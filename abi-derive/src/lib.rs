@@ -1,184 +1,726 @@
+//! Derives `Encodable`, `Decodable` and `AbiSchema` of plasma-core.
+//!
+//! Structs are encoded as a tuple of their fields and enums are encoded as `(tag, bytes)`.
+//! `AbiSchema` describes the same layout as a Solidity struct whose fields are camelCase.
+//! Fields can be configured by `#[abi(...)]`.
+//!
+//! - `#[abi(skip)]` doesn't encode the field, it is decoded by `Default::default()`.
+//! - `#[abi(skip, default = "expr")]` decodes the skipped field by `expr`.
+//! - `#[abi(type = "uint64")]` changes the ABI type of the field.
+//! - `#[abi(rename = "name")]` changes the name of the field in the schema.
+//! - `#[abi(tag = "Path")]` on an enum changes the `VariantTag`.
+//! - `#[abi(other)]` on a variant with one field decodes unknown tags.
+//!   The field is encoded as the whole `(tag, bytes)` tuple.
+
 extern crate proc_macro;
 
+mod attr;
+
+use crate::attr::{AbiType, ContainerAttrs, FieldAttrs};
 use crate::proc_macro::TokenStream;
-#[allow(unused_imports)]
-use bytes::Bytes;
-#[allow(unused_imports)]
-use ethabi::Token;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use quote::quote;
-use syn;
+use std::collections::HashSet;
+use syn::spanned::Spanned;
 
-#[proc_macro_derive(AbiEncodable)]
+#[proc_macro_derive(AbiEncodable, attributes(abi))]
 pub fn encodable_macro_derive(input: TokenStream) -> TokenStream {
-    let ast = syn::parse(input).unwrap();
+    let ast = syn::parse_macro_input!(input as syn::DeriveInput);
     impl_encodable_macro(&ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
 
-#[proc_macro_derive(AbiDecodable)]
+#[proc_macro_derive(AbiDecodable, attributes(abi))]
 pub fn decodable_macro_derive(input: TokenStream) -> TokenStream {
-    let ast = syn::parse(input).unwrap();
+    let ast = syn::parse_macro_input!(input as syn::DeriveInput);
     impl_decodable_macro(&ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
 
-/// Builds encodable trait implementation
-fn impl_encodable_macro(ast: &syn::DeriveInput) -> TokenStream {
+#[proc_macro_derive(AbiSchema, attributes(abi))]
+pub fn schema_macro_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse_macro_input!(input as syn::DeriveInput);
+    impl_schema_macro(&ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Builds encodable trait implementation.
+/// Structs are encoded as a tuple of their fields.
+/// Enums are encoded as `(tag, bytes)` where bytes is the ABI of the variant fields.
+fn impl_encodable_macro(ast: &syn::DeriveInput) -> syn::Result<TokenStream2> {
     let name = &ast.ident;
-    if let syn::Data::Struct(data_struct) = &ast.data {
-        let field_list = data_struct
-            .fields
-            .iter()
-            .filter(|f| f.attrs.first().is_none())
-            .map(create_tokens);
-        let gen = quote! {
-            impl Encodable for #name {
-                fn to_tuple(&self) -> Vec<Token> {
-                    vec![#(#field_list),*]
+    let container = ContainerAttrs::from_ast(ast)?;
+    check_generics(ast)?;
+    let body = match &ast.data {
+        syn::Data::Struct(data_struct) => {
+            let fields = parse_fields(&data_struct.fields)?;
+            let bindings = create_bindings(&fields);
+            let pattern = create_pattern(quote! { #name }, &data_struct.fields, &bindings);
+            let token_list = create_token_list(&fields, &bindings);
+            quote! {
+                let #pattern = self;
+                vec![#(#token_list),*]
+            }
+        }
+        syn::Data::Enum(data_enum) => {
+            let tag = container.get_tag();
+            let variant_attrs = attr::parse_variant_attrs(data_enum)?;
+            let arms = data_enum
+                .variants
+                .iter()
+                .zip(variant_attrs.iter())
+                .enumerate()
+                .map(|(index, (variant, attrs))| {
+                    let ident = &variant.ident;
+                    if attrs.other {
+                        return Ok(quote! {
+                            #name::#ident(other) => {
+                                plasma_core::data_structure::abi::Encodable::to_tuple(other)
+                            }
+                        });
+                    }
+                    let fields = parse_fields(&variant.fields)?;
+                    let bindings = create_bindings(&fields);
+                    let pattern =
+                        create_pattern(quote! { #name::#ident }, &variant.fields, &bindings);
+                    let token_list = create_token_list(&fields, &bindings);
+                    let index = index as u64;
+                    Ok(quote! {
+                        #pattern => vec![
+                            <#tag as plasma_core::data_structure::abi::VariantTag>::to_token(#index),
+                            ethabi::Token::Bytes(ethabi::encode(&[#(#token_list),*])),
+                        ]
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! {
+                match self {
+                    #(#arms),*
                 }
             }
-        };
-        gen.into()
-    } else {
-        panic!("invalid data")
-    }
+        }
+        syn::Data::Union(_) => return Err(syn::Error::new(name.span(), "union isn't supported")),
+    };
+    Ok(quote! {
+        impl plasma_core::data_structure::abi::Encodable for #name {
+            fn to_tuple(&self) -> Vec<ethabi::Token> {
+                #body
+            }
+        }
+    })
 }
 
 /// Builds decodable trait implementation
-fn impl_decodable_macro(ast: &syn::DeriveInput) -> TokenStream {
+fn impl_decodable_macro(ast: &syn::DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &ast.ident;
+    let container = ContainerAttrs::from_ast(ast)?;
+    check_generics(ast)?;
+    let error = create_decode_error();
+    let (from_tuple, param_types) = match &ast.data {
+        syn::Data::Struct(data_struct) => {
+            let fields = parse_fields(&data_struct.fields)?;
+            let param_type_list = create_param_type_list(&fields);
+            (
+                create_constructor(quote! { #name }, &data_struct.fields, &fields),
+                quote! { vec![#(#param_type_list),*] },
+            )
+        }
+        syn::Data::Enum(data_enum) => {
+            let tag = container.get_tag();
+            let variant_attrs = attr::parse_variant_attrs(data_enum)?;
+            let mut fallback = quote! { Err(#error) };
+            let mut arms = vec![];
+            for (index, (variant, attrs)) in data_enum
+                .variants
+                .iter()
+                .zip(variant_attrs.iter())
+                .enumerate()
+            {
+                let ident = &variant.ident;
+                if attrs.other {
+                    let ty = &variant.fields.iter().next().unwrap().ty;
+                    fallback = quote! {
+                        <#ty as plasma_core::data_structure::abi::Decodable>::from_tuple(tuple)
+                            .map(#name::#ident)
+                    };
+                    continue;
+                }
+                let fields = parse_fields(&variant.fields)?;
+                let param_type_list = create_param_type_list(&fields);
+                let constructor =
+                    create_constructor(quote! { #name::#ident }, &variant.fields, &fields);
+                let index = index as u64;
+                arms.push(quote! {
+                    Some(#index) => {
+                        let decoded = ethabi::decode(&[#(#param_type_list),*], &data)?;
+                        let tuple = &decoded[..];
+                        #constructor
+                    }
+                });
+            }
+            (
+                quote! {
+                    plasma_core::data_structure::abi::check_length(tuple, 2)?;
+                    let index =
+                        <#tag as plasma_core::data_structure::abi::VariantTag>::from_token(tuple[0].clone());
+                    let data = tuple[1].clone().to_bytes().ok_or_else(|| #error)?;
+                    match index {
+                        #(#arms)*
+                        _ => #fallback,
+                    }
+                },
+                quote! {
+                    vec![
+                        <#tag as plasma_core::data_structure::abi::VariantTag>::get_param_type(),
+                        ethabi::ParamType::Bytes,
+                    ]
+                },
+            )
+        }
+        syn::Data::Union(_) => return Err(syn::Error::new(name.span(), "union isn't supported")),
+    };
+    Ok(quote! {
+        impl plasma_core::data_structure::abi::Decodable for #name {
+            type Ok = #name;
+            fn from_tuple(tuple: &[ethabi::Token]) -> Result<Self, plasma_core::data_structure::error::Error> {
+                #from_tuple
+            }
+            fn get_param_types() -> Vec<ethabi::ParamType> {
+                #param_types
+            }
+        }
+    })
+}
+
+/// Builds schema trait implementation.
+/// Enums are described as `(tag, data)` because variants are encoded in bytes.
+fn impl_schema_macro(ast: &syn::DeriveInput) -> syn::Result<TokenStream2> {
     let name = &ast.ident;
-    if let syn::Data::Struct(data_struct) = &ast.data {
-        impl_decodable_macro_for_struct(name, data_struct)
+    let container = ContainerAttrs::from_ast(ast)?;
+    check_generics(ast)?;
+    let field_list = match &ast.data {
+        syn::Data::Struct(data_struct) => parse_fields(&data_struct.fields)?
+            .iter()
+            .filter_map(|f| {
+                f.kind.as_ref().map(|kind| {
+                    let field_name = &f.name;
+                    let schema_type = create_schema_type(kind);
+                    quote! {
+                        plasma_core::data_structure::schema::SchemaField::new(#field_name, #schema_type)
+                    }
+                })
+            })
+            .collect(),
+        syn::Data::Enum(data_enum) => {
+            attr::parse_variant_attrs(data_enum)?;
+            for variant in data_enum.variants.iter() {
+                parse_fields(&variant.fields)?;
+            }
+            let tag = container.get_tag();
+            vec![
+                quote! {
+                    plasma_core::data_structure::schema::SchemaField::new(
+                        "tag",
+                        plasma_core::data_structure::schema::SchemaType::Elementary(
+                            <#tag as plasma_core::data_structure::abi::VariantTag>::get_param_type()
+                        ),
+                    )
+                },
+                quote! {
+                    plasma_core::data_structure::schema::SchemaField::new(
+                        "data",
+                        plasma_core::data_structure::schema::SchemaType::bytes(),
+                    )
+                },
+            ]
+        }
+        syn::Data::Union(_) => return Err(syn::Error::new(name.span(), "union isn't supported")),
+    };
+    let type_name = name.to_string();
+    Ok(quote! {
+        impl plasma_core::data_structure::schema::AbiSchema for #name {
+            fn get_schema() -> plasma_core::data_structure::schema::Schema {
+                plasma_core::data_structure::schema::Schema::new(#type_name, vec![#(#field_list),*])
+            }
+        }
+    })
+}
+
+fn check_generics(ast: &syn::DeriveInput) -> syn::Result<()> {
+    if ast.generics.params.is_empty() {
+        Ok(())
     } else {
-        panic!("haven't supported except struct")
+        Err(syn::Error::new(
+            ast.generics.span(),
+            "generic types aren't supported",
+        ))
     }
 }
 
-fn impl_decodable_macro_for_struct(
-    name: &syn::Ident,
-    data_struct: &syn::DataStruct,
-) -> TokenStream {
-    let fields = &data_struct.fields;
-    let param_type_list = fields
+fn create_decode_error() -> TokenStream2 {
+    quote! {
+        plasma_core::data_structure::error::Error::from(
+            plasma_core::data_structure::error::ErrorKind::AbiDecode
+        )
+    }
+}
+
+/// A field with its attributes, `kind` is `None` if the field is skipped
+struct AbiField<'a> {
+    name: String,
+    attrs: FieldAttrs,
+    kind: Option<Kind<'a>>,
+}
+
+/// Parses attributes and types of fields.
+/// The names of encoded fields must be unique after `rename`.
+fn parse_fields(fields: &syn::Fields) -> syn::Result<Vec<AbiField>> {
+    let mut names = HashSet::new();
+    fields
         .iter()
-        .filter(|f| f.attrs.first().is_none())
-        .map(create_param_type_token_stream);
-    let mut count = -1;
-    let field_list: &Vec<_> = &fields
+        .enumerate()
+        .map(|(index, field)| {
+            let attrs = FieldAttrs::from_field(field)?;
+            let name = attrs.rename.clone().unwrap_or_else(|| match &field.ident {
+                Some(ident) => to_camel_case(&ident.to_string()),
+                None => format!("field{}", index),
+            });
+            if attrs.skip {
+                return Ok(AbiField {
+                    name,
+                    attrs,
+                    kind: None,
+                });
+            }
+            if !names.insert(name.clone()) {
+                return Err(syn::Error::new(
+                    field.span(),
+                    format!("duplicate field name `{}`", name),
+                ));
+            }
+            let kind = classify(&field.ty, attrs.abi_type.as_ref())?;
+            Ok(AbiField {
+                name,
+                attrs,
+                kind: Some(kind),
+            })
+        })
+        .collect()
+}
+
+/// Converts `snake_case` field names to `camelCase` which Solidity uses
+fn to_camel_case(name: &str) -> String {
+    let mut camel_case = String::new();
+    let mut upper = false;
+    for c in name.trim_start_matches("r#").chars() {
+        if c == '_' {
+            upper = !camel_case.is_empty();
+        } else if upper {
+            camel_case.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            camel_case.push(c);
+        }
+    }
+    camel_case
+}
+
+/// Creates variable names to bind fields, `None` for skipped fields
+fn create_bindings(fields: &[AbiField]) -> Vec<Option<syn::Ident>> {
+    fields
         .iter()
-        .filter(|f| f.attrs.first().is_none())
-        .map(move |f| {
-            let ident = &f.ident.clone().unwrap();
-            if let syn::Type::Path(path) = &f.ty {
-                let type_name = &path.path.segments.first().unwrap().ident;
-                count += 1;
-                create_parse_val_token_stream(ident, type_name, count as usize)
-            } else {
-                panic!("invalid type")
+        .enumerate()
+        .map(|(i, f)| {
+            f.kind
+                .as_ref()
+                .map(|_| syn::Ident::new(&format!("field_{}", i), Span::call_site()))
+        })
+        .collect()
+}
+
+/// Creates a pattern which binds references of fields
+fn create_pattern(
+    path: TokenStream2,
+    fields: &syn::Fields,
+    bindings: &[Option<syn::Ident>],
+) -> TokenStream2 {
+    match fields {
+        syn::Fields::Named(named) => {
+            let field_patterns = named.named.iter().zip(bindings).filter_map(|(f, binding)| {
+                let ident = &f.ident;
+                binding.as_ref().map(|binding| quote! { #ident: #binding })
+            });
+            quote! { #path { #(#field_patterns,)* .. } }
+        }
+        syn::Fields::Unnamed(_) => {
+            let field_patterns = bindings.iter().map(|binding| match binding {
+                Some(binding) => quote! { #binding },
+                None => quote! { _ },
+            });
+            quote! { #path(#(#field_patterns),*) }
+        }
+        syn::Fields::Unit => quote! { #path },
+    }
+}
+
+fn create_token_list(fields: &[AbiField], bindings: &[Option<syn::Ident>]) -> Vec<TokenStream2> {
+    fields
+        .iter()
+        .zip(bindings)
+        .filter_map(|(f, binding)| match (&f.kind, binding) {
+            (Some(kind), Some(binding)) => Some(create_token(quote! { #binding }, kind)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn create_param_type_list(fields: &[AbiField]) -> Vec<TokenStream2> {
+    fields
+        .iter()
+        .filter_map(|f| f.kind.as_ref().map(create_param_type))
+        .collect()
+}
+
+/// Creates the expression which decodes `tuple` and builds the struct or the variant.
+/// Skipped fields are filled by their `default`.
+fn create_constructor(
+    path: TokenStream2,
+    syn_fields: &syn::Fields,
+    fields: &[AbiField],
+) -> TokenStream2 {
+    let length = fields.iter().filter(|f| f.kind.is_some()).count();
+    let mut index = 0;
+    let values: Vec<_> = fields
+        .iter()
+        .map(|f| match &f.kind {
+            Some(kind) => {
+                let value = create_parse_value(quote! { tuple[#index].clone() }, kind);
+                index += 1;
+                quote! { #value? }
             }
+            None => match &f.attrs.default {
+                Some(default) => quote! { #default },
+                None => quote! { Default::default() },
+            },
         })
         .collect();
-    let create_fields = fields.iter().map(|f| {
-        let is_none = f.attrs.first().is_none();
-        if is_none {
-            let ident = &f.ident.clone().unwrap();
-            return quote! { #ident };
-        } else {
-            return quote! {
-                None
-            };
+    let value = match syn_fields {
+        syn::Fields::Named(named) => {
+            let idents = named.named.iter().map(|f| &f.ident);
+            quote! { #path { #(#idents: #values),* } }
         }
-    });
-    let gen = quote! {
-        impl Decodable for #name {
-            type Ok = #name;
-            fn from_tuple(tuple: &[Token]) -> Result<Self, plasma_core::data_structure::error::Error> {
-                #(#field_list)*
-                Ok(#name::new(
-                    #(#create_fields),*
-                ))
+        syn::Fields::Unnamed(_) => quote! { #path(#(#values),*) },
+        syn::Fields::Unit => quote! { #path },
+    };
+    quote! {
+        plasma_core::data_structure::abi::check_length(tuple, #length)?;
+        Ok(#value)
+    }
+}
+
+/// How a Rust type is mapped to an ABI type.
+/// The size of uint is 256 unless it is changed by `#[abi(type = "...")]`.
+enum Kind<'a> {
+    Bytes,
+    ByteVec,
+    Integer(&'a syn::Type, usize),
+    U256(usize),
+    Uint(&'a syn::Type, usize),
+    H256,
+    Address,
+    Bool,
+    Boxed(Box<Kind<'a>>),
+    Array(Box<Kind<'a>>),
+    // Option is encoded as an array which has one element at most
+    Optional(Box<Kind<'a>>),
+    ByteArray(usize),
+    FixedArray(Box<Kind<'a>>, usize),
+    Tuple(&'a syn::Type),
+}
+
+/// Returns how `ty` is encoded. `abi_type` is applied to the innermost type of containers.
+fn classify<'a>(ty: &'a syn::Type, abi_type: Option<&AbiType>) -> syn::Result<Kind<'a>> {
+    let kind = match ty {
+        syn::Type::Array(array) => {
+            let length = get_array_length(array)?;
+            if is_u8(&array.elem) {
+                Kind::ByteArray(length)
+            } else {
+                let item = classify(&array.elem, abi_type)?;
+                return Ok(Kind::FixedArray(Box::new(item), length));
             }
-            fn get_param_types() -> Vec<ParamType> {
-                vec![#(#param_type_list),*]
+        }
+        syn::Type::Path(_) => {
+            let (name, argument) = split_type_path(ty).ok_or_else(|| unsupported_type_error(ty))?;
+            match (name.as_str(), argument) {
+                ("Bytes", _) => Kind::Bytes,
+                ("Integer", _) => Kind::Integer(ty, 256),
+                ("U256", _) => Kind::U256(256),
+                ("u8", _) | ("u16", _) | ("u32", _) | ("u64", _) => Kind::Uint(ty, 256),
+                ("H256", _) => Kind::H256,
+                ("Address", _) => Kind::Address,
+                ("bool", _) => Kind::Bool,
+                ("Vec", Some(inner)) if is_u8(inner) => Kind::ByteVec,
+                ("Box", Some(inner)) => {
+                    return Ok(Kind::Boxed(Box::new(classify(inner, abi_type)?)));
+                }
+                ("Vec", Some(inner)) => {
+                    return Ok(Kind::Array(Box::new(classify(inner, abi_type)?)));
+                }
+                ("Option", Some(inner)) => {
+                    return Ok(Kind::Optional(Box::new(classify(inner, abi_type)?)));
+                }
+                ("Box", None) | ("Vec", None) | ("Option", None) => {
+                    return Err(unsupported_type_error(ty));
+                }
+                _ => Kind::Tuple(ty),
             }
         }
+        _ => return Err(unsupported_type_error(ty)),
     };
-    gen.into()
+    match abi_type {
+        Some(abi_type) => apply_abi_type(kind, ty, abi_type),
+        None => Ok(kind),
+    }
 }
 
-fn create_tokens(f: &syn::Field) -> proc_macro2::TokenStream {
-    let ident = &f.ident.clone().unwrap();
-    if let syn::Type::Path(path) = &f.ty {
-        let type_name = &path.path.segments.first().unwrap().ident;
-        match &*type_name.to_string() {
-            "Bytes" => quote! {
-                Token::Bytes(self.#ident.to_vec())
-            },
-            "Integer" => quote! {
-                Token::Uint(self.#ident.0.into())
-            },
-            "H256" => quote! {
-                Token::FixedBytes(self.#ident.as_bytes().to_vec())
-            },
-            "Address" => quote! {
-                Token::Address(self.#ident)
-            },
-            _ => quote! {
-                Token::Tuple(self.#ident.to_tuple())
-            },
+/// Checks `abi_type` can represent every value of `kind`
+fn apply_abi_type<'a>(kind: Kind<'a>, ty: &syn::Type, abi_type: &AbiType) -> syn::Result<Kind<'a>> {
+    match (kind, abi_type) {
+        (Kind::Integer(path, _), AbiType::Uint(size)) if *size >= 64 => {
+            Ok(Kind::Integer(path, *size))
+        }
+        (Kind::U256(_), AbiType::Uint(256)) => Ok(Kind::U256(256)),
+        (Kind::Uint(path, _), AbiType::Uint(size)) if *size >= get_bit_width(path) => {
+            Ok(Kind::Uint(path, *size))
+        }
+        (Kind::Bytes, AbiType::Bytes) => Ok(Kind::Bytes),
+        (Kind::ByteVec, AbiType::Bytes) => Ok(Kind::ByteVec),
+        (Kind::H256, AbiType::FixedBytes(32)) => Ok(Kind::H256),
+        (Kind::ByteArray(length), AbiType::FixedBytes(size)) if length == *size => {
+            Ok(Kind::ByteArray(length))
         }
+        (Kind::Address, AbiType::Address) => Ok(Kind::Address),
+        (Kind::Bool, AbiType::Bool) => Ok(Kind::Bool),
+        _ => Err(syn::Error::new(
+            ty.span(),
+            format!("`{}` can't be encoded as `{}`", quote! { #ty }, abi_type),
+        )),
+    }
+}
+
+fn unsupported_type_error(ty: &syn::Type) -> syn::Error {
+    syn::Error::new(ty.span(), format!("unsupported type `{}`", quote! { #ty }))
+}
+
+/// Returns the last segment and the first generic argument of the type
+fn split_type_path(ty: &syn::Type) -> Option<(String, Option<&syn::Type>)> {
+    if let syn::Type::Path(type_path) = ty {
+        let segment = type_path.path.segments.last()?;
+        let argument = match &segment.arguments {
+            syn::PathArguments::AngleBracketed(arguments) => {
+                arguments.args.iter().find_map(|argument| match argument {
+                    syn::GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                })
+            }
+            _ => None,
+        };
+        Some((segment.ident.to_string(), argument))
     } else {
-        quote! {
-            Token::Tuple(self.#ident.to_tuple())
+        None
+    }
+}
+
+fn is_u8(ty: &syn::Type) -> bool {
+    split_type_path(ty).map_or(false, |(name, _)| name == "u8")
+}
+
+fn get_bit_width(ty: &syn::Type) -> usize {
+    match split_type_path(ty) {
+        Some((ref name, _)) if name == "u8" => 8,
+        Some((ref name, _)) if name == "u16" => 16,
+        Some((ref name, _)) if name == "u32" => 32,
+        _ => 64,
+    }
+}
+
+fn get_array_length(array: &syn::TypeArray) -> syn::Result<usize> {
+    if let syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Int(length),
+        ..
+    }) = &array.len
+    {
+        length.base10_parse()
+    } else {
+        Err(syn::Error::new(
+            array.len.span(),
+            "length of array must be a literal",
+        ))
+    }
+}
+
+/// Creates `Token` from `value` which is a reference of the field
+fn create_token(value: TokenStream2, kind: &Kind) -> TokenStream2 {
+    match kind {
+        Kind::Bytes | Kind::ByteVec => quote! { ethabi::Token::Bytes(#value.to_vec()) },
+        Kind::Integer(_, _) => quote! { ethabi::Token::Uint(#value.0.into()) },
+        Kind::U256(_) => quote! { ethabi::Token::Uint(*#value) },
+        Kind::Uint(_, _) => quote! { ethabi::Token::Uint((*#value).into()) },
+        Kind::H256 => quote! { ethabi::Token::FixedBytes(#value.as_bytes().to_vec()) },
+        Kind::Address => quote! { ethabi::Token::Address(*#value) },
+        Kind::Bool => quote! { ethabi::Token::Bool(*#value) },
+        Kind::Boxed(inner) => create_token(quote! { (&**#value) }, inner),
+        Kind::Array(inner) | Kind::Optional(inner) => {
+            let item = create_token(quote! { item }, inner);
+            quote! { ethabi::Token::Array(#value.iter().map(|item| #item).collect()) }
         }
+        Kind::ByteArray(_) => quote! { ethabi::Token::FixedBytes(#value.to_vec()) },
+        Kind::FixedArray(inner, _) => {
+            let item = create_token(quote! { item }, inner);
+            quote! { ethabi::Token::FixedArray(#value.iter().map(|item| #item).collect()) }
+        }
+        Kind::Tuple(_) => quote! {
+            ethabi::Token::Tuple(plasma_core::data_structure::abi::Encodable::to_tuple(#value))
+        },
     }
 }
 
-fn create_param_type_token_stream(f: &syn::Field) -> proc_macro2::TokenStream {
-    if let syn::Type::Path(path) = &f.ty {
-        let type_name = &path.path.segments.first().unwrap().ident;
-        match &*type_name.to_string() {
-            "Bytes" => quote! {
-                ethabi::ParamType::Bytes
-            },
-            "Integer" => quote! {
-                ethabi::ParamType::Uint(256)
-            },
-            "H256" => quote! {
-                ethabi::ParamType::FixedBytes(32)
-            },
-            "Address" => quote! {
-                ethabi::ParamType::Address
-            },
-            _ => quote! {
-                ethabi::ParamType::Tuple(#type_name::get_param_types())
-            },
+fn create_param_type(kind: &Kind) -> TokenStream2 {
+    match kind {
+        Kind::Bytes | Kind::ByteVec => quote! { ethabi::ParamType::Bytes },
+        Kind::Integer(_, size) | Kind::U256(size) | Kind::Uint(_, size) => {
+            quote! { ethabi::ParamType::Uint(#size) }
         }
-    } else {
-        quote! {
-            ethabi::ParamType::Address
+        Kind::H256 => quote! { ethabi::ParamType::FixedBytes(32) },
+        Kind::Address => quote! { ethabi::ParamType::Address },
+        Kind::Bool => quote! { ethabi::ParamType::Bool },
+        Kind::Boxed(inner) => create_param_type(inner),
+        Kind::Array(inner) | Kind::Optional(inner) => {
+            let item = create_param_type(inner);
+            quote! { ethabi::ParamType::Array(Box::new(#item)) }
         }
+        Kind::ByteArray(length) => quote! { ethabi::ParamType::FixedBytes(#length) },
+        Kind::FixedArray(inner, length) => {
+            let item = create_param_type(inner);
+            quote! { ethabi::ParamType::FixedArray(Box::new(#item), #length) }
+        }
+        Kind::Tuple(ty) => quote! {
+            ethabi::ParamType::Tuple(
+                <#ty as plasma_core::data_structure::abi::Decodable>::get_param_types()
+            )
+        },
     }
 }
 
-fn create_parse_val_token_stream(
-    ident: &syn::Ident,
-    type_name: &syn::Ident,
-    index: usize,
-) -> proc_macro2::TokenStream {
-    match &*type_name.to_string() {
-        "Bytes" => quote! {
-            let #ident = Bytes::from(tuple[#index].clone().to_bytes().unwrap());
+fn create_schema_type(kind: &Kind) -> TokenStream2 {
+    match kind {
+        Kind::Boxed(inner) => create_schema_type(inner),
+        Kind::Array(inner) | Kind::Optional(inner) => {
+            let item = create_schema_type(inner);
+            quote! { plasma_core::data_structure::schema::SchemaType::Array(Box::new(#item)) }
+        }
+        Kind::FixedArray(inner, length) => {
+            let item = create_schema_type(inner);
+            quote! {
+                plasma_core::data_structure::schema::SchemaType::FixedArray(Box::new(#item), #length)
+            }
+        }
+        Kind::Tuple(ty) => quote! {
+            plasma_core::data_structure::schema::SchemaType::Struct(
+                <#ty as plasma_core::data_structure::schema::AbiSchema>::get_schema()
+            )
+        },
+        _ => {
+            let param_type = create_param_type(kind);
+            quote! { plasma_core::data_structure::schema::SchemaType::Elementary(#param_type) }
+        }
+    }
+}
+
+/// Creates `Result<T, Error>` from `token`
+fn create_parse_value(token: TokenStream2, kind: &Kind) -> TokenStream2 {
+    let error = create_decode_error();
+    match kind {
+        Kind::Bytes => quote! {
+            (#token).to_bytes().map(bytes::Bytes::from).ok_or_else(|| #error)
         },
-        "Integer" => quote! {
-            let #ident: Integer = Integer(tuple[#index].clone().to_uint().unwrap().as_u64());
+        Kind::ByteVec => quote! { (#token).to_bytes().ok_or_else(|| #error) },
+        Kind::Integer(ty, _) => quote! {
+            plasma_core::data_structure::abi::to_u64(#token)
+                .map(#ty)
+                .ok_or_else(|| #error)
         },
-        "H256" => quote! {
-            let #ident: H256 = H256::from_slice(&tuple[#index].clone().to_fixed_bytes().unwrap().to_vec());
+        Kind::U256(_) => quote! { (#token).to_uint().ok_or_else(|| #error) },
+        Kind::Uint(ty, _) => quote! {
+            plasma_core::data_structure::abi::to_u64(#token)
+                .and_then(|n| <#ty as std::convert::TryFrom<u64>>::try_from(n).ok())
+                .ok_or_else(|| #error)
         },
-        "Address" => quote! {
-            let #ident: Address = tuple[#index].clone().to_address().unwrap();
+        Kind::H256 => quote! {
+            plasma_core::data_structure::abi::to_h256(#token).ok_or_else(|| #error)
         },
-        _ => quote! {
-            let #ident: #type_name = #type_name::from_tuple(&tuple[#index].clone().to_tuple().unwrap()).unwrap();
+        Kind::Address => quote! { (#token).to_address().ok_or_else(|| #error) },
+        Kind::Bool => quote! { (#token).to_bool().ok_or_else(|| #error) },
+        Kind::Boxed(inner) => {
+            let value = create_parse_value(token, inner);
+            quote! { #value.map(Box::new) }
+        }
+        Kind::Array(inner) => {
+            let item = create_parse_value(quote! { item }, inner);
+            quote! {
+                (#token)
+                    .to_array()
+                    .ok_or_else(|| #error)
+                    .and_then(|items| items.into_iter().map(|item| #item).collect::<Result<Vec<_>, _>>())
+            }
+        }
+        Kind::Optional(inner) => {
+            let item = create_parse_value(quote! { item }, inner);
+            quote! {
+                (#token)
+                    .to_array()
+                    .filter(|items| items.len() <= 1)
+                    .ok_or_else(|| #error)
+                    .and_then(|items| items.into_iter().next().map(|item| #item).transpose())
+            }
+        }
+        Kind::ByteArray(length) => quote! {
+            (#token)
+                .to_fixed_bytes()
+                .filter(|bytes| bytes.len() == #length)
+                .map(|bytes| {
+                    let mut array = [0u8; #length];
+                    array.copy_from_slice(&bytes);
+                    array
+                })
+                .ok_or_else(|| #error)
+        },
+        Kind::FixedArray(inner, length) => {
+            let items = (0..*length).map(|_| {
+                let item =
+                    create_parse_value(quote! { items.next().ok_or_else(|| #error)? }, inner);
+                quote! { #item? }
+            });
+            quote! {
+                (#token)
+                    .to_fixed_array()
+                    .filter(|items| items.len() == #length)
+                    .ok_or_else(|| #error)
+                    .and_then(|items| {
+                        let mut items = items.into_iter();
+                        Ok([#(#items),*])
+                    })
+            }
+        }
+        Kind::Tuple(ty) => quote! {
+            (#token)
+                .to_tuple()
+                .ok_or_else(|| #error)
+                .and_then(|tuple| <#ty as plasma_core::data_structure::abi::Decodable>::from_tuple(&tuple))
         },
     }
 }
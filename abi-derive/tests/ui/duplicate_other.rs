@@ -0,0 +1,11 @@
+use abi_derive::AbiEncodable;
+
+#[derive(AbiEncodable)]
+enum Message {
+    #[abi(other)]
+    Unknown(u64),
+    #[abi(other)]
+    Other(u64),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use abi_derive::AbiEncodable;
+
+#[derive(AbiEncodable)]
+struct Message {
+    #[abi(other)]
+    nonce: u64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use abi_derive::AbiEncodable;
+
+#[derive(AbiEncodable)]
+#[abi(tag = "UintTag")]
+struct Message {
+    nonce: u64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use abi_derive::AbiEncodable;
+
+#[derive(AbiEncodable)]
+struct Message {
+    #[abi(size = "8")]
+    nonce: u64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use abi_derive::AbiEncodable;
+
+#[derive(AbiEncodable)]
+struct Message {
+    #[abi(type = "address")]
+    nonce: u64,
+}
+
+fn main() {}
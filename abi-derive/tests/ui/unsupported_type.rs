@@ -0,0 +1,8 @@
+use abi_derive::AbiEncodable;
+
+#[derive(AbiEncodable)]
+struct Message {
+    callback: fn(),
+}
+
+fn main() {}
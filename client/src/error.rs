@@ -11,6 +11,7 @@ use hex::FromHexError;
 use jsonrpc_core_client::RpcError;
 use plasma_core::data_structure::error::Error as PlasmaCoreError;
 use plasma_db::error::Error as PlasmaDbError;
+use predicate_plugins::Error as PredicatePluginError;
 use std::fmt;
 use std::fmt::Display;
 use std::io::Error as IoError;
@@ -29,6 +30,8 @@ pub enum ErrorKind {
     PlasmaDbError,
     #[fail(display = "Invalid transaction")]
     InvalidTransaction,
+    #[fail(display = "Predicate plugin error")]
+    PredicatePlugin,
     #[fail(display = "Lock is acquired")]
     PoisonError,
     #[fail(display = "RPC error")]
@@ -114,6 +117,14 @@ impl From<PlasmaDbError> for Error {
     }
 }
 
+impl From<PredicatePluginError> for Error {
+    fn from(error: PredicatePluginError) -> Error {
+        Error {
+            inner: error.context(ErrorKind::PredicatePlugin),
+        }
+    }
+}
+
 impl<T> From<PoisonError<T>> for Error {
     fn from(_error: PoisonError<T>) -> Error {
         Error {
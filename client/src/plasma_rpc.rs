@@ -5,7 +5,7 @@ use crate::error::Error;
 use futures::Future;
 use jsonrpc_core_client::transports;
 use jsonrpc_core_client::{RpcChannel, RpcError, TypedClient};
-use plasma_core::data_structure::abi::Encodable;
+use plasma_core::data_structure::abi::{Decodable, Encodable};
 use plasma_core::data_structure::{StateQuery, StateQueryResult, Transaction};
 use std::cell::RefCell;
 
@@ -101,7 +101,7 @@ mod tests {
     use ethereum_types::{Address, H256};
     use jsonrpc_core::{Error, ErrorCode, IoHandler, Params, Value};
     use jsonrpc_http_server::*;
-    use plasma_core::data_structure::abi::Decodable;
+    use plasma_core::data_structure::abi::{Decodable, Encodable};
     use plasma_core::data_structure::{
         Range, StateObject, StateQuery, StateQueryResult, StateUpdate, Transaction, Witness,
     };
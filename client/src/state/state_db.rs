@@ -2,7 +2,7 @@ extern crate ethabi;
 
 use crate::error::{Error, ErrorKind};
 use ethabi::Token;
-use plasma_core::data_structure::abi::{Decodable, Encodable};
+use plasma_core::data_structure::abi::{to_u64, Decodable, Encodable};
 use plasma_core::data_structure::StateUpdate;
 use plasma_db::impls::rangedb::RangeDbImpl;
 use plasma_db::traits::{DatabaseTrait, KeyValueStore, RangeStore};
@@ -57,27 +57,27 @@ impl VerifiedStateUpdate {
     pub fn from_abi(data: &[u8]) -> Result<Self, Error> {
         let decoded: Vec<Token> = ethabi::decode(
             &[
-                ethabi::ParamType::Uint(8),
-                ethabi::ParamType::Uint(8),
-                ethabi::ParamType::Uint(8),
+                ethabi::ParamType::Uint(256),
+                ethabi::ParamType::Uint(256),
+                ethabi::ParamType::Uint(256),
                 ethabi::ParamType::Bytes,
             ],
             data,
         )
         .map_err(|_e| Error::from(ErrorKind::AbiDecode))?;
-        let block_number = decoded[0].clone().to_uint();
-        let start = decoded[1].clone().to_uint();
-        let end = decoded[2].clone().to_uint();
+        let start = to_u64(decoded[0].clone());
+        let end = to_u64(decoded[1].clone());
+        let block_number = to_u64(decoded[2].clone());
         let state_update = decoded[3].clone().to_bytes();
 
-        if let (Some(block_number), Some(start), Some(end), Some(state_update)) =
-            (block_number, start, end, state_update)
+        if let (Some(start), Some(end), Some(block_number), Some(state_update)) =
+            (start, end, block_number, state_update)
         {
             Ok(VerifiedStateUpdate::new(
-                block_number.as_u64(),
-                start.as_u64(),
-                end.as_u64(),
-                StateUpdate::from_abi(&state_update).unwrap(),
+                start,
+                end,
+                block_number,
+                StateUpdate::from_abi(&state_update).map_err::<Error, _>(Into::into)?,
             ))
         } else {
             Err(Error::from(ErrorKind::AbiDecode))
@@ -110,11 +110,20 @@ where
         start: u64,
         end: u64,
     ) -> Result<Box<[VerifiedStateUpdate]>, Error> {
+        self.iter_verified_state_updates(start, end)?.collect()
+    }
+    /// Reads all ranges between start and end up front, and decodes verified state updates
+    /// from them one by one, so that callers can stop without decoding the rest
+    pub fn iter_verified_state_updates(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> Result<impl Iterator<Item = Result<VerifiedStateUpdate, Error>>, Error> {
         let ranges = self.db.get(start, end).map_err::<Error, _>(Into::into)?;
-        ranges
-            .iter()
-            .map(|range| VerifiedStateUpdate::from_abi(range.get_value()))
-            .collect()
+        Ok(ranges
+            .into_vec()
+            .into_iter()
+            .map(|range| VerifiedStateUpdate::from_abi(range.get_value())))
     }
     pub fn put_verified_state_update(
         &self,
@@ -129,3 +138,45 @@ where
             .map_err::<Error, _>(Into::into)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::VerifiedStateUpdate;
+    use bytes::Bytes;
+    use ethereum_types::Address;
+    use plasma_core::data_structure::{Range, StateObject, StateUpdate};
+
+    #[test]
+    fn test_golden_vector() {
+        let state_update = StateUpdate::new(
+            StateObject::new(Address::zero(), Bytes::from(&b"parameters"[..])),
+            Range::new(0, 100),
+            1,
+            Address::zero(),
+        );
+        let verified_state_update = VerifiedStateUpdate::new(0, 100, 1, state_update);
+        let expected = concat!(
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000064",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            "0000000000000000000000000000000000000000000000000000000000000140",
+            "00000000000000000000000000000000000000000000000000000000000000a0",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000064",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "000000000000000000000000000000000000000000000000000000000000000a",
+            "706172616d657465727300000000000000000000000000000000000000000000",
+        );
+        assert_eq!(hex::encode(verified_state_update.to_abi()), expected);
+        assert_eq!(
+            VerifiedStateUpdate::from_abi(&hex::decode(expected).unwrap()).unwrap(),
+            verified_state_update
+        );
+    }
+
+}
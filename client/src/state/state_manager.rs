@@ -64,26 +64,30 @@ where
         ))
     }
 
-    /// Execute a transaction
+    /// Execute a transaction in the block `pending_block_number`
     pub fn execute_transaction(
         &self,
         transaction: &Transaction,
+        pending_block_number: u64,
     ) -> Result<ResultOfExecuteTransaction, Error> {
         let verified_state_updates = self.db.get_verified_state_updates(
             transaction.get_range().get_start(),
             transaction.get_range().get_end(),
         )?;
-        let new_state_updates: Vec<StateUpdate> = verified_state_updates
+        let new_state_updates = verified_state_updates
             .iter()
             .map(|verified_state_update| {
                 let predicate_address: Address = verified_state_update
                     .get_state_update()
                     .get_state_object()
                     .get_predicate();
-                PredicateManager::get_plugin(predicate_address)
-                    .execute_state_transition(verified_state_update.get_state_update(), transaction)
+                PredicateManager::get_plugin(predicate_address).execute_state_transition(
+                    verified_state_update.get_state_update(),
+                    transaction,
+                    pending_block_number,
+                )
             })
-            .collect();
+            .collect::<Result<Vec<StateUpdate>, _>>()?;
         // new_state_updates should has same state_update
         if new_state_updates.is_empty() {
             return Err(Error::from(ErrorKind::InvalidTransaction));
@@ -100,19 +104,41 @@ where
         ))
     }
 
+    /// Returns results of the query, stops decoding and filtering state updates once
+    /// `offset + limit` of them have matched the filter
     pub fn query_state(&self, query: &StateQuery) -> Result<Box<[StateQueryResult]>, Error> {
-        let verified_state_updates = self.db.get_verified_state_updates(
-            query.get_start().unwrap_or(0),
-            query.get_end().unwrap_or(0),
-        )?;
-        let state_query_result: Vec<StateQueryResult> = verified_state_updates
-            .iter()
-            .map(|verified_state_update| {
-                let result = PredicateManager::get_plugin(query.get_predicate_address())
-                    .query_state(verified_state_update.get_state_update(), query.get_params());
-                StateQueryResult::new(verified_state_update.get_state_update().clone(), &result)
-            })
-            .collect();
+        let range = query.get_range();
+        let limit = query
+            .get_limit()
+            .map_or(usize::max_value(), |limit| limit as usize);
+        let mut skipped = 0;
+        let mut state_query_result = vec![];
+        if limit == 0 {
+            return Ok(state_query_result.into_boxed_slice());
+        }
+        for verified_state_update in self
+            .db
+            .iter_verified_state_updates(range.get_start(), range.get_end())?
+        {
+            let state_update = verified_state_update?.get_state_update().clone();
+            if !query
+                .get_filter()
+                .as_ref()
+                .map_or(true, |filter| filter.matches(&state_update))
+            {
+                continue;
+            }
+            if skipped < query.get_offset() {
+                skipped += 1;
+                continue;
+            }
+            let result = PredicateManager::get_plugin(query.get_predicate_address())
+                .query_state(&state_update, query.get_params());
+            state_query_result.push(StateQueryResult::new(state_update, &result));
+            if state_query_result.len() >= limit {
+                break;
+            }
+        }
         Ok(state_query_result.into_boxed_slice())
     }
 }
@@ -123,9 +149,15 @@ mod tests {
     use super::StateQuery;
     use bytes::Bytes;
     use ethereum_types::{Address, H256};
-    use plasma_core::data_structure::{Range, StateObject, StateUpdate, Transaction, Witness};
+    use ovm::deciders::preimage_exists_decider::Verifier;
+    use plasma_core::data_structure::{
+        Range, StateFilter, StateObject, StateUpdate, Transaction, Witness,
+    };
     use plasma_db::impls::kvs::memory::CoreDbMemoryImpl;
-    use predicate_plugins::{OwnershipPredicateParameters, PredicateParameters};
+    use predicate_plugins::{
+        HashlockPredicateParameters, HashlockState, OwnershipPredicateParameters, PredicateManager,
+        PredicateParameters,
+    };
 
     fn create_state_update(start: u64, end: u64, block_number: u64) -> StateUpdate {
         StateUpdate::new(
@@ -157,7 +189,7 @@ mod tests {
         let state_manager: StateManager<CoreDbMemoryImpl> = Default::default();
         let deposit_result = state_manager.deposit(0, 100, state_update);
         assert!(deposit_result.is_ok());
-        let result = state_manager.execute_transaction(&transaction);
+        let result = state_manager.execute_transaction(&transaction, 10);
         assert!(result.is_ok());
     }
 
@@ -182,7 +214,7 @@ mod tests {
         let state_manager: StateManager<CoreDbMemoryImpl> = Default::default();
         let deposit_result = state_manager.deposit(0, 100, state_update);
         assert!(deposit_result.is_ok());
-        let result = state_manager.execute_transaction(&transaction);
+        let result = state_manager.execute_transaction(&transaction, 10);
         assert!(result.is_ok());
     }
 
@@ -208,10 +240,74 @@ mod tests {
         let state_manager: StateManager<CoreDbMemoryImpl> = Default::default();
         assert!(state_manager.deposit(0, 100, state_update1).is_ok());
         assert!(state_manager.deposit(100, 200, state_update2).is_ok());
-        let result = state_manager.execute_transaction(&transaction);
+        let result = state_manager.execute_transaction(&transaction, 10);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_execute_hashlock_transaction() {
+        let predicate_address = PredicateManager::get_hashlock_predicate_address();
+        let alice_address = Address::from_low_u64_be(2);
+        let bob_address = Address::from_low_u64_be(3);
+        let state = HashlockState::new(
+            alice_address,
+            bob_address,
+            Verifier::static_hash(&Bytes::from(&b"secret"[..])),
+            20,
+        );
+        let state_update = StateUpdate::new(
+            StateObject::new(predicate_address, state.encode()),
+            Range::new(0, 100),
+            1,
+            Address::zero(),
+        );
+        let create_transaction = |preimage: &[u8]| {
+            let parameters = HashlockPredicateParameters::new(
+                StateObject::new(Address::zero(), Bytes::from(bob_address.as_bytes())),
+                Bytes::from(preimage),
+                5,
+                30,
+            );
+            Transaction::new(
+                Address::zero(),
+                Range::new(0, 100),
+                parameters.encode(),
+                &Witness::new(H256::zero(), H256::zero(), 0),
+            )
+        };
+
+        let state_manager: StateManager<CoreDbMemoryImpl> = Default::default();
+        assert!(state_manager.deposit(0, 100, state_update).is_ok());
+        let query = StateQuery::new(
+            Address::zero(),
+            predicate_address,
+            Some(0),
+            Some(100),
+            Bytes::new(),
+        );
+        let result = state_manager.query_state(&query).unwrap();
+        assert_eq!(
+            result[0].get_result(),
+            &[
+                Bytes::from(bob_address.as_bytes()),
+                Bytes::from(alice_address.as_bytes())
+            ][..]
+        );
+        assert!(state_manager
+            .execute_transaction(&create_transaction(b"wrong"), 10)
+            .is_err());
+        assert!(state_manager
+            .execute_transaction(&create_transaction(b"secret"), 25)
+            .is_err());
+        let result = state_manager
+            .execute_transaction(&create_transaction(b"secret"), 10)
+            .unwrap();
+        assert_eq!(
+            result.get_state_update().get_state_object().get_data(),
+            &Bytes::from(bob_address.as_bytes())
+        );
+    }
+
     #[test]
     fn test_query_state() {
         // make state update
@@ -234,4 +330,36 @@ mod tests {
         assert_eq!(&result.ok().unwrap()[0].get_result()[0][..], &b"data"[..],);
     }
 
+    #[test]
+    fn test_query_state_without_range() {
+        let state_update1 = create_state_update(0, 100, 1);
+        let state_update2 = create_state_update(100, 200, 2);
+        let state_manager: StateManager<CoreDbMemoryImpl> = Default::default();
+        assert!(state_manager.deposit(0, 100, state_update1).is_ok());
+        assert!(state_manager.deposit(100, 200, state_update2).is_ok());
+        let query = StateQuery::new(Address::zero(), Address::zero(), None, None, Bytes::new());
+        let result = state_manager.query_state(&query).ok().unwrap();
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn test_query_state_with_filter_and_pagination() {
+        let state_manager: StateManager<CoreDbMemoryImpl> = Default::default();
+        for i in 0..4 {
+            let state_update = create_state_update(i * 100, (i + 1) * 100, i + 1);
+            assert!(state_manager
+                .deposit(i * 100, (i + 1) * 100, state_update)
+                .is_ok());
+        }
+        let query = StateQuery::new(Address::zero(), Address::zero(), None, None, Bytes::new())
+            .with_filter(StateFilter::BlockRange(Range::new(2, 10)))
+            .with_pagination(1, Some(1));
+        let result = state_manager.query_state(&query).ok().unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].get_state_update().get_block_number(), 3);
+        let query = StateQuery::new(Address::zero(), Address::zero(), None, None, Bytes::new())
+            .with_pagination(0, Some(0));
+        assert!(state_manager.query_state(&query).ok().unwrap().is_empty());
+    }
+
 }
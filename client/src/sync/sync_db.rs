@@ -1,7 +1,7 @@
 use crate::error::Error;
 use bytes::Bytes;
 use ethereum_types::Address;
-use plasma_core::data_structure::abi::{Decodable, Encodable};
+use plasma_core::data_structure::abi::{Decodable, Encodable, Hashable};
 use plasma_core::data_structure::StateQuery;
 use plasma_core::types::BlockNumber;
 use plasma_db::traits::{BaseDbKey, KeyValueStore};
@@ -89,7 +89,7 @@ where
         deposit_contract: Address,
         state_query: &StateQuery,
     ) -> Result<(), Error> {
-        let state_query_key: BaseDbKey = state_query.to_hash().into();
+        let state_query_key = BaseDbKey::from(state_query.hash().as_bytes());
         self.db
             .root()
             .bucket(&BaseDbKey::from(&b"sync_queries"[..]))
@@ -102,7 +102,7 @@ where
         deposit_contract: Address,
         state_query: &StateQuery,
     ) -> Result<(), Error> {
-        let state_query_key: BaseDbKey = state_query.to_hash().into();
+        let state_query_key = BaseDbKey::from(state_query.hash().as_bytes());
         self.db
             .root()
             .bucket(&BaseDbKey::from(&b"sync_queries"[..]))
@@ -1,12 +1,17 @@
 pub mod abi;
 pub mod error;
 pub mod range;
+pub mod schema;
+#[cfg(feature = "json")]
+pub mod serde_hex;
+pub mod state_filter;
 pub mod state_object;
 pub mod state_query;
 pub mod state_update;
 pub mod transaction;
 
 pub use self::range::Range;
+pub use self::state_filter::{Comparison, StateFilter};
 pub use self::state_object::StateObject;
 pub use self::state_query::{StateQuery, StateQueryResult};
 pub use self::state_update::StateUpdate;
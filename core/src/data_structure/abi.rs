@@ -2,6 +2,8 @@ extern crate ethabi;
 
 use super::error::{Error, ErrorKind};
 use ethabi::{ParamType, Token};
+use ethereum_types::{H256, U256};
+use tiny_keccak::Keccak;
 
 pub trait Encodable {
     fn to_abi(&self) -> Vec<u8> {
@@ -24,3 +26,144 @@ pub trait Decodable {
         vec![]
     }
 }
+
+/// Canonical hash which is keccak256 of the ABI encoding.
+/// DB keys of data structures are derived from it.
+pub trait Hashable {
+    fn hash(&self) -> H256;
+}
+
+impl<T: Encodable> Hashable for T {
+    fn hash(&self) -> H256 {
+        keccak256(&self.to_abi())
+    }
+}
+
+pub fn keccak256(data: &[u8]) -> H256 {
+    let mut hasher = Keccak::new_keccak256();
+    hasher.update(data);
+    let mut result: [u8; 32] = [0; 32];
+    hasher.finalize(&mut result);
+    H256::from(result)
+}
+
+/// Tag of enum variants which abi-derive encodes as `(tag, bytes)`.
+pub trait VariantTag {
+    fn get_param_type() -> ParamType;
+    /// Returns the tag of the variant at `index`
+    fn to_token(index: u64) -> Token;
+    /// Returns the index of the variant, `None` if the tag is unknown
+    fn from_token(token: Token) -> Option<u64>;
+}
+
+/// The default VariantTag which uses the index of the variant as uint.
+pub struct UintTag;
+
+impl VariantTag for UintTag {
+    fn get_param_type() -> ParamType {
+        ParamType::Uint(256)
+    }
+    fn to_token(index: u64) -> Token {
+        Token::Uint(index.into())
+    }
+    fn from_token(token: Token) -> Option<u64> {
+        to_u64(token)
+    }
+}
+
+/// Checks the number of tokens so that decoders never index out of bounds.
+pub fn check_length(tuple: &[Token], length: usize) -> Result<(), Error> {
+    if tuple.len() == length {
+        Ok(())
+    } else {
+        Err(Error::from(ErrorKind::AbiDecode))
+    }
+}
+
+/// Converts uint token to u64, returns `None` if the value overflows u64.
+pub fn to_u64(token: Token) -> Option<u64> {
+    token
+        .to_uint()
+        .filter(|n| *n <= U256::from(u64::max_value()))
+        .map(|n| n.as_u64())
+}
+
+/// Encodes an optional u64 as an array which has one element at most, like abi-derive does.
+pub fn from_optional_u64(value: Option<u64>) -> Token {
+    Token::Array(value.into_iter().map(|v| Token::Uint(v.into())).collect())
+}
+
+/// Converts an array token made by `from_optional_u64`, returns `None` if it's malformed.
+pub fn to_optional_u64(token: Token) -> Option<Option<u64>> {
+    match token.to_array() {
+        Some(ref items) if items.is_empty() => Some(None),
+        Some(ref items) if items.len() == 1 => to_u64(items[0].clone()).map(Some),
+        _ => None,
+    }
+}
+
+/// Converts fixed bytes token to H256, returns `None` if the length isn't 32.
+pub fn to_h256(token: Token) -> Option<H256> {
+    token
+        .to_fixed_bytes()
+        .filter(|b| b.len() == 32)
+        .map(|b| H256::from_slice(&b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_length, from_optional_u64, keccak256, to_h256, to_optional_u64, to_u64, Encodable,
+        Hashable,
+    };
+    use crate::data_structure::Range;
+    use ethabi::Token;
+    use ethereum_types::U256;
+
+    #[test]
+    fn test_check_length() {
+        let tuple = vec![Token::Uint(U256::zero())];
+        assert!(check_length(&tuple, 1).is_ok());
+        assert!(check_length(&tuple, 2).is_err());
+    }
+
+    #[test]
+    fn test_to_u64() {
+        assert_eq!(to_u64(Token::Uint(U256::from(10))), Some(10));
+        assert_eq!(to_u64(Token::Uint(U256::from(u64::max_value()) + 1)), None);
+        assert_eq!(to_u64(Token::Bool(true)), None);
+    }
+
+    #[test]
+    fn test_optional_u64() {
+        assert_eq!(to_optional_u64(from_optional_u64(None)), Some(None));
+        assert_eq!(to_optional_u64(from_optional_u64(Some(0))), Some(Some(0)));
+        assert_eq!(
+            to_optional_u64(Token::Array(vec![Token::Uint(U256::zero()); 2])),
+            None
+        );
+        assert_eq!(to_optional_u64(Token::Uint(U256::zero())), None);
+    }
+
+    #[test]
+    fn test_keccak256() {
+        assert_eq!(
+            hex::encode(keccak256(&[]).as_bytes()),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+    }
+
+    #[test]
+    fn test_hash() {
+        let range = Range::new(0, 100);
+        assert_eq!(range.hash(), keccak256(&range.to_abi()));
+        assert_ne!(range.hash(), Range::new(0, 101).hash());
+    }
+
+    #[test]
+    fn test_to_h256() {
+        assert!(to_h256(Token::FixedBytes(vec![0; 32])).is_some());
+        assert!(to_h256(Token::FixedBytes(vec![0; 31])).is_none());
+    }
+
+}
@@ -1,8 +1,11 @@
-use super::abi::{Decodable, Encodable};
+use super::abi::{check_length, to_u64, Decodable, Encodable};
 use super::error::{Error, ErrorKind};
+use super::schema::{AbiSchema, Schema, SchemaField, SchemaType};
 use ethabi::Token;
+use std::cmp::{max, min};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Range {
     start: u64,
     end: u64,
@@ -18,11 +21,12 @@ impl Range {
     pub fn get_end(&self) -> u64 {
         self.end
     }
+    /// Returns the intersection of two ranges, `Range(0, 0)` if they don't overlap
     pub fn get_overlapping_range(&self, b: &Range) -> Range {
-        if self.start < b.start && b.start <= self.end {
-            Range::new(b.start, self.end)
-        } else if b.start < self.start && self.start <= b.end {
-            Range::new(self.start, b.end)
+        let start = max(self.start, b.start);
+        let end = min(self.end, b.end);
+        if start < end {
+            Range::new(start, end)
         } else {
             Range::new(0, 0)
         }
@@ -38,15 +42,67 @@ impl Encodable for Range {
 impl Decodable for Range {
     type Ok = Self;
     fn from_tuple(tuple: &[Token]) -> Result<Self, Error> {
-        let start = tuple[0].clone().to_uint();
-        let end = tuple[1].clone().to_uint();
+        check_length(tuple, 2)?;
+        let start = to_u64(tuple[0].clone());
+        let end = to_u64(tuple[1].clone());
         if let (Some(start), Some(end)) = (start, end) {
-            Ok(Range::new(start.as_u64(), end.as_u64()))
+            Ok(Range::new(start, end))
         } else {
             Err(Error::from(ErrorKind::AbiDecode))
         }
     }
     fn get_param_types() -> Vec<ethabi::ParamType> {
-        vec![ethabi::ParamType::Uint(64), ethabi::ParamType::Uint(64)]
+        vec![ethabi::ParamType::Uint(256), ethabi::ParamType::Uint(256)]
     }
 }
+
+impl AbiSchema for Range {
+    fn get_schema() -> Schema {
+        Schema::new(
+            "Range",
+            vec![
+                SchemaField::new("start", SchemaType::uint()),
+                SchemaField::new("end", SchemaType::uint()),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Range;
+    use crate::data_structure::abi::{Decodable, Encodable};
+
+    #[test]
+    fn test_golden_vector() {
+        let range = Range::new(0, 100);
+        let expected = concat!(
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000064",
+        );
+        assert_eq!(hex::encode(range.to_abi()), expected);
+        assert_eq!(
+            Range::from_abi(&hex::decode(expected).unwrap()).unwrap(),
+            range
+        );
+    }
+
+    #[test]
+    fn test_get_overlapping_range() {
+        let range = Range::new(10, 100);
+        assert_eq!(range.get_overlapping_range(&range), range);
+        assert_eq!(
+            range.get_overlapping_range(&Range::new(0, 50)),
+            Range::new(10, 50)
+        );
+        assert_eq!(
+            range.get_overlapping_range(&Range::new(20, 30)),
+            Range::new(20, 30)
+        );
+        assert_eq!(
+            range.get_overlapping_range(&Range::new(100, 200)),
+            Range::new(0, 0)
+        );
+    }
+
+}
@@ -0,0 +1,296 @@
+use ethabi::ParamType;
+
+/// Describes the ABI of a type so that Solidity structs and ABI JSON can be generated from Rust.
+pub trait AbiSchema {
+    fn get_schema() -> Schema;
+}
+
+/// ABI type of a field
+#[derive(Clone, Debug, PartialEq)]
+pub enum SchemaType {
+    Elementary(ParamType),
+    Array(Box<SchemaType>),
+    FixedArray(Box<SchemaType>, usize),
+    Struct(Schema),
+}
+
+impl SchemaType {
+    pub fn uint() -> Self {
+        SchemaType::Elementary(ParamType::Uint(256))
+    }
+    pub fn bytes() -> Self {
+        SchemaType::Elementary(ParamType::Bytes)
+    }
+    pub fn address() -> Self {
+        SchemaType::Elementary(ParamType::Address)
+    }
+
+    /// Returns the type in Solidity, e.g. `uint256`, `address[]` or `Range`
+    pub fn to_solidity_type(&self) -> String {
+        match self {
+            SchemaType::Elementary(param_type) => get_elementary_name(param_type),
+            SchemaType::Array(item) => format!("{}[]", item.to_solidity_type()),
+            SchemaType::FixedArray(item, length) => {
+                format!("{}[{}]", item.to_solidity_type(), length)
+            }
+            SchemaType::Struct(schema) => schema.name.clone(),
+        }
+    }
+
+    /// Returns the type in ABI JSON, structs are `tuple`
+    pub fn to_abi_type(&self) -> String {
+        match self {
+            SchemaType::Elementary(param_type) => get_elementary_name(param_type),
+            SchemaType::Array(item) => format!("{}[]", item.to_abi_type()),
+            SchemaType::FixedArray(item, length) => format!("{}[{}]", item.to_abi_type(), length),
+            SchemaType::Struct(_) => "tuple".to_string(),
+        }
+    }
+
+    pub fn to_param_type(&self) -> ParamType {
+        match self {
+            SchemaType::Elementary(param_type) => param_type.clone(),
+            SchemaType::Array(item) => ParamType::Array(Box::new(item.to_param_type())),
+            SchemaType::FixedArray(item, length) => {
+                ParamType::FixedArray(Box::new(item.to_param_type()), *length)
+            }
+            SchemaType::Struct(schema) => ParamType::Tuple(schema.get_param_types()),
+        }
+    }
+
+    /// Returns the struct of this type or of the items of arrays
+    pub fn get_struct(&self) -> Option<&Schema> {
+        match self {
+            SchemaType::Elementary(_) => None,
+            SchemaType::Array(item) | SchemaType::FixedArray(item, _) => item.get_struct(),
+            SchemaType::Struct(schema) => Some(schema),
+        }
+    }
+}
+
+fn get_elementary_name(param_type: &ParamType) -> String {
+    match param_type {
+        ParamType::Address => "address".to_string(),
+        ParamType::Bytes => "bytes".to_string(),
+        ParamType::Int(size) => format!("int{}", size),
+        ParamType::Uint(size) => format!("uint{}", size),
+        ParamType::Bool => "bool".to_string(),
+        ParamType::String => "string".to_string(),
+        ParamType::Array(item) => format!("{}[]", get_elementary_name(item)),
+        ParamType::FixedBytes(size) => format!("bytes{}", size),
+        ParamType::FixedArray(item, length) => format!("{}[{}]", get_elementary_name(item), length),
+        ParamType::Tuple(_) => "tuple".to_string(),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaField {
+    pub name: String,
+    pub schema_type: SchemaType,
+}
+
+impl SchemaField {
+    pub fn new(name: &str, schema_type: SchemaType) -> Self {
+        SchemaField {
+            name: name.to_string(),
+            schema_type,
+        }
+    }
+}
+
+/// A struct in Solidity, which is a tuple in ABI
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schema {
+    pub name: String,
+    pub fields: Vec<SchemaField>,
+}
+
+impl Schema {
+    pub fn new(name: &str, fields: Vec<SchemaField>) -> Self {
+        Schema {
+            name: name.to_string(),
+            fields,
+        }
+    }
+
+    /// Returns param types which must be the same as `Decodable::get_param_types`
+    pub fn get_param_types(&self) -> Vec<ParamType> {
+        self.fields
+            .iter()
+            .map(|field| field.schema_type.to_param_type())
+            .collect()
+    }
+
+    /// Returns the Solidity struct declaration of this struct only
+    pub fn to_solidity_struct(&self) -> String {
+        let fields: String = self
+            .fields
+            .iter()
+            .map(|field| {
+                format!(
+                    "    {} {};\n",
+                    field.schema_type.to_solidity_type(),
+                    field.name
+                )
+            })
+            .collect();
+        format!("struct {} {{\n{}}}\n", self.name, fields)
+    }
+
+    /// Collects this struct and nested structs, nested structs come first
+    fn collect_structs<'a>(&'a self, structs: &mut Vec<&'a Schema>) {
+        if structs.iter().any(|s| s.name == self.name) {
+            return;
+        }
+        for field in self.fields.iter() {
+            if let Some(schema) = field.schema_type.get_struct() {
+                schema.collect_structs(structs);
+            }
+        }
+        structs.push(self);
+    }
+}
+
+/// Returns Solidity struct declarations of schemas and their nested structs.
+/// Each struct is declared once even if it is used by several schemas.
+pub fn to_solidity(schemas: &[Schema]) -> String {
+    let mut structs = vec![];
+    for schema in schemas.iter() {
+        schema.collect_structs(&mut structs);
+    }
+    structs
+        .iter()
+        .map(|schema| schema.to_solidity_struct())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns ABI JSON which describes each schema as a tuple parameter.
+pub fn to_json(schemas: &[Schema]) -> String {
+    let params = schemas
+        .iter()
+        .map(|schema| {
+            let mut param = String::new();
+            write_json_param(&mut param, "", &SchemaType::Struct(schema.clone()), 1);
+            param
+        })
+        .collect::<Vec<_>>();
+    format!("[\n{}\n]\n", params.join(",\n"))
+}
+
+fn write_json_param(out: &mut String, name: &str, schema_type: &SchemaType, indent: usize) {
+    let pad = "  ".repeat(indent);
+    out.push_str(&format!("{}{{\n", pad));
+    out.push_str(&format!("{}  \"name\": \"{}\",\n", pad, name));
+    out.push_str(&format!(
+        "{}  \"type\": \"{}\",\n",
+        pad,
+        schema_type.to_abi_type()
+    ));
+    if let Some(schema) = schema_type.get_struct() {
+        out.push_str(&format!(
+            "{}  \"internalType\": \"struct {}\",\n",
+            pad,
+            schema_type.to_solidity_type()
+        ));
+        out.push_str(&format!("{}  \"components\": [\n", pad));
+        for (i, field) in schema.fields.iter().enumerate() {
+            if i > 0 {
+                out.push_str(",\n");
+            }
+            write_json_param(out, &field.name, &field.schema_type, indent + 2);
+        }
+        out.push_str(&format!("\n{}  ]\n", pad));
+    } else {
+        out.push_str(&format!(
+            "{}  \"internalType\": \"{}\"\n",
+            pad,
+            schema_type.to_solidity_type()
+        ));
+    }
+    out.push_str(&format!("{}}}", pad));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_json, to_solidity, AbiSchema};
+    use crate::data_structure::abi::Decodable;
+    use crate::data_structure::{
+        Range, StateFilter, StateObject, StateQuery, StateQueryResult, StateUpdate, Transaction,
+    };
+
+    fn assert_schema<T: AbiSchema + Decodable>() {
+        assert_eq!(T::get_schema().get_param_types(), T::get_param_types());
+    }
+
+    #[test]
+    fn test_schemas_match_param_types() {
+        assert_schema::<Range>();
+        assert_schema::<StateObject>();
+        assert_schema::<StateUpdate>();
+        assert_schema::<Transaction>();
+        assert_schema::<StateQuery>();
+        assert_schema::<StateQueryResult>();
+        assert_schema::<StateFilter>();
+    }
+
+    #[test]
+    fn test_to_solidity() {
+        let solidity = to_solidity(&[StateUpdate::get_schema(), Transaction::get_schema()]);
+        assert_eq!(
+            solidity,
+            concat!(
+                "struct Range {\n",
+                "    uint256 start;\n",
+                "    uint256 end;\n",
+                "}\n",
+                "\n",
+                "struct StateUpdate {\n",
+                "    bytes stateObject;\n",
+                "    Range range;\n",
+                "    uint256 blockNumber;\n",
+                "    address plasmaContract;\n",
+                "}\n",
+                "\n",
+                "struct Transaction {\n",
+                "    address plasmaContractAddress;\n",
+                "    Range range;\n",
+                "    bytes parameters;\n",
+                "    bytes32 v;\n",
+                "    bytes32 r;\n",
+                "    uint256 s;\n",
+                "}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let json = to_json(&[Range::get_schema()]);
+        assert_eq!(
+            json,
+            concat!(
+                "[\n",
+                "  {\n",
+                "    \"name\": \"\",\n",
+                "    \"type\": \"tuple\",\n",
+                "    \"internalType\": \"struct Range\",\n",
+                "    \"components\": [\n",
+                "      {\n",
+                "        \"name\": \"start\",\n",
+                "        \"type\": \"uint256\",\n",
+                "        \"internalType\": \"uint256\"\n",
+                "      },\n",
+                "      {\n",
+                "        \"name\": \"end\",\n",
+                "        \"type\": \"uint256\",\n",
+                "        \"internalType\": \"uint256\"\n",
+                "      }\n",
+                "    ]\n",
+                "  }\n",
+                "]\n",
+            )
+        );
+    }
+
+}
@@ -0,0 +1,40 @@
+//! Serializers for `#[serde(with = "...")]` which represent bytes as 0x-prefixed hex strings.
+
+use bytes::Bytes;
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serializer};
+
+fn encode(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn decode<E: DeError>(s: &str) -> Result<Bytes, E> {
+    hex::decode(s.trim_start_matches("0x"))
+        .map(Bytes::from)
+        .map_err(E::custom)
+}
+
+pub fn serialize<S: Serializer>(bytes: &Bytes, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&encode(bytes))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Bytes, D::Error> {
+    decode(&String::deserialize(deserializer)?)
+}
+
+/// For the list of bytes
+pub mod vec {
+    use bytes::Bytes;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(list: &[Bytes], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(list.iter().map(|bytes| super::encode(bytes)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Bytes>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|s| super::decode(s))
+            .collect()
+    }
+}
@@ -0,0 +1,342 @@
+use super::abi::{check_length, to_u64, Decodable, Encodable};
+use super::error::{Error, ErrorKind};
+use super::schema::{AbiSchema, Schema, SchemaField, SchemaType};
+use super::{Range, StateUpdate};
+use ethabi::{ParamType, Token};
+use ethereum_types::{Address, U256};
+
+/// Nesting depth of `And`, `Or` and `Not` which the decoder accepts
+const MAX_DEPTH: usize = 32;
+
+/// Comparison operator used by `StateFilter::DataField`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+}
+
+impl Comparison {
+    pub fn get_id(self) -> u64 {
+        match self {
+            Comparison::Equal => 0,
+            Comparison::NotEqual => 1,
+            Comparison::LessThan => 2,
+            Comparison::LessThanOrEqual => 3,
+            Comparison::GreaterThan => 4,
+            Comparison::GreaterThanOrEqual => 5,
+        }
+    }
+    fn from_id(id: u64) -> Result<Self, Error> {
+        match id {
+            0 => Ok(Comparison::Equal),
+            1 => Ok(Comparison::NotEqual),
+            2 => Ok(Comparison::LessThan),
+            3 => Ok(Comparison::LessThanOrEqual),
+            4 => Ok(Comparison::GreaterThan),
+            5 => Ok(Comparison::GreaterThanOrEqual),
+            _ => Err(Error::from(ErrorKind::AbiDecode)),
+        }
+    }
+    pub fn compare(self, left: U256, right: U256) -> bool {
+        match self {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::LessThan => left < right,
+            Comparison::LessThanOrEqual => left <= right,
+            Comparison::GreaterThan => left > right,
+            Comparison::GreaterThanOrEqual => left >= right,
+        }
+    }
+}
+
+/// StateFilter is a filter expression which the operator evaluates against StateUpdates.
+/// It is sent in StateQuery so that clients receive only matching results.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum StateFilter {
+    /// data of the state object equals to the owner address.
+    /// It matches only state objects of the ownership predicate, whose data is the owner.
+    OwnerEquals(Address),
+    /// block number of the state update is in the range
+    BlockRange(Range),
+    /// predicate of the state object is one of the addresses
+    PredicateIn(Vec<Address>),
+    /// compares 32 bytes word at the index of state object data with the value
+    DataField(u64, Comparison, U256),
+    And(Vec<StateFilter>),
+    Or(Vec<StateFilter>),
+    Not(Box<StateFilter>),
+}
+
+impl StateFilter {
+    /// Evaluates the filter for a StateUpdate
+    pub fn matches(&self, state_update: &StateUpdate) -> bool {
+        let state_object = state_update.get_state_object();
+        match self {
+            StateFilter::OwnerEquals(owner) => state_object.get_data()[..] == owner.as_bytes()[..],
+            StateFilter::BlockRange(range) => {
+                range.get_start() <= state_update.get_block_number()
+                    && state_update.get_block_number() < range.get_end()
+            }
+            StateFilter::PredicateIn(predicates) => {
+                predicates.contains(&state_object.get_predicate())
+            }
+            StateFilter::DataField(index, comparison, value) => {
+                let data = state_object.get_data();
+                if *index >= (data.len() / 32) as u64 {
+                    return false;
+                }
+                let offset = *index as usize * 32;
+                comparison.compare(U256::from_big_endian(&data[offset..offset + 32]), *value)
+            }
+            StateFilter::And(filters) => filters.iter().all(|f| f.matches(state_update)),
+            StateFilter::Or(filters) => filters.iter().any(|f| f.matches(state_update)),
+            StateFilter::Not(filter) => !filter.matches(state_update),
+        }
+    }
+    pub fn get_id(&self) -> u64 {
+        match self {
+            StateFilter::OwnerEquals(_) => 0,
+            StateFilter::BlockRange(_) => 1,
+            StateFilter::PredicateIn(_) => 2,
+            StateFilter::DataField(_, _, _) => 3,
+            StateFilter::And(_) => 4,
+            StateFilter::Or(_) => 5,
+            StateFilter::Not(_) => 6,
+        }
+    }
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            StateFilter::OwnerEquals(owner) => ethabi::encode(&[Token::Address(*owner)]),
+            StateFilter::BlockRange(range) => range.to_abi(),
+            StateFilter::PredicateIn(predicates) => ethabi::encode(&[Token::Array(
+                predicates.iter().map(|p| Token::Address(*p)).collect(),
+            )]),
+            StateFilter::DataField(index, comparison, value) => ethabi::encode(&[
+                Token::Uint((*index).into()),
+                Token::Uint(comparison.get_id().into()),
+                Token::Uint(*value),
+            ]),
+            StateFilter::And(filters) | StateFilter::Or(filters) => {
+                ethabi::encode(&[Token::Array(
+                    filters.iter().map(|f| Token::Bytes(f.to_abi())).collect(),
+                )])
+            }
+            StateFilter::Not(filter) => ethabi::encode(&[Token::Bytes(filter.to_abi())]),
+        }
+    }
+    fn decode_nested(data: &[u8], depth: usize) -> Result<Self, Error> {
+        if depth >= MAX_DEPTH {
+            return Err(Error::from(ErrorKind::AbiDecode));
+        }
+        let decoded = ethabi::decode(&Self::get_param_types(), data)?;
+        Self::from_tuple_at(&decoded, depth)
+    }
+    fn from_tuple_at(tuple: &[Token], depth: usize) -> Result<Self, Error> {
+        check_length(tuple, 2)?;
+        let id = to_u64(tuple[0].clone());
+        let data = tuple[1].clone().to_bytes();
+        if let (Some(id), Some(data)) = (id, data) {
+            StateFilter::from_bytes(id, &data, depth)
+        } else {
+            Err(Error::from(ErrorKind::AbiDecode))
+        }
+    }
+    fn decode_filters(data: &[u8], depth: usize) -> Result<Vec<StateFilter>, Error> {
+        let decoded = ethabi::decode(&[ParamType::Array(Box::new(ParamType::Bytes))], data)?;
+        let filters = decoded[0]
+            .clone()
+            .to_array()
+            .ok_or_else(|| Error::from(ErrorKind::AbiDecode))?;
+        filters
+            .into_iter()
+            .map(|f| {
+                f.to_bytes()
+                    .ok_or_else(|| Error::from(ErrorKind::AbiDecode))
+                    .and_then(|b| StateFilter::decode_nested(&b, depth + 1))
+            })
+            .collect()
+    }
+    fn from_bytes(id: u64, data: &[u8], depth: usize) -> Result<Self, Error> {
+        match id {
+            0 => {
+                let decoded = ethabi::decode(&[ParamType::Address], data)?;
+                decoded[0]
+                    .clone()
+                    .to_address()
+                    .map(StateFilter::OwnerEquals)
+                    .ok_or_else(|| Error::from(ErrorKind::AbiDecode))
+            }
+            1 => Range::from_abi(data).map(StateFilter::BlockRange),
+            2 => {
+                let decoded =
+                    ethabi::decode(&[ParamType::Array(Box::new(ParamType::Address))], data)?;
+                decoded[0]
+                    .clone()
+                    .to_array()
+                    .and_then(|predicates| {
+                        predicates
+                            .into_iter()
+                            .map(|p| p.to_address())
+                            .collect::<Option<Vec<Address>>>()
+                    })
+                    .map(StateFilter::PredicateIn)
+                    .ok_or_else(|| Error::from(ErrorKind::AbiDecode))
+            }
+            3 => {
+                let decoded = ethabi::decode(
+                    &[
+                        ParamType::Uint(256),
+                        ParamType::Uint(256),
+                        ParamType::Uint(256),
+                    ],
+                    data,
+                )?;
+                let index = to_u64(decoded[0].clone());
+                let comparison = to_u64(decoded[1].clone());
+                let value = decoded[2].clone().to_uint();
+                if let (Some(index), Some(comparison), Some(value)) = (index, comparison, value) {
+                    Ok(StateFilter::DataField(
+                        index,
+                        Comparison::from_id(comparison)?,
+                        value,
+                    ))
+                } else {
+                    Err(Error::from(ErrorKind::AbiDecode))
+                }
+            }
+            4 => Self::decode_filters(data, depth).map(StateFilter::And),
+            5 => Self::decode_filters(data, depth).map(StateFilter::Or),
+            6 => {
+                let decoded = ethabi::decode(&[ParamType::Bytes], data)?;
+                let filter = decoded[0]
+                    .clone()
+                    .to_bytes()
+                    .ok_or_else(|| Error::from(ErrorKind::AbiDecode))?;
+                StateFilter::decode_nested(&filter, depth + 1)
+                    .map(|f| StateFilter::Not(Box::new(f)))
+            }
+            _ => Err(Error::from(ErrorKind::AbiDecode)),
+        }
+    }
+}
+
+impl Encodable for StateFilter {
+    fn to_tuple(&self) -> Vec<Token> {
+        vec![
+            Token::Uint(self.get_id().into()),
+            Token::Bytes(self.to_bytes()),
+        ]
+    }
+}
+
+impl Decodable for StateFilter {
+    type Ok = Self;
+    fn from_tuple(tuple: &[Token]) -> Result<Self, Error> {
+        Self::from_tuple_at(tuple, 0)
+    }
+    fn get_param_types() -> Vec<ParamType> {
+        vec![ParamType::Uint(256), ParamType::Bytes]
+    }
+}
+
+impl AbiSchema for StateFilter {
+    fn get_schema() -> Schema {
+        Schema::new(
+            "StateFilter",
+            vec![
+                SchemaField::new("tag", SchemaType::uint()),
+                SchemaField::new("data", SchemaType::bytes()),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Comparison, StateFilter};
+    use crate::data_structure::abi::{Decodable, Encodable};
+    use crate::data_structure::{Range, StateObject, StateUpdate};
+    use bytes::Bytes;
+    use ethereum_types::{Address, U256};
+
+    fn create_state_update(predicate: Address, data: Bytes, block_number: u64) -> StateUpdate {
+        StateUpdate::new(
+            StateObject::new(predicate, data),
+            Range::new(0, 100),
+            block_number,
+            Address::zero(),
+        )
+    }
+
+    #[test]
+    fn test_encode_and_decode() {
+        let filter = StateFilter::And(vec![
+            StateFilter::OwnerEquals(Address::zero()),
+            StateFilter::Or(vec![
+                StateFilter::BlockRange(Range::new(5, 10)),
+                StateFilter::PredicateIn(vec![Address::zero(), Address::random()]),
+            ]),
+            StateFilter::Not(Box::new(StateFilter::DataField(
+                1,
+                Comparison::GreaterThan,
+                U256::from(100),
+            ))),
+        ]);
+        let decoded = StateFilter::from_abi(&filter.to_abi()).unwrap();
+        assert_eq!(decoded, filter);
+    }
+
+    #[test]
+    fn test_decode_too_deep_filter() {
+        let nest = |depth: usize| {
+            (0..depth).fold(StateFilter::PredicateIn(vec![]), |f, _| {
+                StateFilter::Not(Box::new(f))
+            })
+        };
+        let filter = nest(10);
+        assert_eq!(StateFilter::from_abi(&filter.to_abi()).unwrap(), filter);
+        assert!(StateFilter::from_abi(&nest(100).to_abi()).is_err());
+        let filter = StateFilter::And(vec![nest(100)]);
+        assert!(StateFilter::from_abi(&filter.to_abi()).is_err());
+    }
+
+    #[test]
+    fn test_matches() {
+        let owner = Address::random();
+        let state_update = create_state_update(Address::zero(), Bytes::from(owner.as_bytes()), 8);
+        assert!(StateFilter::OwnerEquals(owner).matches(&state_update));
+        assert!(!StateFilter::OwnerEquals(Address::zero()).matches(&state_update));
+        assert!(StateFilter::BlockRange(Range::new(5, 10)).matches(&state_update));
+        assert!(!StateFilter::BlockRange(Range::new(0, 8)).matches(&state_update));
+        assert!(StateFilter::PredicateIn(vec![Address::zero()]).matches(&state_update));
+        assert!(StateFilter::Not(Box::new(StateFilter::PredicateIn(vec![]))).matches(&state_update));
+    }
+
+    #[test]
+    fn test_matches_data_field() {
+        let data = ethabi::encode(&[
+            ethabi::Token::Address(Address::zero()),
+            ethabi::Token::Uint(U256::from(100)),
+        ]);
+        let state_update = create_state_update(Address::zero(), Bytes::from(data), 1);
+        assert!(
+            StateFilter::DataField(1, Comparison::Equal, U256::from(100)).matches(&state_update)
+        );
+        assert!(
+            StateFilter::DataField(1, Comparison::LessThan, U256::from(101)).matches(&state_update)
+        );
+        assert!(
+            !StateFilter::DataField(1, Comparison::GreaterThan, U256::from(100))
+                .matches(&state_update)
+        );
+        // out of range field never matches
+        assert!(!StateFilter::DataField(2, Comparison::Equal, U256::zero()).matches(&state_update));
+    }
+
+}
@@ -1,16 +1,19 @@
 extern crate ethabi;
 
-use super::abi::{Decodable, Encodable};
+use super::abi::{check_length, Decodable, Encodable};
 use super::error::{Error, ErrorKind};
+use super::schema::{AbiSchema, Schema, SchemaField, SchemaType};
 use bytes::Bytes;
-use ethabi::Token;
+use ethabi::{ParamType, Token};
 use ethereum_types::Address;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 /// StateObject represents state of assets
 /// See http://spec.plasma.group/en/latest/src/01-core/state-system.html#state-objects
 pub struct StateObject {
     predicate: Address,
+    #[cfg_attr(feature = "json", serde(with = "super::serde_hex"))]
     data: Bytes,
 }
 
@@ -27,9 +30,6 @@ impl StateObject {
 }
 
 impl Encodable for StateObject {
-    fn to_abi(&self) -> Vec<u8> {
-        ethabi::encode(&self.to_tuple())
-    }
     fn to_tuple(&self) -> Vec<Token> {
         vec![
             Token::Address(self.predicate),
@@ -41,6 +41,7 @@ impl Encodable for StateObject {
 impl Decodable for StateObject {
     type Ok = Self;
     fn from_tuple(tuple: &[Token]) -> Result<Self, Error> {
+        check_length(tuple, 2)?;
         let predicate = tuple[0].clone().to_address();
         let data = tuple[1].clone().to_bytes();
         if let (Some(predicate), Some(data)) = (predicate, data) {
@@ -49,13 +50,20 @@ impl Decodable for StateObject {
             Err(Error::from(ErrorKind::AbiDecode))
         }
     }
-    fn from_abi(data: &[u8]) -> Result<Self, Error> {
-        let decoded: Vec<Token> = ethabi::decode(
-            &[ethabi::ParamType::Address, ethabi::ParamType::Bytes],
-            data,
+    fn get_param_types() -> Vec<ParamType> {
+        vec![ParamType::Address, ParamType::Bytes]
+    }
+}
+
+impl AbiSchema for StateObject {
+    fn get_schema() -> Schema {
+        Schema::new(
+            "StateObject",
+            vec![
+                SchemaField::new("predicate", SchemaType::address()),
+                SchemaField::new("data", SchemaType::bytes()),
+            ],
         )
-        .map_err(|_e| Error::from(ErrorKind::AbiDecode))?;
-        Self::from_tuple(&decoded)
     }
 }
 
@@ -75,4 +83,20 @@ mod tests {
         assert_eq!(decoded.predicate, state_object.predicate);
     }
 
+    #[test]
+    fn test_golden_vector() {
+        let state_object = StateObject::new(Address::zero(), Bytes::from(&b"parameters"[..]));
+        let expected = concat!(
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "000000000000000000000000000000000000000000000000000000000000000a",
+            "706172616d657465727300000000000000000000000000000000000000000000",
+        );
+        assert_eq!(hex::encode(state_object.to_abi()), expected);
+        assert_eq!(
+            StateObject::from_abi(&hex::decode(expected).unwrap()).unwrap(),
+            state_object
+        );
+    }
+
 }
@@ -1,20 +1,32 @@
-use super::abi::{Decodable, Encodable};
+use super::abi::{check_length, from_optional_u64, to_optional_u64, to_u64, Decodable, Encodable};
 use super::error::{Error, ErrorKind};
-use super::StateUpdate;
+use super::schema::{AbiSchema, Schema, SchemaField, SchemaType};
+use super::{Range, StateFilter, StateUpdate};
 use bytes::Bytes;
-use ethabi::Token;
+use ethabi::{ParamType, Token};
 use ethereum_types::Address;
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
 
-/// StateQuery is the query to get StateUpdates
-#[derive(Debug, Clone, Hash)]
+/// Lower bound of the range when a StateQuery has no start
+const UNBOUNDED_START: u64 = 0;
+/// Upper bound of the range when a StateQuery has no end
+const UNBOUNDED_END: u64 = 0xffff_ffff_ffff_ffff;
+
+/// StateQuery is the query to get StateUpdates.
+/// `start` and `end` are optional, `None` means the query isn't bounded on that side.
+/// `filter` is evaluated by the operator and results are paginated by `offset` and `limit`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 pub struct StateQuery {
     plasma_contract: Address,
     predicate_address: Address,
     start: Option<u64>,
     end: Option<u64>,
+    #[cfg_attr(feature = "json", serde(with = "super::serde_hex"))]
     params: Bytes,
+    filter: Option<StateFilter>,
+    offset: u64,
+    limit: Option<u64>,
 }
 
 impl StateQuery {
@@ -31,8 +43,22 @@ impl StateQuery {
             start,
             end,
             params,
+            filter: None,
+            offset: 0,
+            limit: None,
         }
     }
+    /// Sets filter expression which is evaluated by the operator
+    pub fn with_filter(mut self, filter: StateFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+    /// Skips `offset` matched results and returns `limit` results at most
+    pub fn with_pagination(mut self, offset: u64, limit: Option<u64>) -> Self {
+        self.offset = offset;
+        self.limit = limit;
+        self
+    }
     pub fn get_plasma_contract(&self) -> Address {
         self.plasma_contract
     }
@@ -48,24 +74,35 @@ impl StateQuery {
     pub fn get_params(&self) -> &[u8] {
         &self.params
     }
-    pub fn to_hash(&self) -> u64 {
-        let mut s = DefaultHasher::new();
-        self.hash(&mut s);
-        s.finish()
+    pub fn get_filter(&self) -> &Option<StateFilter> {
+        &self.filter
+    }
+    pub fn get_offset(&self) -> u64 {
+        self.offset
+    }
+    pub fn get_limit(&self) -> Option<u64> {
+        self.limit
+    }
+    /// Returns the range to query, unbounded sides are filled by the full coin range
+    pub fn get_range(&self) -> Range {
+        Range::new(
+            self.start.unwrap_or(UNBOUNDED_START),
+            self.end.unwrap_or(UNBOUNDED_END),
+        )
     }
 }
 
 impl Encodable for StateQuery {
-    fn to_abi(&self) -> Vec<u8> {
-        ethabi::encode(&self.to_tuple())
-    }
     fn to_tuple(&self) -> Vec<Token> {
         vec![
             Token::Address(self.plasma_contract),
             Token::Address(self.predicate_address),
-            Token::Int(self.start.unwrap().into()),
-            Token::Int(self.end.unwrap().into()),
+            from_optional_u64(self.start),
+            from_optional_u64(self.end),
             Token::Bytes(self.params.to_vec()),
+            Token::Bytes(self.filter.as_ref().map_or(vec![], |f| f.to_abi())),
+            Token::Uint(self.offset.into()),
+            from_optional_u64(self.limit),
         ]
     }
 }
@@ -73,43 +110,76 @@ impl Encodable for StateQuery {
 impl Decodable for StateQuery {
     type Ok = Self;
     fn from_tuple(tuple: &[Token]) -> Result<Self, Error> {
+        check_length(tuple, 8)?;
         let plasma = tuple[0].clone().to_address();
         let predicate = tuple[1].clone().to_address();
-        let start = tuple[2].clone().to_uint();
-        let end = tuple[3].clone().to_uint();
+        let start = to_optional_u64(tuple[2].clone());
+        let end = to_optional_u64(tuple[3].clone());
         let params = tuple[4].clone().to_bytes();
-        if let (Some(plasma), Some(predicate), Some(params)) = (plasma, predicate, params) {
-            Ok(StateQuery::new(
-                plasma,
-                predicate,
-                start.map(|s| s.as_u64()),
-                end.map(|e| e.as_u64()),
-                Bytes::from(params),
-            ))
+        let filter = tuple[5].clone().to_bytes();
+        let offset = to_u64(tuple[6].clone());
+        let limit = to_optional_u64(tuple[7].clone());
+        if let (
+            Some(plasma),
+            Some(predicate),
+            Some(start),
+            Some(end),
+            Some(params),
+            Some(filter),
+            Some(offset),
+            Some(limit),
+        ) = (plasma, predicate, start, end, params, filter, offset, limit)
+        {
+            let query = StateQuery::new(plasma, predicate, start, end, Bytes::from(params))
+                .with_pagination(offset, limit);
+            if filter.is_empty() {
+                Ok(query)
+            } else {
+                Ok(query.with_filter(StateFilter::from_abi(&filter)?))
+            }
         } else {
             Err(Error::from(ErrorKind::AbiDecode))
         }
     }
-    fn from_abi(data: &[u8]) -> Result<Self, Error> {
-        let decoded: Vec<Token> = ethabi::decode(
-            &[
-                ethabi::ParamType::Address,
-                ethabi::ParamType::Address,
-                ethabi::ParamType::Int(8),
-                ethabi::ParamType::Int(8),
-                ethabi::ParamType::Bytes,
+    fn get_param_types() -> Vec<ParamType> {
+        vec![
+            ParamType::Address,
+            ParamType::Address,
+            ParamType::Array(Box::new(ParamType::Uint(256))),
+            ParamType::Array(Box::new(ParamType::Uint(256))),
+            ParamType::Bytes,
+            ParamType::Bytes,
+            ParamType::Uint(256),
+            ParamType::Array(Box::new(ParamType::Uint(256))),
+        ]
+    }
+}
+
+impl AbiSchema for StateQuery {
+    fn get_schema() -> Schema {
+        Schema::new(
+            "StateQuery",
+            vec![
+                SchemaField::new("plasmaContract", SchemaType::address()),
+                SchemaField::new("predicateAddress", SchemaType::address()),
+                SchemaField::new("start", SchemaType::Array(Box::new(SchemaType::uint()))),
+                SchemaField::new("end", SchemaType::Array(Box::new(SchemaType::uint()))),
+                SchemaField::new("params", SchemaType::bytes()),
+                SchemaField::new("filter", SchemaType::bytes()),
+                SchemaField::new("offset", SchemaType::uint()),
+                SchemaField::new("limit", SchemaType::Array(Box::new(SchemaType::uint()))),
             ],
-            data,
         )
-        .map_err(|_e| Error::from(ErrorKind::AbiDecode))?;
-        Self::from_tuple(&decoded)
     }
 }
 
 /// The result of StateQuery
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 pub struct StateQueryResult {
     state_update: StateUpdate,
+    #[cfg_attr(feature = "json", serde(with = "super::serde_hex::vec"))]
     result: Vec<Bytes>,
 }
 
@@ -126,7 +196,10 @@ impl StateQueryResult {
     pub fn get_result(&self) -> &[Bytes] {
         &self.result
     }
-    pub fn to_tuple(&self) -> Vec<Token> {
+}
+
+impl Encodable for StateQueryResult {
+    fn to_tuple(&self) -> Vec<Token> {
         vec![
             Token::Bytes(self.state_update.to_abi()),
             Token::Array(
@@ -137,34 +210,181 @@ impl StateQueryResult {
             ),
         ]
     }
-    pub fn to_abi(&self) -> Vec<u8> {
-        ethabi::encode(&self.to_tuple())
-    }
-    pub fn from_tuple(tuple: &[Token]) -> Result<Self, Error> {
+}
+
+impl Decodable for StateQueryResult {
+    type Ok = Self;
+    fn from_tuple(tuple: &[Token]) -> Result<Self, Error> {
+        check_length(tuple, 2)?;
         let state_update_bytes = tuple[0].clone().to_bytes();
-        let result_array = tuple[1].clone().to_array();
-        if let (Some(state_update_bytes), Some(result_array)) = (state_update_bytes, result_array) {
-            let result: Vec<Bytes> = result_array
-                .iter()
-                .filter_map(|r| r.clone().to_bytes().map(Bytes::from))
-                .collect();
+        let result = tuple[1].clone().to_array().and_then(|result_array| {
+            result_array
+                .into_iter()
+                .map(|r| r.to_bytes().map(Bytes::from))
+                .collect::<Option<Vec<Bytes>>>()
+        });
+        if let (Some(state_update_bytes), Some(result)) = (state_update_bytes, result) {
             Ok(StateQueryResult::new(
-                StateUpdate::from_abi(&state_update_bytes).ok().unwrap(),
+                StateUpdate::from_abi(&state_update_bytes)?,
                 &result,
             ))
         } else {
             Err(Error::from(ErrorKind::AbiDecode))
         }
     }
-    pub fn from_abi(data: &[u8]) -> Result<Self, Error> {
-        let decoded: Vec<Token> = ethabi::decode(
-            &[
-                ethabi::ParamType::Bytes,
-                ethabi::ParamType::Array(Box::new(ethabi::ParamType::Bytes)),
+    fn get_param_types() -> Vec<ParamType> {
+        vec![
+            ParamType::Bytes,
+            ParamType::Array(Box::new(ParamType::Bytes)),
+        ]
+    }
+}
+
+impl AbiSchema for StateQueryResult {
+    fn get_schema() -> Schema {
+        Schema::new(
+            "StateQueryResult",
+            vec![
+                SchemaField::new("stateUpdate", SchemaType::bytes()),
+                SchemaField::new("result", SchemaType::Array(Box::new(SchemaType::bytes()))),
             ],
-            data,
         )
-        .map_err(|_e| Error::from(ErrorKind::AbiDecode))?;
-        Self::from_tuple(&decoded)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{StateQuery, StateQueryResult};
+    use crate::data_structure::abi::{Decodable, Encodable};
+    use crate::data_structure::{Range, StateFilter, StateObject, StateUpdate};
+    use bytes::Bytes;
+    use ethereum_types::Address;
+
+    #[test]
+    fn test_golden_vector() {
+        let query = StateQuery::new(
+            Address::zero(),
+            Address::zero(),
+            Some(0),
+            Some(100),
+            Bytes::new(),
+        );
+        let expected = concat!(
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000100",
+            "0000000000000000000000000000000000000000000000000000000000000140",
+            "0000000000000000000000000000000000000000000000000000000000000180",
+            "00000000000000000000000000000000000000000000000000000000000001a0",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "00000000000000000000000000000000000000000000000000000000000001c0",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000064",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+        );
+        assert_eq!(hex::encode(query.to_abi()), expected);
+        let decoded = StateQuery::from_abi(&hex::decode(expected).unwrap()).unwrap();
+        assert_eq!(decoded.get_start(), Some(0));
+        assert_eq!(decoded.get_end(), Some(100));
+    }
+
+    #[test]
+    fn test_unbounded_query() {
+        let query = StateQuery::new(Address::zero(), Address::zero(), None, None, Bytes::new());
+        let decoded = StateQuery::from_abi(&query.to_abi()).unwrap();
+        assert_eq!(decoded.get_start(), None);
+        assert_eq!(decoded.get_end(), None);
+        assert_eq!(decoded.get_range(), Range::new(0, 0xffff_ffff_ffff_ffff));
+    }
+
+    #[test]
+    fn test_bounds_at_the_edges() {
+        let query = StateQuery::new(
+            Address::zero(),
+            Address::zero(),
+            Some(0),
+            Some(0xffff_ffff_ffff_ffff),
+            Bytes::new(),
+        )
+        .with_pagination(0, Some(0));
+        let decoded = StateQuery::from_abi(&query.to_abi()).unwrap();
+        assert_eq!(decoded.get_start(), Some(0));
+        assert_eq!(decoded.get_end(), Some(0xffff_ffff_ffff_ffff));
+        assert_eq!(decoded.get_limit(), Some(0));
+    }
+
+    #[test]
+    fn test_filter_and_pagination() {
+        let filter = StateFilter::And(vec![
+            StateFilter::PredicateIn(vec![Address::zero()]),
+            StateFilter::BlockRange(Range::new(1, 10)),
+        ]);
+        let query = StateQuery::new(Address::zero(), Address::zero(), None, None, Bytes::new())
+            .with_filter(filter.clone())
+            .with_pagination(5, Some(10));
+        let decoded = StateQuery::from_abi(&query.to_abi()).unwrap();
+        assert_eq!(decoded.get_filter(), &Some(filter));
+        assert_eq!(decoded.get_offset(), 5);
+        assert_eq!(decoded.get_limit(), Some(10));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json() {
+        let query = StateQuery::new(
+            Address::zero(),
+            Address::zero(),
+            None,
+            Some(100),
+            Bytes::from(&b"params"[..]),
+        )
+        .with_filter(StateFilter::PredicateIn(vec![Address::zero()]))
+        .with_pagination(0, Some(10));
+        let json = serde_json::to_string(&query).unwrap();
+        assert!(json.contains(r#""params":"0x706172616d73""#));
+        let decoded: StateQuery = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.get_start(), None);
+        assert_eq!(decoded.get_params(), query.get_params());
+        assert_eq!(decoded.get_filter(), query.get_filter());
+        assert_eq!(decoded.get_limit(), Some(10));
+    }
+
+    #[test]
+    fn test_result_golden_vector() {
+        let state_update = StateUpdate::new(
+            StateObject::new(Address::zero(), Bytes::from(&b"parameters"[..])),
+            Range::new(0, 100),
+            1,
+            Address::zero(),
+        );
+        let result = StateQueryResult::new(state_update.clone(), &[Bytes::from(&b"data"[..])]);
+        let expected = concat!(
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "00000000000000000000000000000000000000000000000000000000000001a0",
+            "0000000000000000000000000000000000000000000000000000000000000140",
+            "00000000000000000000000000000000000000000000000000000000000000a0",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000064",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "000000000000000000000000000000000000000000000000000000000000000a",
+            "706172616d657465727300000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000004",
+            "6461746100000000000000000000000000000000000000000000000000000000",
+        );
+        assert_eq!(hex::encode(result.to_abi()), expected);
+        let decoded = StateQueryResult::from_abi(&hex::decode(expected).unwrap()).unwrap();
+        assert_eq!(decoded.get_state_update(), &state_update);
+        assert_eq!(decoded.get_result(), result.get_result());
+    }
+
+}
@@ -1,12 +1,15 @@
 extern crate ethabi;
 
-use super::abi::{Decodable, Encodable};
+use super::abi::{check_length, to_u64, Decodable, Encodable};
 use super::error::{Error, ErrorKind};
+use super::schema::{AbiSchema, Schema, SchemaField, SchemaType};
 use super::{Range, StateObject};
 use ethabi::{ParamType, Token};
 use ethereum_types::Address;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 pub struct StateUpdate {
     state_object: StateObject,
     range: Range,
@@ -53,37 +56,46 @@ impl Encodable for StateUpdate {
 impl Decodable for StateUpdate {
     type Ok = Self;
     fn from_tuple(tuple: &[Token]) -> Result<Self, Error> {
+        check_length(tuple, 4)?;
         let state_object = tuple[0].clone().to_bytes();
         let range = tuple[1].clone().to_tuple();
-        let block_number = tuple[2].clone().to_uint();
+        let block_number = to_u64(tuple[2].clone());
         let plasma_contract = tuple[3].clone().to_address();
 
         if let (Some(state_object), Some(range), Some(block_number), Some(plasma_contract)) =
             (state_object, range, block_number, plasma_contract)
         {
             Ok(StateUpdate::new(
-                StateObject::from_abi(&state_object).unwrap(),
-                Range::from_tuple(&range).unwrap(),
-                block_number.as_u64(),
+                StateObject::from_abi(&state_object)?,
+                Range::from_tuple(&range)?,
+                block_number,
                 plasma_contract,
             ))
         } else {
             Err(Error::from(ErrorKind::AbiDecode))
         }
     }
-    fn from_abi(data: &[u8]) -> Result<Self, Error> {
-        let decoded: Vec<Token> = ethabi::decode(
-            &[
-                // ParamType::Tuple(vec![ParamType::Address, ParamType::Bytes]),
-                ParamType::Bytes,
-                ParamType::Tuple(vec![ParamType::Uint(8), ParamType::Uint(8)]),
-                ParamType::Uint(8),
-                ParamType::Address,
+    fn get_param_types() -> Vec<ParamType> {
+        vec![
+            ParamType::Bytes,
+            ParamType::Tuple(Range::get_param_types()),
+            ParamType::Uint(256),
+            ParamType::Address,
+        ]
+    }
+}
+
+impl AbiSchema for StateUpdate {
+    fn get_schema() -> Schema {
+        Schema::new(
+            "StateUpdate",
+            vec![
+                SchemaField::new("stateObject", SchemaType::bytes()),
+                SchemaField::new("range", SchemaType::Struct(Range::get_schema())),
+                SchemaField::new("blockNumber", SchemaType::uint()),
+                SchemaField::new("plasmaContract", SchemaType::address()),
             ],
-            data,
         )
-        .map_err(|_e| Error::from(ErrorKind::AbiDecode))?;
-        Self::from_tuple(&decoded)
     }
 }
 
@@ -108,4 +120,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_golden_vector() {
+        let state_object = StateObject::new(Address::zero(), Bytes::from(&b"parameters"[..]));
+        let state_update = StateUpdate::new(state_object, Range::new(0, 100), 1, Address::zero());
+        let expected = concat!(
+            "00000000000000000000000000000000000000000000000000000000000000a0",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000064",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "000000000000000000000000000000000000000000000000000000000000000a",
+            "706172616d657465727300000000000000000000000000000000000000000000",
+        );
+        assert_eq!(hex::encode(state_update.to_abi()), expected);
+        assert_eq!(
+            StateUpdate::from_abi(&hex::decode(expected).unwrap()).unwrap(),
+            state_update
+        );
+    }
+
+    #[test]
+    fn test_decode_malformed() {
+        let state_object = StateObject::new(Address::zero(), Bytes::from(&b"parameters"[..]));
+        let state_update = StateUpdate::new(state_object, Range::new(0, 100), 1, Address::zero());
+        let encoded = state_update.to_abi();
+        // truncated data
+        assert!(StateUpdate::from_abi(&encoded[..encoded.len() - 1]).is_err());
+        assert!(StateUpdate::from_abi(&[]).is_err());
+        // block number which overflows u64
+        let mut overflowed = encoded.clone();
+        overflowed[3 * 32 + 23] = 1;
+        assert!(StateUpdate::from_abi(&overflowed).is_err());
+        // tuple which is shorter than expected
+        assert!(StateUpdate::from_tuple(&state_update.to_tuple()[..3]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json() {
+        let state_object = StateObject::new(Address::zero(), Bytes::from(&b"parameters"[..]));
+        let state_update = StateUpdate::new(state_object, Range::new(0, 100), 1, Address::zero());
+        let expected = concat!(
+            r#"{"stateObject":{"predicate":"0x0000000000000000000000000000000000000000","#,
+            r#""data":"0x706172616d6574657273"},"range":{"start":0,"end":100},"#,
+            r#""blockNumber":1,"plasmaContract":"0x0000000000000000000000000000000000000000"}"#,
+        );
+        assert_eq!(serde_json::to_string(&state_update).unwrap(), expected);
+        assert_eq!(
+            serde_json::from_str::<StateUpdate>(expected).unwrap(),
+            state_update
+        );
+    }
+
 }
@@ -1,15 +1,17 @@
 extern crate ethereum_types;
 extern crate tiny_keccak;
 
-use super::abi::{Decodable, Encodable};
+use super::abi::{check_length, to_h256, to_u64, Decodable, Encodable};
 use super::error::{Error, ErrorKind};
+use super::schema::{AbiSchema, Schema, SchemaField, SchemaType};
 use super::Range;
 use bytes::Bytes;
-use ethabi::Token;
+use ethabi::{ParamType, Token};
 use ethereum_types::{Address, H256};
 use tiny_keccak::Keccak;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Witness {
     v: H256,
     r: H256,
@@ -23,6 +25,8 @@ impl Witness {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 /// ## struct Transaction
 /// - has a `plasma_contract_address`
 /// - has a `start` (A range element)
@@ -36,6 +40,7 @@ impl Witness {
 pub struct Transaction {
     plasma_contract_address: Address,
     range: Range,
+    #[cfg_attr(feature = "json", serde(with = "super::serde_hex"))]
     parameters: Bytes,
     witness: Witness,
 }
@@ -120,47 +125,51 @@ impl Encodable for Transaction {
 impl Decodable for Transaction {
     type Ok = Self;
     fn from_tuple(tuple: &[Token]) -> Result<Self, Error> {
+        check_length(tuple, 6)?;
         let plasma_contract = tuple[0].clone().to_address();
         let range = tuple[1].clone().to_tuple();
         let parameters = tuple[2].clone().to_bytes();
-        let v = tuple[3].clone().to_fixed_bytes();
-        let r = tuple[4].clone().to_fixed_bytes();
-        let s = tuple[5].clone().to_uint();
+        let v = to_h256(tuple[3].clone());
+        let r = to_h256(tuple[4].clone());
+        let s = to_u64(tuple[5].clone());
         if let (Some(plasma_contract), Some(range), Some(parameters), Some(v), Some(r), Some(s)) =
             (plasma_contract, range, parameters, v, r, s)
         {
             Ok(Transaction::new(
                 plasma_contract,
-                Range::from_tuple(&range).ok().unwrap(),
+                Range::from_tuple(&range)?,
                 Bytes::from(parameters),
-                &Witness::new(H256::from_slice(&v), H256::from_slice(&r), s.as_u64()),
+                &Witness::new(v, r, s),
             ))
         } else {
             Err(Error::from(ErrorKind::AbiDecode))
         }
     }
-    /// ### Transaction.from_abi()
-    /// A static function to convert the abi into a tx instance
-    /// ```ignore
-    /// let tx = Transaction.from_abi(&abi)
-    /// ```
-    fn from_abi(data: &[u8]) -> Result<Self, Error> {
-        let decoded: Vec<Token> = ethabi::decode(
-            &[
-                ethabi::ParamType::Address,
-                ethabi::ParamType::Tuple(vec![
-                    ethabi::ParamType::Uint(8),
-                    ethabi::ParamType::Uint(8),
-                ]),
-                ethabi::ParamType::Bytes,
-                ethabi::ParamType::FixedBytes(32),
-                ethabi::ParamType::FixedBytes(32),
-                ethabi::ParamType::Uint(1),
+    fn get_param_types() -> Vec<ParamType> {
+        vec![
+            ParamType::Address,
+            ParamType::Tuple(Range::get_param_types()),
+            ParamType::Bytes,
+            ParamType::FixedBytes(32),
+            ParamType::FixedBytes(32),
+            ParamType::Uint(256),
+        ]
+    }
+}
+
+impl AbiSchema for Transaction {
+    fn get_schema() -> Schema {
+        Schema::new(
+            "Transaction",
+            vec![
+                SchemaField::new("plasmaContractAddress", SchemaType::address()),
+                SchemaField::new("range", SchemaType::Struct(Range::get_schema())),
+                SchemaField::new("parameters", SchemaType::bytes()),
+                SchemaField::new("v", SchemaType::Elementary(ParamType::FixedBytes(32))),
+                SchemaField::new("r", SchemaType::Elementary(ParamType::FixedBytes(32))),
+                SchemaField::new("s", SchemaType::uint()),
             ],
-            data,
         )
-        .map_err(|_e| Error::from(ErrorKind::AbiDecode))?;
-        Self::from_tuple(&decoded)
     }
 }
 
@@ -188,4 +197,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_golden_vector() {
+        let transaction = Transaction::new(
+            Address::zero(),
+            Range::new(0, 100),
+            Bytes::from(&b"parameters"[..]),
+            &Witness::new(H256::zero(), H256::zero(), 0),
+        );
+        let expected = concat!(
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000064",
+            "00000000000000000000000000000000000000000000000000000000000000e0",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "000000000000000000000000000000000000000000000000000000000000000a",
+            "706172616d657465727300000000000000000000000000000000000000000000",
+        );
+        assert_eq!(hex::encode(transaction.to_abi()), expected);
+        assert_eq!(
+            Transaction::from_abi(&hex::decode(expected).unwrap()).unwrap(),
+            transaction
+        );
+    }
+
 }
@@ -0,0 +1,6 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use ovm::types::DecisionValue;
+use plasma_fuzz::check_decoder;
+
+fuzz_target!(|data: &[u8]| check_decoder::<DecisionValue>(data));
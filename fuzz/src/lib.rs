@@ -0,0 +1,11 @@
+use plasma_core::data_structure::abi::{Decodable, Encodable};
+
+/// Decodes arbitrary data. Decoding must not panic,
+/// and a decoded value must be decoded again from its own encoding.
+pub fn check_decoder<T: Decodable<Ok = T> + Encodable>(data: &[u8]) {
+    if let Ok(decoded) = T::from_abi(data) {
+        let encoded = decoded.to_abi();
+        let decoded_again = T::from_abi(&encoded).expect("failed to decode encoded value");
+        assert_eq!(decoded_again.to_abi(), encoded);
+    }
+}
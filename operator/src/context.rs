@@ -11,7 +11,7 @@ use crate::block::BlockManager;
 use crate::error::Error;
 use parking_lot::RwLock;
 use plasma_client::state::StateManager;
-use plasma_core::data_structure::{StateUpdate, Transaction};
+use plasma_core::data_structure::{StateQuery, StateQueryResult, StateUpdate, Transaction};
 use plasma_db::impls::kvs::CoreDbMemoryImpl;
 use std::sync::Arc;
 
@@ -42,14 +42,21 @@ impl ChainContext {
             .is_ok()
     }
     pub fn append(&self, signed_transaction: &Transaction) -> Result<(), Error> {
+        let pending_block_number = self.block_manager.read().get_next_block_number()?.as_u64();
         let result = self
             .state_manager
             .write()
-            .execute_transaction(signed_transaction)
+            .execute_transaction(signed_transaction, pending_block_number)
             .map_err::<Error, _>(Into::into)?;
         self.block_manager
             .write()
             .add_pending_state_update(result.get_state_update())
             .map_err::<Error, _>(Into::into)
     }
+    pub fn query_state(&self, query: &StateQuery) -> Result<Box<[StateQueryResult]>, Error> {
+        self.state_manager
+            .read()
+            .query_state(query)
+            .map_err::<Error, _>(Into::into)
+    }
 }
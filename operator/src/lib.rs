@@ -20,6 +20,7 @@ pub mod server;
 
 use self::server::get_server;
 use env_logger;
+use ovm::types::{set_decider_ids, DeciderIdRegistry};
 use std::env;
 
 /// entry point of plasma chain.
@@ -27,6 +28,15 @@ pub fn entry() {
     env::set_var("RUST_LOG", "info");
     env_logger::init();
     println!("Hello, operator!!");
+    load_decider_ids();
     let server = get_server(&Default::default()).expect("Unable to start rpc server.");
     server.wait();
 }
+
+/// Loads decider IDs from the file at `DECIDER_CONFIG`, default IDs are used without it.
+fn load_decider_ids() {
+    if let Ok(path) = env::var("DECIDER_CONFIG") {
+        let registry = DeciderIdRegistry::load(&path).expect("Unable to load decider config.");
+        set_decider_ids(registry);
+    }
+}
@@ -11,6 +11,7 @@ extern crate plasma_core;
 
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
+use plasma_core::data_structure::{StateQuery, StateQueryResult, Transaction};
 
 /// Plasma JSON RPC trait.
 #[rpc]
@@ -21,4 +22,13 @@ pub trait PlasmaRpc {
     /// append signed transaction
     #[rpc(name = "sendTransaction")]
     fn send_transaction(&self, message: String) -> Result<bool>;
+    /// query state updates, unbounded start and end are allowed
+    #[rpc(name = "sendQuery")]
+    fn send_query(&self, message: String) -> Result<Vec<String>>;
+    /// append transaction represented in JSON
+    #[rpc(name = "sendTransactionJson")]
+    fn send_transaction_json(&self, transaction: Transaction) -> Result<bool>;
+    /// query state updates by StateQuery represented in JSON
+    #[rpc(name = "sendQueryJson")]
+    fn send_query_json(&self, query: StateQuery) -> Result<Vec<StateQueryResult>>;
 }
@@ -13,8 +13,8 @@ use super::errors;
 use super::plasmarpc::PlasmaRpc;
 use crate::context::ChainContext;
 use jsonrpc_core::Result;
-use plasma_core::data_structure::abi::Decodable;
-use plasma_core::data_structure::Transaction;
+use plasma_core::data_structure::abi::{Decodable, Encodable};
+use plasma_core::data_structure::{StateQuery, StateQueryResult, Transaction};
 
 /// Plasma JSON RPC implementation.
 #[derive(Default)]
@@ -46,8 +46,24 @@ impl PlasmaRpc for PlasmaRpcImpl {
         let abi_bytes = hex::decode(message).map_err(errors::invalid_params)?;
         let transaction: Transaction =
             Transaction::from_abi(&abi_bytes).map_err(errors::invalid_params)?;
+        self.send_transaction_json(transaction)
+    }
+    fn send_query(&self, message: String) -> Result<Vec<String>> {
+        let abi_bytes = hex::decode(message).map_err(errors::invalid_params)?;
+        let query: StateQuery = StateQuery::from_abi(&abi_bytes).map_err(errors::invalid_params)?;
+        let results = self.send_query_json(query)?;
+        Ok(results.iter().map(|r| hex::encode(r.to_abi())).collect())
+    }
+    fn send_transaction_json(&self, transaction: Transaction) -> Result<bool> {
         Ok(self.chain_context.append(&transaction).is_ok())
     }
+    fn send_query_json(&self, query: StateQuery) -> Result<Vec<StateQueryResult>> {
+        let results = self
+            .chain_context
+            .query_state(&query)
+            .map_err(errors::invalid_params)?;
+        Ok(results.to_vec())
+    }
 }
 
 #[cfg(test)]
@@ -57,9 +73,11 @@ mod tests {
     use super::PlasmaRpcImpl;
     use bytes::Bytes;
     use ethereum_types::{Address, H256};
-    use jsonrpc_http_server::jsonrpc_core::IoHandler;
+    use jsonrpc_http_server::jsonrpc_core::{serde_json, IoHandler};
     use plasma_core::data_structure::abi::Encodable;
-    use plasma_core::data_structure::{Range, StateObject, StateUpdate, Transaction, Witness};
+    use plasma_core::data_structure::{
+        Range, StateObject, StateQuery, StateQueryResult, StateUpdate, Transaction, Witness,
+    };
     use predicate_plugins::parameters::PredicateParameters;
     use predicate_plugins::OwnershipPredicateParameters;
 
@@ -119,6 +137,81 @@ mod tests {
         assert_eq!(io.handle_request_sync(&request), Some(response.to_string()));
     }
 
+    #[test]
+    fn test_send_query_without_range() {
+        let mut io = IoHandler::new();
+
+        let context = ChainContext::new();
+        assert!(context.initiate().is_ok());
+        let deposit_state = StateUpdate::new(
+            StateObject::new(Address::zero(), Bytes::from(&b"data"[..])),
+            Range::new(0, 200),
+            10,
+            Address::zero(),
+        );
+        assert!(context.force_deposit(&deposit_state));
+        let rpc = PlasmaRpcImpl::from(context);
+        io.extend_with(rpc.to_delegate());
+
+        let query = StateQuery::new(Address::zero(), Address::zero(), None, None, Bytes::new());
+        let request = format!(
+            r#"{{
+                "jsonrpc": "2.0",
+                "method": "sendQuery",
+                "params": ["{}"],
+                "id": 1
+            }}"#,
+            hex::encode(query.to_abi()),
+        );
+        let expected = StateQueryResult::new(deposit_state, &[Bytes::from(&b"data"[..])]);
+        let response = format!(
+            r#"{{"jsonrpc":"2.0","result":["{}"],"id":1}}"#,
+            hex::encode(expected.to_abi())
+        );
+
+        assert_eq!(io.handle_request_sync(&request), Some(response));
+    }
+
+    #[test]
+    fn test_send_query_json() {
+        let mut io = IoHandler::new();
+
+        let context = ChainContext::new();
+        assert!(context.initiate().is_ok());
+        let deposit_state = StateUpdate::new(
+            StateObject::new(Address::zero(), Bytes::from(&b"data"[..])),
+            Range::new(0, 200),
+            10,
+            Address::zero(),
+        );
+        assert!(context.force_deposit(&deposit_state));
+        let rpc = PlasmaRpcImpl::from(context);
+        io.extend_with(rpc.to_delegate());
+
+        let request = r#"{
+            "jsonrpc": "2.0",
+            "method": "sendQueryJson",
+            "params": [{
+                "plasmaContract": "0x0000000000000000000000000000000000000000",
+                "predicateAddress": "0x0000000000000000000000000000000000000000",
+                "start": null,
+                "end": null,
+                "params": "0x",
+                "filter": null,
+                "offset": 0,
+                "limit": null
+            }],
+            "id": 1
+        }"#;
+        let expected = StateQueryResult::new(deposit_state, &[Bytes::from(&b"data"[..])]);
+        let response = format!(
+            r#"{{"jsonrpc":"2.0","result":[{}],"id":1}}"#,
+            serde_json::to_string(&expected).unwrap()
+        );
+
+        assert_eq!(io.handle_request_sync(request), Some(response));
+    }
+
     /*
     #[test]
     fn test_faile_to_send_transaction() {
@@ -0,0 +1,28 @@
+//! Prints Solidity structs or ABI JSON of the types which contracts decode,
+//! so that contracts and Rust codecs can be diffed.
+//! The output is committed in `ovm/schema`, and CI fails if it is out of date.
+//!
+//! ```sh
+//! cargo run -p ovm --example abi_schema -- solidity > ovm/schema/Types.sol
+//! cargo run -p ovm --example abi_schema -- json > ovm/schema/types.json
+//! ```
+
+use ovm::types::schema::get_schemas;
+use plasma_core::data_structure::schema::{to_json, to_solidity};
+use std::env;
+use std::process;
+
+fn main() {
+    let schemas = get_schemas();
+    match env::args().nth(1).as_ref().map(String::as_str) {
+        Some("solidity") | None => print!(
+            "pragma solidity ^0.5.0;\npragma experimental ABIEncoderV2;\n\n{}",
+            to_solidity(&schemas)
+        ),
+        Some("json") => print!("{}", to_json(&schemas)),
+        Some(format) => {
+            eprintln!("unknown format `{}`, use `solidity` or `json`", format);
+            process::exit(1);
+        }
+    }
+}
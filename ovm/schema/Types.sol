@@ -0,0 +1,126 @@
+pragma solidity ^0.5.0;
+pragma experimental ABIEncoderV2;
+
+struct Range {
+    uint256 start;
+    uint256 end;
+}
+
+struct StateObject {
+    address predicate;
+    bytes data;
+}
+
+struct StateUpdate {
+    bytes stateObject;
+    Range range;
+    uint256 blockNumber;
+    address plasmaContract;
+}
+
+struct Transaction {
+    address plasmaContractAddress;
+    Range range;
+    bytes parameters;
+    bytes32 v;
+    bytes32 r;
+    uint256 s;
+}
+
+struct Property {
+    address tag;
+    bytes data;
+}
+
+struct Quantifier {
+    uint256 tag;
+    bytes data;
+}
+
+struct Witness {
+    uint256 tag;
+    bytes data;
+}
+
+struct PlasmaDataBlock {
+    uint256 leafIndex;
+    Range updatedRange;
+    Property property;
+}
+
+struct DecisionValue {
+    bool decision;
+    Witness witness;
+}
+
+struct AndDeciderInput {
+    Property left;
+    Witness leftWitness;
+    Property right;
+    Witness rightWitness;
+}
+
+struct OrDeciderInput {
+    Property left;
+    Witness leftWitness;
+    Property right;
+    Witness rightWitness;
+}
+
+struct NotDeciderInput {
+    Property property;
+    Witness witness;
+}
+
+struct Variable {
+    bytes name;
+}
+
+struct ForAllSuchThatInput {
+    Quantifier quantifier;
+    Variable variable;
+    Property propertyTemplate;
+    Witness[] witnessTemplate;
+}
+
+struct ThereExistsSuchThatInput {
+    Quantifier quantifier;
+    Variable variable;
+    Property propertyTemplate;
+    Witness[] witnessTemplate;
+}
+
+struct PreimageExistsInput {
+    bytes32 hash;
+}
+
+struct SignedByInput {
+    bytes message;
+    address publicKey;
+}
+
+struct IncludedInIntervalTreeAtBlockInput {
+    uint256 blockNumber;
+    Range coinRange;
+}
+
+struct Message {
+    bytes channelId;
+    address sender;
+    address recipient;
+    uint256 nonce;
+    address[] signers;
+    bytes message;
+    bytes signedMessage;
+}
+
+struct HasLowerNonceInput {
+    Message message;
+    uint256 nonce;
+}
+
+struct ChannelUpdateSignatureExistsDeciderInput {
+    bytes channelId;
+    uint256 nonce;
+    address particilant;
+}
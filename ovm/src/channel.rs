@@ -0,0 +1,502 @@
+use crate::contradiction::{find_contradiction, Contradiction};
+use crate::db::Message;
+use crate::deciders::BlockDeadlineDecider;
+use crate::error::{Error, ErrorKind};
+use crate::property_executor::PropertyExecutor;
+use crate::statements::{
+    create_state_channel_dispute_property, create_state_channel_exit_property,
+};
+use crate::types::{
+    BlockDeadlineInput, ChannelUpdateSignatureExistsDeciderInput, Decider, Integer, Property,
+};
+use bytes::Bytes;
+use ethabi::{ParamType, Token};
+use ethereum_types::Address;
+use ethsign::SecretKey;
+use plasma_db::traits::kvs::KeyValueStore;
+
+/// The status of a channel from the view of a participant
+#[derive(Clone, Debug)]
+pub enum ChannelStatus {
+    Open,
+    /// The participant has claimed the exit with the update,
+    /// which can be disputed until the deadline block
+    Exiting(Message, Integer),
+    /// The channel is closed with the final state
+    Closed(Bytes),
+}
+
+/// A state channel between two participants. Updates are messages of `MessageDb` whose
+/// nonces increase by one from 1, and each of them is proposed by a participant and
+/// countersigned by the other. The body of an update has the state and whether it's final.
+pub struct StateChannel {
+    channel_id: Bytes,
+    participant: Address,
+    counterparty: Address,
+    status: ChannelStatus,
+}
+
+impl StateChannel {
+    /// Opens the channel for `participant`, who owns the `MessageDb` which methods are given
+    pub fn open(channel_id: Bytes, participant: Address, counterparty: Address) -> Self {
+        StateChannel {
+            channel_id,
+            participant,
+            counterparty,
+            status: ChannelStatus::Open,
+        }
+    }
+    pub fn get_channel_id(&self) -> &Bytes {
+        &self.channel_id
+    }
+    pub fn get_participant(&self) -> Address {
+        self.participant
+    }
+    pub fn get_counterparty(&self) -> Address {
+        self.counterparty
+    }
+    pub fn get_status(&self) -> &ChannelStatus {
+        &self.status
+    }
+    /// Returns the latest update which both participants have signed
+    pub fn get_latest_update<KVS: KeyValueStore>(
+        &self,
+        decider: &PropertyExecutor<KVS>,
+    ) -> Result<Option<Message>, Error> {
+        let signers = [self.participant, self.counterparty];
+        Ok(self
+            .get_updates(decider)?
+            .into_iter()
+            .rev()
+            .find(|update| is_signed_by(update, &signers)))
+    }
+    /// Signs and stores the next update with the state, which is sent to the counterparty
+    pub fn propose_update<KVS: KeyValueStore>(
+        &self,
+        decider: &PropertyExecutor<KVS>,
+        key: &SecretKey,
+        state: Bytes,
+    ) -> Result<Message, Error> {
+        self.propose(decider, key, state, false)
+    }
+    /// Proposes the final update with the latest state to close the channel cooperatively
+    pub fn propose_close<KVS: KeyValueStore>(
+        &self,
+        decider: &PropertyExecutor<KVS>,
+        key: &SecretKey,
+    ) -> Result<Message, Error> {
+        let state = match self.get_latest_update(decider)? {
+            Some(update) => decode_update(&update)?.0,
+            None => Bytes::new(),
+        };
+        self.propose(decider, key, state, true)
+    }
+    /// Countersigns and stores the update proposed by the counterparty,
+    /// which is sent back to the counterparty
+    pub fn accept_update<KVS: KeyValueStore>(
+        &mut self,
+        decider: &PropertyExecutor<KVS>,
+        key: &SecretKey,
+        update: &Message,
+    ) -> Result<Message, Error> {
+        self.check_signer(key)?;
+        self.check_next_update(decider, update, self.counterparty, &[self.counterparty])?;
+        // the participant has proposed another update at the same nonce
+        if self.get_pending_update(decider, update.nonce)?.is_some() {
+            return Err(Error::from(ErrorKind::InvalidChannelUpdate));
+        }
+        let mut update = update.clone();
+        update.sign(key);
+        self.store_update(decider, &update)?;
+        Ok(update)
+    }
+    /// Stores the update proposed by the participant and countersigned by the counterparty
+    pub fn receive_update<KVS: KeyValueStore>(
+        &mut self,
+        decider: &PropertyExecutor<KVS>,
+        update: &Message,
+    ) -> Result<(), Error> {
+        self.check_next_update(
+            decider,
+            update,
+            self.participant,
+            &[self.participant, self.counterparty],
+        )?;
+        match self.get_pending_update(decider, update.nonce)? {
+            Some(proposed) if proposed.get_message() == update.get_message() => {
+                self.store_update(decider, update)
+            }
+            _ => Err(Error::from(ErrorKind::InvalidChannelUpdate)),
+        }
+    }
+    /// Claims the exit with the latest update which the counterparty has signed,
+    /// and the dispute period lasts until the `deadline` block.
+    /// The claim is refused if the participant has signed the next update.
+    pub fn exit<KVS: KeyValueStore>(
+        &mut self,
+        decider: &PropertyExecutor<KVS>,
+        deadline: Integer,
+    ) -> Result<Property, Error> {
+        self.check_open()?;
+        let update = self
+            .get_updates(decider)?
+            .into_iter()
+            .rev()
+            .find(|update| update.get_signers().contains(&self.counterparty))
+            .ok_or_else(|| Error::from(ErrorKind::InvalidChannelStatus))?;
+        let claim = self.check_exit(decider, &update)?;
+        self.status = ChannelStatus::Exiting(update, deadline);
+        Ok(claim)
+    }
+    /// Returns the contradiction against the exit claimed by the counterparty with the update
+    /// at `nonce`, if the counterparty has signed the next update
+    pub fn dispute<KVS: KeyValueStore>(
+        &self,
+        decider: &PropertyExecutor<KVS>,
+        nonce: Integer,
+    ) -> Result<Option<Contradiction>, Error> {
+        let claim = create_state_channel_exit_property(
+            self.channel_id.clone(),
+            nonce,
+            self.counterparty,
+            self.participant,
+        )?;
+        let dispute = create_state_channel_dispute_property(
+            self.channel_id.clone(),
+            nonce,
+            self.counterparty,
+        )?;
+        if !is_decided_true(decider, &dispute)? {
+            return Ok(None);
+        }
+        find_contradiction(decider, &claim, &dispute)
+    }
+    /// Closes the channel with the state of the exit once `BlockDeadlineDecider` decides
+    /// the deadline has come, unless the participant has signed the next update since the claim
+    pub fn close_exit<KVS: KeyValueStore>(
+        &mut self,
+        decider: &PropertyExecutor<KVS>,
+    ) -> Result<(), Error> {
+        let (update, deadline) = match &self.status {
+            ChannelStatus::Exiting(update, deadline) => (update.clone(), *deadline),
+            _ => return Err(Error::from(ErrorKind::InvalidChannelStatus)),
+        };
+        let deadline = BlockDeadlineInput::new(deadline);
+        if !BlockDeadlineDecider::decide(decider, &deadline, None)?.get_outcome() {
+            return Err(Error::from(ErrorKind::InvalidChannelStatus));
+        }
+        self.check_exit(decider, &update)?;
+        self.status = ChannelStatus::Closed(decode_update(&update)?.0);
+        Ok(())
+    }
+    /// Returns the exit claim with the update. The counterparty's signature is decided locally,
+    /// while that the participant hasn't signed the next update is left to disputes,
+    /// so the claim is refused only if the participant's signature on it is known.
+    fn check_exit<KVS: KeyValueStore>(
+        &self,
+        decider: &PropertyExecutor<KVS>,
+        update: &Message,
+    ) -> Result<Property, Error> {
+        let signed = Property::ChannelUpdateSignatureExistsDecider(
+            ChannelUpdateSignatureExistsDeciderInput::new(
+                self.channel_id.clone(),
+                update.nonce,
+                self.counterparty,
+            ),
+        );
+        let next = create_state_channel_dispute_property(
+            self.channel_id.clone(),
+            update.nonce,
+            self.participant,
+        )?;
+        if !is_decided_true(decider, &signed)? || is_decided_true(decider, &next)? {
+            return Err(Error::from(ErrorKind::InvalidChannelStatus));
+        }
+        create_state_channel_exit_property(
+            self.channel_id.clone(),
+            update.nonce,
+            self.participant,
+            self.counterparty,
+        )
+    }
+    fn propose<KVS: KeyValueStore>(
+        &self,
+        decider: &PropertyExecutor<KVS>,
+        key: &SecretKey,
+        state: Bytes,
+        is_final: bool,
+    ) -> Result<Message, Error> {
+        self.check_open()?;
+        self.check_signer(key)?;
+        let nonce = self.get_next_nonce(decider)?;
+        // an update can't be proposed until the previous one is countersigned
+        if self.get_pending_update(decider, nonce)?.is_some() {
+            return Err(Error::from(ErrorKind::InvalidChannelUpdate));
+        }
+        let mut update = Message::new(
+            self.channel_id.clone(),
+            self.participant,
+            self.counterparty,
+            nonce,
+            encode_update(&state, is_final),
+        );
+        update.sign(key);
+        decider.get_message_db().store_message(&update)?;
+        Ok(update)
+    }
+    /// Stores the update signed by both participants, the final one closes the channel
+    fn store_update<KVS: KeyValueStore>(
+        &mut self,
+        decider: &PropertyExecutor<KVS>,
+        update: &Message,
+    ) -> Result<(), Error> {
+        let (state, is_final) = decode_update(update)?;
+        decider.get_message_db().store_message(update)?;
+        if is_final {
+            self.status = ChannelStatus::Closed(state);
+        }
+        Ok(())
+    }
+    fn check_next_update<KVS: KeyValueStore>(
+        &self,
+        decider: &PropertyExecutor<KVS>,
+        update: &Message,
+        sender: Address,
+        signers: &[Address],
+    ) -> Result<(), Error> {
+        self.check_open()?;
+        let recipient = if sender == self.participant {
+            self.counterparty
+        } else {
+            self.participant
+        };
+        if update.channel_id != self.channel_id
+            || update.get_sender() != sender
+            || update.get_recipient() != recipient
+            || update.get_signers().as_slice() != signers
+            || update.nonce != self.get_next_nonce(decider)?
+        {
+            return Err(Error::from(ErrorKind::InvalidChannelUpdate));
+        }
+        decode_update(update)?;
+        update.verify_signatures()
+    }
+    fn check_open(&self) -> Result<(), Error> {
+        match self.status {
+            ChannelStatus::Open => Ok(()),
+            _ => Err(Error::from(ErrorKind::InvalidChannelStatus)),
+        }
+    }
+    fn check_signer(&self, key: &SecretKey) -> Result<(), Error> {
+        if Address::from(key.public().address()) != self.participant {
+            return Err(Error::from(ErrorKind::InvalidChannelUpdate));
+        }
+        Ok(())
+    }
+    fn get_next_nonce<KVS: KeyValueStore>(
+        &self,
+        decider: &PropertyExecutor<KVS>,
+    ) -> Result<Integer, Error> {
+        let next_nonce = match self.get_latest_update(decider)? {
+            Some(update) => update.nonce.0.checked_add(1),
+            None => Some(1),
+        };
+        next_nonce
+            .map(Integer)
+            .ok_or_else(|| Error::from(ErrorKind::InvalidChannelUpdate))
+    }
+    /// Returns the update at the nonce which only one participant has signed
+    fn get_pending_update<KVS: KeyValueStore>(
+        &self,
+        decider: &PropertyExecutor<KVS>,
+        nonce: Integer,
+    ) -> Result<Option<Message>, Error> {
+        decider
+            .get_message_db()
+            .get_message_by_channel_id_and_nonce(self.channel_id.clone(), nonce)
+    }
+    fn get_updates<KVS: KeyValueStore>(
+        &self,
+        decider: &PropertyExecutor<KVS>,
+    ) -> Result<Vec<Message>, Error> {
+        decider
+            .get_message_db()
+            .get_messages_by_channel_id(&self.channel_id)
+    }
+}
+
+/// Returns the state of the update and whether it's final
+pub fn decode_update(update: &Message) -> Result<(Bytes, bool), Error> {
+    let tokens = ethabi::decode(&[ParamType::Bytes, ParamType::Bool], update.get_message())
+        .map_err(|_| Error::from(ErrorKind::InvalidChannelUpdate))?;
+    match (&tokens[0], &tokens[1]) {
+        (Token::Bytes(state), Token::Bool(is_final)) => Ok((Bytes::from(&state[..]), *is_final)),
+        _ => Err(Error::from(ErrorKind::InvalidChannelUpdate)),
+    }
+}
+
+fn encode_update(state: &Bytes, is_final: bool) -> Bytes {
+    Bytes::from(ethabi::encode(&[
+        Token::Bytes(state.to_vec()),
+        Token::Bool(is_final),
+    ]))
+}
+
+fn is_decided_true<KVS: KeyValueStore>(
+    decider: &PropertyExecutor<KVS>,
+    property: &Property,
+) -> Result<bool, Error> {
+    Ok(decider
+        .try_decide(property, None)?
+        .map_or(false, |decision| decision.get_outcome()))
+}
+
+fn is_signed_by(update: &Message, signers: &[Address]) -> bool {
+    signers
+        .iter()
+        .all(|signer| update.get_signers().contains(signer))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{decode_update, ChannelStatus, StateChannel};
+    use crate::db::BlockRootDb;
+    use crate::error::ErrorKind;
+    use crate::property_executor::PropertyExecutor;
+    use crate::types::Integer;
+    use bytes::Bytes;
+    use ethereum_types::Address;
+    use ethsign::SecretKey;
+    use plasma_db::impls::kvs::CoreDbLevelDbImpl;
+
+    struct Participant {
+        key: SecretKey,
+        decider: PropertyExecutor<CoreDbLevelDbImpl>,
+        channel: StateChannel,
+    }
+
+    fn open_channel() -> (Participant, Participant) {
+        let alice_key = SecretKey::from_raw(&[1; 32]).unwrap();
+        let bob_key = SecretKey::from_raw(&[2; 32]).unwrap();
+        let alice: Address = alice_key.public().address().into();
+        let bob: Address = bob_key.public().address().into();
+        let channel_id = Bytes::from("channel");
+        (
+            Participant {
+                key: alice_key,
+                decider: Default::default(),
+                channel: StateChannel::open(channel_id.clone(), alice, bob),
+            },
+            Participant {
+                key: bob_key,
+                decider: Default::default(),
+                channel: StateChannel::open(channel_id, bob, alice),
+            },
+        )
+    }
+
+    fn exchange(proposer: &mut Participant, acceptor: &mut Participant, state: &str) {
+        let proposed = proposer
+            .channel
+            .propose_update(&proposer.decider, &proposer.key, Bytes::from(state))
+            .unwrap();
+        let accepted = acceptor
+            .channel
+            .accept_update(&acceptor.decider, &acceptor.key, &proposed)
+            .unwrap();
+        assert!(proposer
+            .channel
+            .receive_update(&proposer.decider, &accepted)
+            .is_ok());
+    }
+
+    fn assert_closed(participant: &Participant, state: &str) {
+        match participant.channel.get_status() {
+            ChannelStatus::Closed(closed) => assert_eq!(closed, &Bytes::from(state)),
+            status => panic!("unexpected status {:?}", status),
+        }
+    }
+
+    #[test]
+    fn test_update_and_close_cooperatively() {
+        let (mut alice, mut bob) = open_channel();
+        exchange(&mut alice, &mut bob, "state1");
+        exchange(&mut bob, &mut alice, "state2");
+        for participant in [&alice, &bob].iter() {
+            let latest = participant
+                .channel
+                .get_latest_update(&participant.decider)
+                .unwrap()
+                .unwrap();
+            assert_eq!(latest.nonce, Integer(2));
+            assert_eq!(decode_update(&latest).unwrap().0, Bytes::from("state2"));
+        }
+        // the update of a stale nonce is refused
+        let stale = alice
+            .channel
+            .get_latest_update(&alice.decider)
+            .unwrap()
+            .unwrap();
+        match bob
+            .channel
+            .receive_update(&bob.decider, &stale)
+            .unwrap_err()
+            .kind()
+        {
+            ErrorKind::InvalidChannelUpdate => (),
+            kind => panic!("unexpected error {}", kind),
+        }
+        let proposed = alice
+            .channel
+            .propose_close(&alice.decider, &alice.key)
+            .unwrap();
+        let accepted = bob
+            .channel
+            .accept_update(&bob.decider, &bob.key, &proposed)
+            .unwrap();
+        assert_closed(&bob, "state2");
+        assert!(alice
+            .channel
+            .receive_update(&alice.decider, &accepted)
+            .is_ok());
+        assert_closed(&alice, "state2");
+    }
+
+    #[test]
+    fn test_exit_and_dispute() {
+        let (mut alice, mut bob) = open_channel();
+        exchange(&mut alice, &mut bob, "state1");
+        exchange(&mut bob, &mut alice, "state2");
+        // bob has signed the update after nonce 1, so the exit with it is contradicted
+        let contradiction = alice
+            .channel
+            .dispute(&alice.decider, Integer(1))
+            .unwrap()
+            .unwrap();
+        assert!(contradiction.verify().is_ok());
+        assert!(alice
+            .channel
+            .dispute(&alice.decider, Integer(2))
+            .unwrap()
+            .is_none());
+        // alice can't exit with nonce 2 after signing the proposal of nonce 3
+        assert!(alice
+            .channel
+            .propose_update(&alice.decider, &alice.key, Bytes::from("state3"))
+            .is_ok());
+        assert!(alice.channel.exit(&alice.decider, Integer(10)).is_err());
+        assert!(bob.channel.exit(&bob.decider, Integer(10)).is_ok());
+        // the exit can't be closed in the dispute period
+        match bob.channel.close_exit(&bob.decider).unwrap_err().kind() {
+            ErrorKind::InvalidChannelStatus => (),
+            kind => panic!("unexpected error {}", kind),
+        }
+        assert!(BlockRootDb::new(bob.decider.get_db())
+            .store_root(Integer(10), &Bytes::from(&b"root"[..]))
+            .is_ok());
+        assert!(bob.channel.close_exit(&bob.decider).is_ok());
+        assert_closed(&bob, "state2");
+    }
+
+}
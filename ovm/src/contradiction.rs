@@ -0,0 +1,407 @@
+use crate::error::{Error, ErrorKind};
+use crate::property_executor::PropertyExecutor;
+use crate::types::{Decision, ImplicationProofElement, Property, QuantifierResultItem, Witness};
+use crate::verifier::verify_implication;
+use bytes::Bytes;
+use ethabi::{ParamType, Token};
+use plasma_core::data_structure::abi::Encodable;
+use plasma_db::traits::kvs::KeyValueStore;
+use std::collections::HashMap;
+
+/// Two claimed properties which imply `P` and `Not(P)`, with arguments of
+/// `verifyContradictingImplications` and `proveUndecidedContradiction`.
+/// The contradiction witness is the index of the implication proof which ends with `Not`,
+/// encoded as `uint`.
+#[derive(Clone, Debug)]
+pub struct Contradiction {
+    root1: Property,
+    implication_proof1: Vec<ImplicationProofElement>,
+    root2: Property,
+    implication_proof2: Vec<ImplicationProofElement>,
+    contradiction_witness: Bytes,
+}
+
+impl Contradiction {
+    pub fn get_root1(&self) -> &Property {
+        &self.root1
+    }
+    pub fn get_implication_proof1(&self) -> &Vec<ImplicationProofElement> {
+        &self.implication_proof1
+    }
+    pub fn get_root2(&self) -> &Property {
+        &self.root2
+    }
+    pub fn get_implication_proof2(&self) -> &Vec<ImplicationProofElement> {
+        &self.implication_proof2
+    }
+    pub fn get_contradiction_witness(&self) -> &Bytes {
+        &self.contradiction_witness
+    }
+    /// Verifies both implication proofs and that the last implications contradict
+    pub fn verify(&self) -> Result<(), Error> {
+        verify_implication(&self.root1, &self.implication_proof1)?;
+        verify_implication(&self.root2, &self.implication_proof2)?;
+        let index = decode_index(&self.contradiction_witness)?;
+        let last1 = self.implication_proof1.last().map(|e| e.get_implication());
+        let last2 = self.implication_proof2.last().map(|e| e.get_implication());
+        match (last1, last2) {
+            (Some(last1), Some(last2)) if contradiction_index(last1, last2) == Some(index) => {
+                Ok(())
+            }
+            _ => Err(Error::from(ErrorKind::InvalidContradiction)),
+        }
+    }
+}
+
+/// Implications of each claim which the first round of the search walks
+const INITIAL_SEARCH_LIMIT: usize = 64;
+
+/// Searches implications of two claims for `P` and `Not(P)`.
+/// `And` and `ForAllSuchThat` imply all of their properties, while `Or` and
+/// `ThereExistsSuchThat` only imply the properties which are decided true locally.
+/// Each round indexes up to a limit of implications of the first claim, then walks as many
+/// implications of the second claim, and the limit doubles until a contradiction is found
+/// or both claims are walked through. Quantified results are fetched lazily, so neither
+/// claim is walked much further than the contradiction.
+/// Returns `None` if the claims don't contradict as far as the local decisions go.
+pub fn find_contradiction<T: KeyValueStore>(
+    decider: &PropertyExecutor<T>,
+    claim1: &Property,
+    claim2: &Property,
+) -> Result<Option<Contradiction>, Error> {
+    let mut limit = INITIAL_SEARCH_LIMIT;
+    loop {
+        let (found, complete) = search_contradiction(decider, claim1, claim2, limit)?;
+        if let Some((proof1, proof2, index)) = found {
+            return Ok(Some(Contradiction {
+                root1: claim1.clone(),
+                implication_proof1: proof1,
+                root2: claim2.clone(),
+                implication_proof2: proof2,
+                contradiction_witness: Bytes::from(ethabi::encode(&[Token::Uint(index.into())])),
+            }));
+        }
+        if complete {
+            return Ok(None);
+        }
+        limit = limit.saturating_mul(2);
+    }
+}
+
+/// Both implication proofs and the contradiction index
+type ContradictingProofs = (
+    Vec<ImplicationProofElement>,
+    Vec<ImplicationProofElement>,
+    u64,
+);
+
+/// Searches up to `limit` implications of each claim,
+/// and returns whether both claims have no more implications
+fn search_contradiction<T: KeyValueStore>(
+    decider: &PropertyExecutor<T>,
+    claim1: &Property,
+    claim2: &Property,
+    limit: usize,
+) -> Result<(Option<ContradictingProofs>, bool), Error> {
+    // proofs of the first claim by the last implication, and by the property negated by it
+    let mut implied: HashMap<Vec<u8>, Vec<ImplicationProofElement>> = HashMap::new();
+    let mut negated: HashMap<Vec<u8>, Vec<ImplicationProofElement>> = HashMap::new();
+    let mut count = 0;
+    let truncated1 = walk(
+        decider,
+        &mut vec![],
+        claim1.clone(),
+        &mut |proof1: &[ImplicationProofElement]| -> Option<()> {
+            if count == limit {
+                return Some(());
+            }
+            count += 1;
+            let last1 = proof1[proof1.len() - 1].get_implication();
+            if let Property::NotDecider(not) = last1 {
+                negated
+                    .entry(not.get_property().to_abi())
+                    .or_insert_with(|| proof1.to_vec());
+            }
+            implied
+                .entry(last1.to_abi())
+                .or_insert_with(|| proof1.to_vec());
+            None
+        },
+    )?
+    .is_some();
+    let mut count = 0;
+    // `Some(None)` stops the walk at the limit
+    let found = walk(
+        decider,
+        &mut vec![],
+        claim2.clone(),
+        &mut |proof2: &[ImplicationProofElement]| {
+            if count == limit {
+                return Some(None);
+            }
+            count += 1;
+            let last2 = proof2[proof2.len() - 1].get_implication();
+            if let Some(proof1) = negated.get(&last2.to_abi()) {
+                return Some(Some((proof1.clone(), proof2.to_vec(), 0u64)));
+            }
+            match last2 {
+                Property::NotDecider(not) => implied
+                    .get(&not.get_property().to_abi())
+                    .map(|proof1| Some((proof1.clone(), proof2.to_vec(), 1))),
+                _ => None,
+            }
+        },
+    )?;
+    Ok(match found {
+        Some(Some(found)) => (Some(found), false),
+        Some(None) => (None, false),
+        None => (None, !truncated1),
+    })
+}
+
+/// Visits the proof which ends with the property, then proofs of its implications,
+/// until `visit` returns `Some`. `chain` is the proof of the premise of the property.
+fn walk<T, R, F>(
+    decider: &PropertyExecutor<T>,
+    chain: &mut Vec<ImplicationProofElement>,
+    property: Property,
+    visit: &mut F,
+) -> Result<Option<R>, Error>
+where
+    T: KeyValueStore,
+    F: FnMut(&[ImplicationProofElement]) -> Option<R>,
+{
+    chain.push(ImplicationProofElement::new(property.clone(), None));
+    let found = match visit(chain) {
+        Some(found) => Some(found),
+        None => walk_implications(decider, chain, &property, visit)?,
+    };
+    chain.pop();
+    Ok(found)
+}
+
+/// Walks implications of the property, the last element of `chain`.
+/// Quantified results are fetched lazily, so the rest isn't fetched once `visit` returns `Some`.
+fn walk_implications<T, R, F>(
+    decider: &PropertyExecutor<T>,
+    chain: &mut Vec<ImplicationProofElement>,
+    property: &Property,
+    visit: &mut F,
+) -> Result<Option<R>, Error>
+where
+    T: KeyValueStore,
+    F: FnMut(&[ImplicationProofElement]) -> Option<R>,
+{
+    match property {
+        Property::AndDecider(input) => {
+            for side in [input.get_left(), input.get_right()].iter() {
+                if let Some(found) = walk_implication(decider, chain, None, (*side).clone(), visit)?
+                {
+                    return Ok(Some(found));
+                }
+            }
+        }
+        Property::OrDecider(input) => {
+            let sides = [
+                (input.get_left(), input.get_left_witness()),
+                (input.get_right(), input.get_right_witness()),
+            ];
+            for (index, (side, witness)) in sides.iter().enumerate() {
+                if !is_decided_true(decider.try_decide(side, Some((*witness).clone()))?) {
+                    continue;
+                }
+                let index = Bytes::from(ethabi::encode(&[Token::Uint((index as u64).into())]));
+                let witness = Some(Witness::Bytes(index));
+                if let Some(found) =
+                    walk_implication(decider, chain, witness, (*side).clone(), visit)?
+                {
+                    return Ok(Some(found));
+                }
+            }
+        }
+        Property::ForAllSuchThatDecider(input) => {
+            for item in decider.iter_quantified(input.get_quantifier()) {
+                let item = item?;
+                let bound = input
+                    .get_variable()
+                    .bind_property(input.get_property_template(), &item)?;
+                let witness = Some(encode_item(item));
+                if let Some(found) = walk_implication(decider, chain, witness, bound, visit)? {
+                    return Ok(Some(found));
+                }
+            }
+        }
+        Property::ThereExistsSuchThatDecider(input) => {
+            for item in decider.iter_quantified(input.get_quantifier()) {
+                let item = item?;
+                let (bound, decision) = decider.decide_bound(
+                    input.get_variable(),
+                    input.get_property_template(),
+                    input.get_witness_template(),
+                    &item,
+                )?;
+                if !is_decided_true(decision) {
+                    continue;
+                }
+                let witness = Some(encode_item(item));
+                if let Some(found) = walk_implication(decider, chain, witness, bound, visit)? {
+                    return Ok(Some(found));
+                }
+            }
+        }
+        _ => (),
+    }
+    Ok(None)
+}
+
+/// Walks the implication, the witness of the premise selects it
+fn walk_implication<T, R, F>(
+    decider: &PropertyExecutor<T>,
+    chain: &mut Vec<ImplicationProofElement>,
+    witness: Option<Witness>,
+    implication: Property,
+    visit: &mut F,
+) -> Result<Option<R>, Error>
+where
+    T: KeyValueStore,
+    F: FnMut(&[ImplicationProofElement]) -> Option<R>,
+{
+    let premise = chain.pop().expect("the premise is in the chain");
+    chain.push(ImplicationProofElement::new(
+        premise.get_implication().clone(),
+        witness,
+    ));
+    walk(decider, chain, implication, visit)
+}
+
+fn is_decided_true(decision: Option<Decision>) -> bool {
+    decision.map_or(false, |decision| decision.get_outcome())
+}
+
+fn encode_item(item: QuantifierResultItem) -> Witness {
+    Witness::Bytes(Bytes::from(ethabi::encode(&[Token::from(item)])))
+}
+
+/// Returns the index of the property which is `Not` of the other
+fn contradiction_index(a: &Property, b: &Property) -> Option<u64> {
+    match (a, b) {
+        (Property::NotDecider(not), _) if not.get_property().to_abi() == b.to_abi() => Some(0),
+        (_, Property::NotDecider(not)) if not.get_property().to_abi() == a.to_abi() => Some(1),
+        _ => None,
+    }
+}
+
+fn decode_index(witness: &Bytes) -> Result<u64, Error> {
+    if let Ok(mut tokens) = ethabi::decode(&[ParamType::Uint(256)], witness) {
+        if let Token::Uint(index) = tokens.remove(0) {
+            if index < 2.into() {
+                return Ok(index.low_u64());
+            }
+        }
+    }
+    Err(Error::from(ErrorKind::InvalidContradiction))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::find_contradiction;
+    use crate::dsl::parse_property;
+    use crate::property_executor::PropertyExecutor;
+    use crate::types::Property;
+    use plasma_core::data_structure::abi::Encodable;
+    use plasma_db::impls::kvs::CoreDbLevelDbImpl;
+
+    const MESSAGE: &str = "Message(\"channel\", 0x0000000000000000000000000000000000000000, \
+                           0x0000000000000000000000000000000000000000, 3, [], 0x, 0x)";
+
+    fn parse(source: &str) -> Property {
+        parse_property(&source.replace("{m}", MESSAGE)).unwrap()
+    }
+
+    #[test]
+    fn test_find_contradiction_of_for_all() {
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let claim1 = parse("And(HasLowerNonce({m}, 6), HasLowerNonce({m}, 5))");
+        let claim2 = parse("ForAll n in 4..6: Not(HasLowerNonce({m}, n))");
+        let contradiction = find_contradiction(&decider, &claim1, &claim2)
+            .unwrap()
+            .unwrap();
+        assert!(contradiction.verify().is_ok());
+        let proof1 = contradiction.get_implication_proof1();
+        let proof2 = contradiction.get_implication_proof2();
+        assert_eq!(proof1.len(), 2);
+        assert_eq!(proof2.len(), 2);
+        assert_eq!(
+            proof1[1].get_implication().to_abi(),
+            parse("HasLowerNonce({m}, 5)").to_abi()
+        );
+        assert_eq!(
+            proof2[1].get_implication().to_abi(),
+            parse("Not(HasLowerNonce({m}, 5))").to_abi()
+        );
+        // swapping the proofs makes the witness invalid
+        let mut swapped = contradiction.clone();
+        std::mem::swap(&mut swapped.root1, &mut swapped.root2);
+        std::mem::swap(
+            &mut swapped.implication_proof1,
+            &mut swapped.implication_proof2,
+        );
+        assert!(swapped.verify().is_err());
+    }
+
+    #[test]
+    fn test_find_contradiction_with_local_decisions() {
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        // the nonce of the message is 3, so only the right side of Or is true
+        let claim = parse("Or(HasLowerNonce({m}, 1), HasLowerNonce({m}, 5))");
+        let contradiction =
+            find_contradiction(&decider, &parse("Not(HasLowerNonce({m}, 5))"), &claim)
+                .unwrap()
+                .unwrap();
+        assert!(contradiction.verify().is_ok());
+        assert!(
+            find_contradiction(&decider, &parse("Not(HasLowerNonce({m}, 1))"), &claim)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_find_contradiction_in_large_domain() {
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        // the search stops at the first contradiction instead of quantifying all results
+        let claim = parse("ForAll n < 1000000000000: Not(HasLowerNonce({m}, n))");
+        let other = parse("HasLowerNonce({m}, 5)");
+        let contradiction = find_contradiction(&decider, &other, &claim)
+            .unwrap()
+            .unwrap();
+        assert!(contradiction.verify().is_ok());
+        assert_eq!(
+            contradiction.get_implication_proof2()[1]
+                .get_implication()
+                .to_abi(),
+            parse("Not(HasLowerNonce({m}, 5))").to_abi()
+        );
+        // the large domain is on the indexed side
+        let contradiction = find_contradiction(&decider, &claim, &other)
+            .unwrap()
+            .unwrap();
+        assert!(contradiction.verify().is_ok());
+        assert_eq!(
+            contradiction.get_implication_proof1()[1]
+                .get_implication()
+                .to_abi(),
+            parse("Not(HasLowerNonce({m}, 5))").to_abi()
+        );
+        // the contradiction is past the first round of the search
+        let late = parse("ForAll n in 100..1000000000000: HasLowerNonce({m}, n)");
+        for (claim1, claim2) in [(&late, &claim), (&claim, &late)].iter() {
+            let contradiction = find_contradiction(&decider, claim1, claim2)
+                .unwrap()
+                .unwrap();
+            assert!(contradiction.verify().is_ok());
+        }
+    }
+
+}
@@ -1,5 +1,7 @@
+pub mod block_root_db;
 pub mod hash_preimage_db;
 pub mod message_db;
 
+pub use self::block_root_db::BlockRootDb;
 pub use self::hash_preimage_db::HashPreimageDb;
 pub use self::message_db::{Message, MessageDb};
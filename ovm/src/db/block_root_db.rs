@@ -0,0 +1,34 @@
+use crate::error::Error;
+use crate::types::Integer;
+use bytes::Bytes;
+use plasma_db::traits::kvs::{BaseDbKey, KeyValueStore};
+
+/// Stores merkle roots of plasma blocks which are submitted to the commitment contract.
+/// Nothing in this crate watches the contract, so the caller must store each root
+/// when it observes the commitment. Until then `IncludedInIntervalTreeAtBlock` is
+/// undecided and `BlockDeadlineDecider` is false for the block.
+pub struct BlockRootDb<'a, KVS: KeyValueStore> {
+    db: &'a KVS,
+}
+
+impl<'a, KVS: KeyValueStore> BlockRootDb<'a, KVS> {
+    pub fn new(db: &'a KVS) -> Self {
+        Self { db }
+    }
+    /// Stores the root of a submitted block, called on each observed commitment
+    pub fn store_root(&self, block_number: Integer, root: &Bytes) -> Result<(), Error> {
+        let block_number: Bytes = block_number.into();
+        self.db
+            .bucket(&BaseDbKey::from(&b"block_root_db"[..]))
+            .put(&block_number.into(), root)
+            .map_err::<Error, _>(Into::into)
+    }
+    pub fn get_root(&self, block_number: Integer) -> Result<Option<Bytes>, Error> {
+        let block_number: Bytes = block_number.into();
+        self.db
+            .bucket(&BaseDbKey::from(&b"block_root_db"[..]))
+            .get(&block_number.into())
+            .map(|root| root.map(Bytes::from))
+            .map_err::<Error, _>(Into::into)
+    }
+}
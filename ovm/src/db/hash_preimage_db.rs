@@ -1,4 +1,4 @@
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 use crate::types::Witness;
 use ethereum_types::H256;
 use plasma_core::data_structure::abi::{Decodable, Encodable};
@@ -14,20 +14,18 @@ impl<'a, KVS: KeyValueStore> HashPreimageDb<'a, KVS> {
     }
     pub fn store_witness(&self, hash: H256, preimage: &Witness) -> Result<(), Error> {
         self.db
-            .bucket(&BaseDbKey::from(&b"preimage_exists_decider"[..]))
+            .bucket(&BaseDbKey::from(&b"hash_preimage_db"[..]))
             .put(&BaseDbKey::from(hash.as_bytes()), &preimage.to_abi())
             .map_err::<Error, _>(Into::into)
     }
     pub fn get_witness(&self, hash: H256) -> Result<Witness, Error> {
         let result = self
             .db
-            .bucket(&BaseDbKey::from(&b"preimage_exists_decider"[..]))
+            .bucket(&BaseDbKey::from(&b"hash_preimage_db"[..]))
             .get(&BaseDbKey::from(hash.as_bytes()))
             .map_err::<Error, _>(Into::into)?;
-        if result.is_none() {
-            panic!("preimage not found");
-        }
-        Witness::from_abi(&result.unwrap()).map_err::<Error, _>(Into::into)
+        let result = result.ok_or_else(|| Error::from(ErrorKind::MissingPreimage))?;
+        Witness::from_abi(&result).map_err::<Error, _>(Into::into)
     }
 }
 
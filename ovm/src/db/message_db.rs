@@ -1,108 +1,150 @@
-use crate::types::Integer;
+use crate::deciders::signed_by_decider::{hash, Verifier};
+use crate::error::{Error, ErrorKind};
+use crate::types::{Integer, Placeholder, Variable};
+use abi_derive::{AbiDecodable, AbiEncodable, AbiSchema};
 use bytes::Bytes;
-use ethabi::{ParamType, Token};
+use ethabi::Token;
 use ethereum_types::Address;
+use ethsign::SecretKey;
 use plasma_core::data_structure::abi::{Decodable, Encodable};
-use plasma_core::data_structure::error::{
-    Error as PlasmaCoreError, ErrorKind as PlasmaCoreErrorKind,
-};
-use plasma_db::traits::kvs::KeyValueStore;
+use plasma_db::traits::kvs::{BaseDbKey, Batch, KeyValue, KeyValueStore};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 pub struct Message {
+    #[cfg_attr(
+        feature = "json",
+        serde(with = "plasma_core::data_structure::serde_hex")
+    )]
     pub channel_id: Bytes,
     sender: Address,
     recipient: Address,
     pub nonce: Integer,
     signers: Vec<Address>,
+    #[cfg_attr(
+        feature = "json",
+        serde(with = "plasma_core::data_structure::serde_hex")
+    )]
     message: Bytes,
+    #[cfg_attr(
+        feature = "json",
+        serde(with = "plasma_core::data_structure::serde_hex")
+    )]
     signed_message: Bytes,
 }
 
 impl Message {
+    /// Creates the message which isn't signed by anyone yet
+    pub fn new(
+        channel_id: Bytes,
+        sender: Address,
+        recipient: Address,
+        nonce: Integer,
+        message: Bytes,
+    ) -> Self {
+        Message {
+            channel_id,
+            sender,
+            recipient,
+            nonce,
+            signers: vec![],
+            message,
+            signed_message: Bytes::new(),
+        }
+    }
+    pub fn get_sender(&self) -> Address {
+        self.sender
+    }
+    pub fn get_recipient(&self) -> Address {
+        self.recipient
+    }
     pub fn get_signers(&self) -> &Vec<Address> {
         &self.signers
     }
-}
-
-impl Encodable for Message {
-    fn to_abi(&self) -> Vec<u8> {
-        ethabi::encode(&self.to_tuple())
+    pub fn get_message(&self) -> &Bytes {
+        &self.message
     }
-    fn to_tuple(&self) -> Vec<Token> {
-        vec![
+    pub fn get_signed_message(&self) -> &Bytes {
+        &self.signed_message
+    }
+    /// Signers sign the channel and the nonce as well as the body,
+    /// so signatures can't be replayed for other updates
+    pub fn get_signing_payload(&self) -> Bytes {
+        Bytes::from(ethabi::encode(&[
             Token::Bytes(self.channel_id.to_vec()),
             Token::Address(self.sender),
             Token::Address(self.recipient),
             Token::Uint(self.nonce.0.into()),
-            Token::Array(self.signers.iter().map(|s| Token::Address(*s)).collect()),
             Token::Bytes(self.message.to_vec()),
-            Token::Bytes(self.signed_message.to_vec()),
-        ]
+        ]))
+    }
+    /// Adds the signer of the key and appends the signature to `signed_message`
+    pub fn sign(&mut self, key: &SecretKey) {
+        let signature = Verifier::sign(key, &self.get_signing_payload());
+        self.signers.push(key.public().address().into());
+        let mut signed_message = self.signed_message.to_vec();
+        signed_message.extend_from_slice(&signature);
+        self.signed_message = Bytes::from(signed_message);
+    }
+    /// `signed_message` must be the signatures of `signers` in the same order
+    pub fn verify_signatures(&self) -> Result<(), Error> {
+        if self.signed_message.len() != SIGNATURE_LENGTH * self.signers.len() {
+            return Err(Error::from(ErrorKind::InvalidSignature));
+        }
+        let payload = self.get_signing_payload();
+        let signatures = self.signed_message.chunks(SIGNATURE_LENGTH);
+        for (signer, signature) in self.signers.iter().zip(signatures) {
+            let recovered = Verifier::recover(&Bytes::from(signature), &payload)
+                .map_err(|_| Error::from(ErrorKind::InvalidSignature))?;
+            if recovered != *signer {
+                return Err(Error::from(ErrorKind::InvalidSignature));
+            }
+        }
+        Ok(())
+    }
+    /// Adds signatures of the other message with the same content, whose signatures are verified.
+    /// Messages with different content can't be merged.
+    pub fn merge(&self, other: &Message) -> Result<Message, Error> {
+        if self.channel_id != other.channel_id
+            || self.sender != other.sender
+            || self.recipient != other.recipient
+            || self.nonce != other.nonce
+            || self.message != other.message
+        {
+            return Err(Error::from(ErrorKind::ConflictingMessage));
+        }
+        let mut merged = self.clone();
+        let mut signed_message = merged.signed_message.to_vec();
+        let signatures = other.signed_message.chunks(SIGNATURE_LENGTH);
+        for (signer, signature) in other.signers.iter().zip(signatures) {
+            if !merged.signers.contains(signer) {
+                merged.signers.push(*signer);
+                signed_message.extend_from_slice(signature);
+            }
+        }
+        merged.signed_message = Bytes::from(signed_message);
+        Ok(merged)
     }
 }
 
-impl Decodable for Message {
-    type Ok = Message;
-    fn from_tuple(tuple: &[Token]) -> Result<Self, PlasmaCoreError> {
-        let channel_id = tuple[0].clone().to_bytes();
-        let sender = tuple[1].clone().to_address();
-        let recipient = tuple[2].clone().to_address();
-        let nonce = tuple[3].clone().to_uint();
-        let signers = tuple[4].clone().to_array();
-        let message = tuple[5].clone().to_bytes();
-        let signed_message = tuple[6].clone().to_bytes();
-        if let (
-            Some(channel_id),
-            Some(sender),
-            Some(recipient),
-            Some(nonce),
-            Some(signers),
-            Some(message),
-            Some(signed_message),
-        ) = (
-            channel_id,
-            sender,
-            recipient,
-            nonce,
-            signers,
-            message,
-            signed_message,
-        ) {
-            Ok(Message {
-                channel_id: Bytes::from(channel_id),
-                sender,
-                recipient,
-                nonce: Integer(nonce.as_u64()),
-                signers: signers
-                    .iter()
-                    .map(|s| s.clone().to_address().unwrap())
-                    .collect(),
-                message: Bytes::from(message),
-                signed_message: Bytes::from(signed_message),
-            })
-        } else {
-            Err(PlasmaCoreError::from(PlasmaCoreErrorKind::AbiDecode))
+impl Placeholder for Message {
+    fn placeholder(variable: &Variable) -> Self {
+        Message {
+            channel_id: variable.placeholder::<Bytes>(),
+            sender: Address::zero(),
+            recipient: Address::zero(),
+            nonce: Integer(0),
+            signers: vec![],
+            message: Bytes::new(),
+            signed_message: Bytes::new(),
         }
     }
-    fn from_abi(data: &[u8]) -> Result<Self, PlasmaCoreError> {
-        let decoded = ethabi::decode(
-            &[
-                ParamType::Bytes,
-                ParamType::Address,
-                ParamType::Address,
-                ParamType::Uint(256),
-                ParamType::Array(Box::new(ParamType::Address)),
-                ParamType::Bytes,
-                ParamType::Bytes,
-            ],
-            data,
-        )
-        .map_err(|_e| PlasmaCoreError::from(PlasmaCoreErrorKind::AbiDecode))?;
-        Self::from_tuple(&decoded)
-    }
 }
 
+/// Stores messages by the channel and the nonce, with the index by signers.
+/// Signatures of messages with the same channel and nonce are merged,
+/// and a message which conflicts with the stored one is rejected.
 pub struct MessageDb<KVS> {
     db: KVS,
 }
@@ -111,34 +153,135 @@ impl<KVS> MessageDb<KVS>
 where
     KVS: KeyValueStore,
 {
+    /// Stores the message after verifying signatures of all signers
+    pub fn store_message(&self, message: &Message) -> Result<(), Error> {
+        message.verify_signatures()?;
+        let key = message_key(&message.channel_id, Some(message.nonce));
+        let message = match self.get_message(&key)? {
+            Some(stored) => stored.merge(message)?,
+            None => message.clone(),
+        };
+        let mut operations = vec![Batch::new_put(
+            BaseDbKey::from(MESSAGES).concat(&key),
+            &message.to_abi(),
+        )];
+        for signer in message.get_signers() {
+            operations.push(Batch::new_put(
+                signer_key(signer).concat(&key),
+                key.as_bytes(),
+            ));
+        }
+        self.db.batch(&operations).map_err::<Error, _>(Into::into)
+    }
     pub fn get_message_by_channel_id_and_nonce(
         &self,
         channel_id: Bytes,
         nonce: Integer,
-    ) -> Option<Message> {
-        let nonce_bytes: Bytes = nonce.into();
-        self.db
-            .bucket(&channel_id.into())
-            .get(&nonce_bytes.into())
-            .ok()
-            .unwrap()
-            .map(|b| Message::from_abi(&b).ok().unwrap())
+    ) -> Result<Option<Message>, Error> {
+        self.get_message(&message_key(&channel_id, Some(nonce)))
     }
+    /// Returns messages of the channel in the order of nonces
+    pub fn get_messages_by_channel_id(&self, channel_id: &Bytes) -> Result<Vec<Message>, Error> {
+        let prefix = BaseDbKey::from(MESSAGES).concat(&message_key(channel_id, None));
+        self.scan(&prefix, None, usize::max_value())
+            .iter()
+            .map(|kv| Message::from_abi(kv.get_value()).map_err::<Error, _>(Into::into))
+            .collect()
+    }
+    /// Returns messages signed by `signer`, which are filtered by the channel and the nonce
+    /// if they are given
     pub fn get_messages_signed_by(
         &self,
         signer: Address,
-        _channel_id: Option<Bytes>,
-        _nonce: Option<Integer>,
-    ) -> Vec<Message> {
+        channel_id: Option<Bytes>,
+        nonce: Option<Integer>,
+    ) -> Result<Vec<Message>, Error> {
+        let prefix = match &channel_id {
+            Some(channel_id) => signer_key(&signer).concat(&message_key(channel_id, nonce)),
+            None => signer_key(&signer),
+        };
+        let kvs = self.scan(&prefix, None, usize::max_value());
+        let messages = self.get_indexed_messages(&kvs)?;
+        Ok(messages
+            .into_iter()
+            .filter(|message| nonce.map_or(true, |nonce| message.nonce == nonce))
+            .collect())
+    }
+    /// Reads at most `limit` messages signed by `signer` after the key `from`, and returns them
+    /// with the last key which is read. The key is `None` if no messages are left.
+    pub fn get_messages_signed_by_page(
+        &self,
+        signer: Address,
+        from: Option<&Bytes>,
+        limit: usize,
+    ) -> Result<(Vec<Message>, Option<Bytes>), Error> {
+        let from = from.map(|key| BaseDbKey::from(key.clone()));
+        let kvs = self.scan(&signer_key(&signer), from.as_ref(), limit);
+        let last_key = if kvs.len() == limit {
+            kvs.last().map(|kv| Bytes::from(kv.get_key().as_bytes()))
+        } else {
+            None
+        };
+        Ok((self.get_indexed_messages(&kvs)?, last_key))
+    }
+    fn get_message(&self, key: &BaseDbKey) -> Result<Option<Message>, Error> {
         self.db
-            .iter_all(&Bytes::from("").into(), Box::new(move |_k, _v| true))
-            .iter()
-            .filter_map(|kv| Message::from_abi(kv.get_value()).ok())
-            .filter(|message| message.get_signers().contains(&signer))
+            .get(&BaseDbKey::from(MESSAGES).concat(key))
+            .map_err::<Error, _>(Into::into)?
+            .map(|b| Message::from_abi(&b).map_err::<Error, _>(Into::into))
+            .transpose()
+    }
+    /// Reads messages which entries of the index point to
+    fn get_indexed_messages(&self, kvs: &[KeyValue]) -> Result<Vec<Message>, Error> {
+        let mut messages = vec![];
+        for kv in kvs.iter() {
+            if let Some(message) = self.get_message(&BaseDbKey::from(&kv.get_value()[..]))? {
+                messages.push(message);
+            }
+        }
+        Ok(messages)
+    }
+    /// Reads at most `limit` entries whose keys start with `prefix` after the key `from`
+    fn scan(&self, prefix: &BaseDbKey, from: Option<&BaseDbKey>, limit: usize) -> Vec<KeyValue> {
+        let start = from.unwrap_or(prefix).clone();
+        let skipped = from.cloned();
+        let prefix = prefix.as_bytes().to_vec();
+        let mut count = 0;
+        // stores may or may not return the key which iteration starts from
+        self.db
+            .iter_all(
+                &start,
+                Box::new(move |k, _v| {
+                    if Some(k) == skipped.as_ref() {
+                        return true;
+                    }
+                    count += 1;
+                    k.as_bytes().starts_with(&prefix) && count <= limit
+                }),
+            )
+            .into_iter()
+            .filter(|kv| Some(kv.get_key()) != from)
             .collect()
     }
 }
 
+const SIGNATURE_LENGTH: usize = 65;
+const MESSAGES: &[u8] = b"messages";
+const SIGNERS: &[u8] = b"signers";
+
+/// The hash of the channel followed by the nonce, so messages of a channel are sorted by nonces
+fn message_key(channel_id: &Bytes, nonce: Option<Integer>) -> BaseDbKey {
+    let mut key = hash(channel_id).as_bytes().to_vec();
+    if let Some(nonce) = nonce {
+        key.extend_from_slice(&nonce.0.to_be_bytes());
+    }
+    BaseDbKey::new(key)
+}
+
+fn signer_key(signer: &Address) -> BaseDbKey {
+    BaseDbKey::from(SIGNERS).concat(&BaseDbKey::from(signer.as_bytes()))
+}
+
 impl<KVS> From<KVS> for MessageDb<KVS>
 where
     KVS: KeyValueStore,
@@ -147,3 +290,147 @@ where
         Self { db: kvs }
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::{Message, MessageDb};
+    use crate::error::ErrorKind;
+    use crate::types::Integer;
+    use bytes::Bytes;
+    use ethereum_types::Address;
+    use ethsign::SecretKey;
+    use plasma_db::impls::kvs::CoreDbLevelDbImpl;
+    use plasma_db::traits::db::DatabaseTrait;
+
+    fn create_message(channel_id: &str, nonce: u64, keys: &[&SecretKey]) -> Message {
+        let mut message = Message::new(
+            Bytes::from(channel_id),
+            Address::zero(),
+            Address::zero(),
+            Integer(nonce),
+            Bytes::from("update"),
+        );
+        for key in keys {
+            message.sign(key);
+        }
+        message
+    }
+
+    #[test]
+    fn test_store_message_verifies_signatures() {
+        let db = MessageDb::from(CoreDbLevelDbImpl::open("test"));
+        let key = SecretKey::from_raw(&[1; 32]).unwrap();
+        let message = create_message("channel", 1, &[&key]);
+        assert!(message.verify_signatures().is_ok());
+        assert!(db.store_message(&message).is_ok());
+        // the signature doesn't cover other nonces
+        let mut replayed = message.clone();
+        replayed.nonce = Integer(2);
+        let mut unsigned = create_message("channel", 3, &[]);
+        unsigned.signers.push(key.public().address().into());
+        for message in [replayed, unsigned].iter() {
+            match db.store_message(message).unwrap_err().kind() {
+                ErrorKind::InvalidSignature => (),
+                kind => panic!("unexpected error {}", kind),
+            }
+        }
+        let stored = db.get_message_by_channel_id_and_nonce(Bytes::from("channel"), Integer(2));
+        assert!(stored.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_get_messages_by_indexes() {
+        let db = MessageDb::from(CoreDbLevelDbImpl::open("test"));
+        let alice_key = SecretKey::from_raw(&[1; 32]).unwrap();
+        let bob_key = SecretKey::from_raw(&[2; 32]).unwrap();
+        let alice: Address = alice_key.public().address().into();
+        let bob: Address = bob_key.public().address().into();
+        for message in [
+            create_message("a", 256, &[&alice_key, &bob_key]),
+            create_message("a", 1, &[&alice_key]),
+            create_message("b", 1, &[&alice_key]),
+        ]
+        .iter()
+        {
+            assert!(db.store_message(message).is_ok());
+        }
+        let nonces: Vec<u64> = db
+            .get_messages_by_channel_id(&Bytes::from("a"))
+            .unwrap()
+            .iter()
+            .map(|message| message.nonce.0)
+            .collect();
+        assert_eq!(nonces, vec![1, 256]);
+        let count = |signer, channel_id: Option<&str>, nonce: Option<u64>| {
+            db.get_messages_signed_by(signer, channel_id.map(Bytes::from), nonce.map(Integer))
+                .unwrap()
+                .len()
+        };
+        assert_eq!(count(alice, None, None), 3);
+        assert_eq!(count(alice, Some("a"), None), 2);
+        assert_eq!(count(alice, Some("a"), Some(256)), 1);
+        assert_eq!(count(alice, None, Some(1)), 2);
+        assert_eq!(count(bob, None, None), 1);
+        // signatures of the same message are merged
+        assert!(db
+            .store_message(&create_message("b", 1, &[&bob_key]))
+            .is_ok());
+        assert_eq!(count(bob, None, None), 2);
+        assert_eq!(count(alice, None, None), 3);
+        let merged = db
+            .get_message_by_channel_id_and_nonce(Bytes::from("b"), Integer(1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(merged.get_signers(), &vec![alice, bob]);
+        assert!(merged.verify_signatures().is_ok());
+    }
+
+    #[test]
+    fn test_store_conflicting_message() {
+        let db = MessageDb::from(CoreDbLevelDbImpl::open("test"));
+        let alice_key = SecretKey::from_raw(&[1; 32]).unwrap();
+        let bob_key = SecretKey::from_raw(&[2; 32]).unwrap();
+        assert!(db
+            .store_message(&create_message("channel", 1, &[&alice_key]))
+            .is_ok());
+        let mut conflicting = Message::new(
+            Bytes::from("channel"),
+            Address::zero(),
+            Address::zero(),
+            Integer(1),
+            Bytes::from("other update"),
+        );
+        conflicting.sign(&bob_key);
+        match db.store_message(&conflicting).unwrap_err().kind() {
+            ErrorKind::ConflictingMessage => (),
+            kind => panic!("unexpected error {}", kind),
+        }
+        let stored = db
+            .get_message_by_channel_id_and_nonce(Bytes::from("channel"), Integer(1))
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.get_message(), &Bytes::from("update"));
+        assert_eq!(stored.get_signers().len(), 1);
+    }
+
+    #[test]
+    fn test_get_messages_signed_by_page() {
+        let db = MessageDb::from(CoreDbLevelDbImpl::open("test"));
+        let key = SecretKey::from_raw(&[1; 32]).unwrap();
+        for nonce in 0..3 {
+            assert!(db
+                .store_message(&create_message("channel", nonce, &[&key]))
+                .is_ok());
+        }
+        let signer: Address = key.public().address().into();
+        let (messages, cursor) = db.get_messages_signed_by_page(signer, None, 2).unwrap();
+        assert_eq!(messages.len(), 2);
+        let (messages, cursor) = db
+            .get_messages_signed_by_page(signer, cursor.as_ref(), 2)
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].nonce, Integer(2));
+        assert!(cursor.is_none());
+    }
+}
@@ -0,0 +1,228 @@
+use crate::error::{Error, ErrorKind};
+use crate::property_executor::PropertyExecutor;
+use crate::types::core::DeciderId;
+use crate::types::{Decider, Decision, Witness};
+use plasma_core::data_structure::abi::Decodable;
+use plasma_db::traits::kvs::KeyValueStore;
+use std::collections::HashMap;
+
+type DecideFn<KVS> = fn(&PropertyExecutor<KVS>, &[u8], Option<Witness>) -> Result<Decision, Error>;
+type CheckDecisionFn<KVS> = fn(&PropertyExecutor<KVS>, &[u8]) -> Result<Decision, Error>;
+
+/// A decider whose input is decoded from ABI before deciding
+struct DeciderEntry<KVS: KeyValueStore> {
+    decide: DecideFn<KVS>,
+    check_decision: CheckDecisionFn<KVS>,
+    is_cacheable: bool,
+}
+
+/// Deciders which are registered at runtime, keyed by decider ID.
+/// `PropertyExecutor` dispatches `Property::CustomDecider` through this registry.
+pub struct DeciderRegistry<KVS: KeyValueStore> {
+    deciders: HashMap<DeciderId, DeciderEntry<KVS>>,
+}
+
+impl<KVS> Default for DeciderRegistry<KVS>
+where
+    KVS: KeyValueStore,
+{
+    fn default() -> Self {
+        DeciderRegistry {
+            deciders: HashMap::new(),
+        }
+    }
+}
+
+impl<KVS> DeciderRegistry<KVS>
+where
+    KVS: KeyValueStore,
+{
+    /// Registers `D` as the decider of `decider_id`, its input is decoded by `D::Input`.
+    /// Fails if the ID is already registered.
+    pub fn register<D>(&mut self, decider_id: DeciderId) -> Result<(), Error>
+    where
+        D: Decider,
+        D::Input: Decodable<Ok = D::Input>,
+    {
+        if self.contains(decider_id) {
+            return Err(Error::from(ErrorKind::InvalidDeciderConfig));
+        }
+        self.deciders.insert(
+            decider_id,
+            DeciderEntry {
+                decide: decide_with::<KVS, D>,
+                check_decision: check_decision_with::<KVS, D>,
+                is_cacheable: D::IS_CACHEABLE,
+            },
+        );
+        Ok(())
+    }
+    pub fn contains(&self, decider_id: DeciderId) -> bool {
+        self.deciders.contains_key(&decider_id)
+    }
+    /// Returns true if the decider opts in to cache its true decisions with `IS_CACHEABLE`
+    pub fn is_cacheable(&self, decider_id: DeciderId) -> bool {
+        self.deciders
+            .get(&decider_id)
+            .map_or(false, |entry| entry.is_cacheable)
+    }
+    pub fn decide(
+        &self,
+        executor: &PropertyExecutor<KVS>,
+        decider_id: DeciderId,
+        input: &[u8],
+        witness: Option<Witness>,
+    ) -> Result<Decision, Error> {
+        (self.get_entry(decider_id)?.decide)(executor, input, witness)
+    }
+    pub fn check_decision(
+        &self,
+        executor: &PropertyExecutor<KVS>,
+        decider_id: DeciderId,
+        input: &[u8],
+    ) -> Result<Decision, Error> {
+        (self.get_entry(decider_id)?.check_decision)(executor, input)
+    }
+    fn get_entry(&self, decider_id: DeciderId) -> Result<&DeciderEntry<KVS>, Error> {
+        self.deciders
+            .get(&decider_id)
+            .ok_or_else(|| Error::from(ErrorKind::CannotDecide))
+    }
+}
+
+fn decide_with<KVS, D>(
+    executor: &PropertyExecutor<KVS>,
+    input: &[u8],
+    witness: Option<Witness>,
+) -> Result<Decision, Error>
+where
+    KVS: KeyValueStore,
+    D: Decider,
+    D::Input: Decodable<Ok = D::Input>,
+{
+    D::decide(executor, &D::Input::from_abi(input)?, witness)
+}
+
+fn check_decision_with<KVS, D>(
+    executor: &PropertyExecutor<KVS>,
+    input: &[u8],
+) -> Result<Decision, Error>
+where
+    KVS: KeyValueStore,
+    D: Decider,
+    D::Input: Decodable<Ok = D::Input>,
+{
+    D::check_decision(executor, &D::Input::from_abi(input)?)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::error::{Error, ErrorKind};
+    use crate::property_executor::PropertyExecutor;
+    use crate::types::{
+        AndDeciderInput, CustomDeciderInput, Decider, DeciderKind, Decision, Integer, Property,
+        Witness,
+    };
+    use abi_derive::{AbiDecodable, AbiEncodable};
+    use bytes::Bytes;
+    use ethereum_types::Address;
+    use plasma_core::data_structure::abi::{Decodable, Encodable};
+    use plasma_db::impls::kvs::CoreDbLevelDbImpl;
+    use plasma_db::traits::kvs::KeyValueStore;
+
+    #[derive(Clone, Debug, AbiDecodable, AbiEncodable)]
+    struct IsEvenInput {
+        number: Integer,
+    }
+
+    /// Decides whether the number is even
+    struct IsEvenDecider;
+
+    impl Decider for IsEvenDecider {
+        type Input = IsEvenInput;
+        fn decide<T: KeyValueStore>(
+            _decider: &PropertyExecutor<T>,
+            input: &IsEvenInput,
+            _witness: Option<Witness>,
+        ) -> Result<Decision, Error> {
+            Ok(Decision::new(input.number.0 % 2 == 0, vec![]))
+        }
+        fn check_decision<T: KeyValueStore>(
+            decider: &PropertyExecutor<T>,
+            input: &IsEvenInput,
+        ) -> Result<Decision, Error> {
+            Self::decide(decider, input, None)
+        }
+    }
+
+    fn create_is_even_property(number: u64) -> Property {
+        let input = IsEvenInput {
+            number: Integer(number),
+        };
+        Property::CustomDecider(CustomDeciderInput::new(
+            Address::from_low_u64_be(100),
+            Bytes::from(input.to_abi()),
+        ))
+    }
+
+    #[test]
+    fn test_decide_custom_decider() {
+        let mut decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        assert!(decider
+            .register_decider::<IsEvenDecider>(Address::from_low_u64_be(100))
+            .is_ok());
+        let even = create_is_even_property(2);
+        let odd = create_is_even_property(3);
+        assert_eq!(decider.decide(&even, None).unwrap().get_outcome(), true);
+        assert_eq!(decider.decide(&odd, None).unwrap().get_outcome(), false);
+        assert_eq!(decider.check_decision(&even).unwrap().get_outcome(), true);
+        let property = Property::AndDecider(Box::new(AndDeciderInput::new(
+            even,
+            Witness::Bytes(Bytes::new()),
+            create_is_even_property(4),
+            Witness::Bytes(Bytes::new()),
+        )));
+        assert_eq!(decider.decide(&property, None).unwrap().get_outcome(), true);
+    }
+
+    #[test]
+    fn test_decide_unregistered_decider() {
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let result = decider.decide(&create_is_even_property(2), None);
+        match result.unwrap_err().kind() {
+            ErrorKind::CannotDecide => (),
+            kind => panic!("unexpected error {}", kind),
+        }
+    }
+
+    #[test]
+    fn test_register_conflicting_decider() {
+        let mut decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let decider_id = Address::from_low_u64_be(100);
+        assert!(decider
+            .register_decider::<IsEvenDecider>(decider_id)
+            .is_ok());
+        assert!(decider
+            .register_decider::<IsEvenDecider>(decider_id)
+            .is_err());
+        assert!(decider
+            .register_decider::<IsEvenDecider>(DeciderKind::And.get_default_id())
+            .is_err());
+    }
+
+    #[test]
+    fn test_encode_and_decode_custom_property() {
+        let property = create_is_even_property(2);
+        let encoded = property.to_abi();
+        assert_eq!(&encoded[12..32], Address::from_low_u64_be(100).as_bytes());
+        if let Property::CustomDecider(input) = Property::from_abi(&encoded).unwrap() {
+            assert_eq!(input.get_decider_id(), Address::from_low_u64_be(100));
+            let decoded = IsEvenInput::from_abi(input.get_input()).unwrap();
+            assert_eq!(decoded.number, Integer(2));
+        } else {
+            panic!("decoded to a built-in property");
+        }
+    }
+
+}
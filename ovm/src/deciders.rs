@@ -1,4 +1,6 @@
 pub mod and_decider;
+pub mod block_deadline_decider;
+pub mod channel_update_signature_exists_decider;
 pub mod for_all_such_that_decider;
 pub mod has_lower_nonce;
 pub mod included_in_interval_tree_at_block_decider;
@@ -6,8 +8,13 @@ pub mod not_decider;
 pub mod or_decider;
 pub mod preimage_exists_decider;
 pub mod signed_by_decider;
+pub mod there_exists_such_that_decider;
+#[cfg(test)]
+mod test_helpers;
 
 pub use self::and_decider::AndDecider;
+pub use self::block_deadline_decider::BlockDeadlineDecider;
+pub use self::channel_update_signature_exists_decider::ChannelUpdateSignatureExistsDecider;
 pub use self::for_all_such_that_decider::ForAllSuchThatDecider;
 pub use self::has_lower_nonce::HasLowerNonceDecider;
 pub use self::included_in_interval_tree_at_block_decider::IncludedInIntervalTreeAtBlock;
@@ -15,3 +22,4 @@ pub use self::not_decider::NotDecider;
 pub use self::or_decider::OrDecider;
 pub use self::preimage_exists_decider::PreimageExistsDecider;
 pub use self::signed_by_decider::{SignedByDecider, Verifier as SignVerifier};
+pub use self::there_exists_such_that_decider::ThereExistsSuchThatDecider;
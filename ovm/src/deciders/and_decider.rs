@@ -1,7 +1,8 @@
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 use crate::property_executor::PropertyExecutor;
-use crate::types::{AndDeciderInput, Decider, Decision, Witness};
-use crate::DecideMixin;
+use crate::types::{
+    AndDeciderInput, Decider, Decision, ImplicationProofElement, Property, Witness,
+};
 use plasma_db::traits::kvs::KeyValueStore;
 
 pub struct AndDecider {}
@@ -10,6 +11,21 @@ impl AndDecider {
     pub fn new() -> Self {
         AndDecider {}
     }
+    /// The implication proof has this property followed by the proof of the false side.
+    /// Both sides determine a true decision, so its proof only has this property.
+    fn get_decision(
+        input: &AndDeciderInput,
+        false_side: Option<(&Property, &Decision)>,
+    ) -> Decision {
+        let mut justification = vec![ImplicationProofElement::new(
+            Property::AndDecider(Box::new(input.clone())),
+            None,
+        )];
+        if let Some((property, decision)) = false_side {
+            justification.extend(decision.get_implication_proof_of(property));
+        }
+        Decision::new(false_side.is_none(), justification)
+    }
 }
 
 impl Default for AndDecider {
@@ -20,31 +36,33 @@ impl Default for AndDecider {
 
 impl Decider for AndDecider {
     type Input = AndDeciderInput;
+    /// Decides false as soon as either side is false, even if the other side is undecided.
+    /// Otherwise it is undecided unless both sides are true.
     fn decide<T: KeyValueStore>(
         decider: &PropertyExecutor<T>,
         input: &AndDeciderInput,
         _witness: Option<Witness>,
     ) -> Result<Decision, Error> {
-        let left_decision = input
-            .get_left()
-            .decide(decider, Some(input.get_left_witness().clone()))?;
-        let right_decision = input
-            .get_right()
-            .decide(decider, Some(input.get_right_witness().clone()))?;
-        if !left_decision.get_outcome() {
-            return Ok(left_decision);
+        let left_decision =
+            decider.try_decide(input.get_left(), Some(input.get_left_witness().clone()))?;
+        if let Some(decision) = left_decision.as_ref().filter(|d| !d.get_outcome()) {
+            return Ok(Self::get_decision(
+                input,
+                Some((input.get_left(), decision)),
+            ));
         }
-        if !right_decision.get_outcome() {
-            return Ok(right_decision);
+        let right_decision =
+            decider.try_decide(input.get_right(), Some(input.get_right_witness().clone()))?;
+        if let Some(decision) = right_decision.as_ref().filter(|d| !d.get_outcome()) {
+            return Ok(Self::get_decision(
+                input,
+                Some((input.get_right(), decision)),
+            ));
+        }
+        match (left_decision, right_decision) {
+            (Some(_), Some(_)) => Ok(Self::get_decision(input, None)),
+            _ => Err(Error::from(ErrorKind::Undecided)),
         }
-        Ok(Decision::new(
-            true,
-            [
-                &left_decision.get_implication_proof()[..],
-                &right_decision.get_implication_proof()[..],
-            ]
-            .concat(),
-        ))
     }
 
     fn check_decision<T: KeyValueStore>(
@@ -60,6 +78,8 @@ mod tests {
     use super::AndDecider;
     use crate::db::HashPreimageDb;
     use crate::deciders::preimage_exists_decider::Verifier;
+    use crate::deciders::test_helpers::{create_lower_nonce_property, create_undecided_property};
+    use crate::error::ErrorKind;
     use crate::property_executor::PropertyExecutor;
     use crate::types::{
         AndDeciderInput, Decider, Decision, PreimageExistsInput, Property, Witness,
@@ -89,4 +109,33 @@ mod tests {
         assert_eq!(status.get_outcome(), true);
     }
 
+    #[test]
+    fn test_decide_false_with_undecided() {
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let (undecided, undecided_witness) = create_undecided_property();
+        let input = AndDeciderInput::new(
+            undecided.clone(),
+            undecided_witness.clone(),
+            create_lower_nonce_property(1, 0),
+            Witness::Bytes(Bytes::new()),
+        );
+        let decided = AndDecider::decide(&decider, &input, None).unwrap();
+        assert_eq!(decided.get_outcome(), false);
+        // the implication proof has this property and the false side
+        assert_eq!(decided.get_implication_proof().len(), 2);
+        let input = AndDeciderInput::new(
+            create_lower_nonce_property(0, 1),
+            Witness::Bytes(Bytes::new()),
+            undecided,
+            undecided_witness,
+        );
+        match AndDecider::decide(&decider, &input, None)
+            .unwrap_err()
+            .kind()
+        {
+            ErrorKind::Undecided => (),
+            kind => panic!("unexpected error {}", kind),
+        }
+    }
+
 }
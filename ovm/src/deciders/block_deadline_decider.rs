@@ -0,0 +1,37 @@
+use crate::db::BlockRootDb;
+use crate::error::Error;
+use crate::property_executor::PropertyExecutor;
+use crate::types::{BlockDeadlineInput, Decider, Decision, Witness};
+use plasma_db::traits::kvs::KeyValueStore;
+
+/// BlockDeadlineDecider decides whether the plasma chain has reached the deadline block,
+/// that is, the root of the block is stored in `BlockRootDb`.
+/// It isn't built in, so it's registered with a decider ID and used by `Property::CustomDecider`.
+/// The outcome turns true when the caller stores the root of the block on observing its
+/// commitment, and never turns false again, so its true decisions are cached.
+pub struct BlockDeadlineDecider {}
+
+impl Default for BlockDeadlineDecider {
+    fn default() -> Self {
+        BlockDeadlineDecider {}
+    }
+}
+
+impl Decider for BlockDeadlineDecider {
+    type Input = BlockDeadlineInput;
+    const IS_CACHEABLE: bool = true;
+    fn decide<T: KeyValueStore>(
+        decider: &PropertyExecutor<T>,
+        input: &BlockDeadlineInput,
+        _witness: Option<Witness>,
+    ) -> Result<Decision, Error> {
+        let root = BlockRootDb::new(decider.get_db()).get_root(input.get_deadline())?;
+        Ok(Decision::new(root.is_some(), vec![]))
+    }
+    fn check_decision<T: KeyValueStore>(
+        decider: &PropertyExecutor<T>,
+        input: &BlockDeadlineInput,
+    ) -> Result<Decision, Error> {
+        Self::decide(decider, input, None)
+    }
+}
@@ -0,0 +1,116 @@
+use crate::error::{Error, ErrorKind};
+use crate::property_executor::PropertyExecutor;
+use crate::types::{
+    ChannelUpdateSignatureExistsDeciderInput, Decider, Decision, ImplicationProofElement, Property,
+    Witness,
+};
+use plasma_core::data_structure::abi::Encodable;
+use plasma_db::traits::kvs::KeyValueStore;
+
+/// ChannelUpdateSignatureExistsDecider decides whether the participant has signed
+/// the update of the channel at the nonce, by looking up messages received so far.
+/// It is undecided until the signed message arrives, since a missing message proves nothing.
+pub struct ChannelUpdateSignatureExistsDecider {}
+
+impl Default for ChannelUpdateSignatureExistsDecider {
+    fn default() -> Self {
+        ChannelUpdateSignatureExistsDecider {}
+    }
+}
+
+impl Decider for ChannelUpdateSignatureExistsDecider {
+    type Input = ChannelUpdateSignatureExistsDeciderInput;
+    fn decide<T: KeyValueStore>(
+        decider: &PropertyExecutor<T>,
+        input: &ChannelUpdateSignatureExistsDeciderInput,
+        _witness: Option<Witness>,
+    ) -> Result<Decision, Error> {
+        let message = decider
+            .get_message_db()
+            .get_message_by_channel_id_and_nonce(input.channel_id.clone(), input.nonce)?
+            .filter(|message| message.get_signers().contains(&input.particilant));
+        if let Some(message) = message {
+            Ok(Decision::new(
+                true,
+                vec![ImplicationProofElement::new(
+                    Property::ChannelUpdateSignatureExistsDecider(input.clone()),
+                    Some(Witness::Bytes(message.to_abi().into())),
+                )],
+            ))
+        } else {
+            Err(Error::from(ErrorKind::Undecided))
+        }
+    }
+
+    fn check_decision<T: KeyValueStore>(
+        decider: &PropertyExecutor<T>,
+        input: &ChannelUpdateSignatureExistsDeciderInput,
+    ) -> Result<Decision, Error> {
+        Self::decide(decider, input, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::ChannelUpdateSignatureExistsDecider;
+    use crate::db::Message;
+    use crate::error::ErrorKind;
+    use crate::property_executor::PropertyExecutor;
+    use crate::types::{
+        ChannelUpdateSignatureExistsDeciderInput, Decider, Decision, Integer, Property,
+    };
+    use bytes::Bytes;
+    use ethereum_types::Address;
+    use ethsign::SecretKey;
+    use plasma_db::impls::kvs::CoreDbLevelDbImpl;
+
+    fn create_message(channel_id: &Bytes, nonce: u64, key: &SecretKey) -> Message {
+        let mut message = Message::new(
+            channel_id.clone(),
+            key.public().address().into(),
+            Address::zero(),
+            Integer(nonce),
+            Bytes::from(&b"update"[..]),
+        );
+        message.sign(key);
+        message
+    }
+
+    #[test]
+    fn test_decide() {
+        let channel_id = Bytes::from(&b"channel"[..]);
+        let alice_key = SecretKey::from_raw(&[1; 32]).unwrap();
+        let alice: Address = alice_key.public().address().into();
+        let bob = Address::from_low_u64_be(2);
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        assert!(decider
+            .get_message_db()
+            .store_message(&create_message(&channel_id, 5, &alice_key))
+            .is_ok());
+        let input =
+            ChannelUpdateSignatureExistsDeciderInput::new(channel_id.clone(), Integer(5), alice);
+        let decided: Decision = decider
+            .decide(
+                &Property::ChannelUpdateSignatureExistsDecider(input.clone()),
+                None,
+            )
+            .unwrap();
+        assert_eq!(decided.get_outcome(), true);
+        let signed_by_bob =
+            ChannelUpdateSignatureExistsDeciderInput::new(channel_id.clone(), Integer(5), bob);
+        let other_nonce =
+            ChannelUpdateSignatureExistsDeciderInput::new(channel_id, Integer(6), alice);
+        // missing messages may arrive later
+        for input in [signed_by_bob, other_nonce].iter() {
+            match ChannelUpdateSignatureExistsDecider::check_decision(&decider, input)
+                .unwrap_err()
+                .kind()
+            {
+                ErrorKind::Undecided => (),
+                kind => panic!("unexpected error {}", kind),
+            }
+        }
+    }
+
+}
@@ -1,38 +1,35 @@
 use crate::error::{Error, ErrorKind};
 use crate::property_executor::PropertyExecutor;
 use crate::types::{
-    Decider, Decision, ForAllSuchThatInput, ImplicationProofElement, Property, QuantifierResult,
-    Witness,
+    Decider, Decision, ForAllSuchThatInput, ImplicationProofElement, Property,
+    QuantifierResultItem, Witness,
 };
-use crate::DecideMixin;
+use bytes::Bytes;
+use ethabi::Token;
 use plasma_db::traits::kvs::KeyValueStore;
 
-/// ForAllSuchThatDecider decides for all quantified results by PropertyFactory and WitnessFactory
+/// ForAllSuchThatDecider decides for all quantified results by binding them to the templates
 pub struct ForAllSuchThatDecider {}
 
 impl ForAllSuchThatDecider {
+    /// The implication proof has this property with the counterexample as the witness,
+    /// followed by the proof of the counterexample.
+    /// All results determine a true decision, so its proof only has this property.
     fn get_decision(
         input: &ForAllSuchThatInput,
-        false_decision: Decision,
-        true_decisions: Vec<Decision>,
-        undecided: bool,
-    ) -> Result<Decision, Error> {
-        if !false_decision.get_outcome() && undecided {
-            return Err(Error::from(ErrorKind::Undecided));
-        }
+        counterexample: Option<(&QuantifierResultItem, &Property, &Decision)>,
+    ) -> Decision {
+        let witness = counterexample.map(|(item, _, _)| {
+            Witness::Bytes(Bytes::from(ethabi::encode(&[Token::from(item.clone())])))
+        });
         let mut justification = vec![ImplicationProofElement::new(
             Property::ForAllSuchThatDecider(Box::new(input.clone())),
-            None,
+            witness,
         )];
-        if false_decision.get_outcome() {
-            justification.extend(false_decision.get_implication_proof().clone())
-        } else {
-            for decision in true_decisions {
-                justification.extend(decision.get_implication_proof().clone())
-            }
+        if let Some((_, property, decision)) = counterexample {
+            justification.extend(decision.get_implication_proof_of(property));
         }
-
-        Ok(Decision::new(!false_decision.get_outcome(), justification))
+        Decision::new(counterexample.is_none(), justification)
     }
 }
 
@@ -49,44 +46,35 @@ impl Decider for ForAllSuchThatDecider {
         input: &ForAllSuchThatInput,
         _witness: Option<Witness>,
     ) -> Result<Decision, Error> {
-        let quantifier_result: QuantifierResult =
-            decider.get_all_quantified(input.get_quantifier());
+        // results are fetched lazily, so the remaining pages aren't fetched after an early exit
+        let mut quantified = decider.iter_quantified(input.get_quantifier());
 
         let mut any_undecided: bool = false;
-        let mut false_decision: Decision = Decision::new(false, vec![]);
-        let mut true_decisions: Vec<Decision> = vec![];
-        for res in quantifier_result.get_results() {
-            let prop: Property = input
-                .get_property_factory()
-                .clone()
-                .unwrap()
-                .call(res.clone());
-            let witness: Option<Witness> = input
-                .get_witness_factory()
-                .clone()
-                .map(|wf| wf.call(res.clone()));
-            let _no_cache = false;
-            let decision_result = prop.decide(
-                decider, witness,
-                // no_cache,
-            );
-            if let Ok(decision) = decision_result {
-                if !decision.get_outcome() {
-                    false_decision = decision;
-                    break;
+        for res in &mut quantified {
+            let res = res?;
+            let (property, decision) = decider.decide_bound(
+                input.get_variable(),
+                input.get_property_template(),
+                input.get_witness_template(),
+                &res,
+            )?;
+            match decision {
+                // a counterexample decides false even if other results are undecided
+                Some(decision) if !decision.get_outcome() => {
+                    return Ok(Self::get_decision(
+                        input,
+                        Some((&res, &property, &decision)),
+                    ));
                 }
-                true_decisions.push(decision)
-            } else {
-                any_undecided = true;
+                Some(_) => (),
+                None => any_undecided = true,
             }
         }
 
-        Self::get_decision(
-            input,
-            false_decision,
-            true_decisions,
-            any_undecided || !quantifier_result.get_all_results_quantified(),
-        )
+        if any_undecided || !quantified.get_all_results_quantified() {
+            return Err(Error::from(ErrorKind::Undecided));
+        }
+        Ok(Self::get_decision(input, None))
     }
     fn check_decision<T: KeyValueStore>(
         decider: &PropertyExecutor<T>,
@@ -99,50 +87,63 @@ impl Decider for ForAllSuchThatDecider {
 #[cfg(test)]
 mod tests {
     use super::ForAllSuchThatDecider;
-    use crate::db::HashPreimageDb;
-    use crate::deciders::preimage_exists_decider::Verifier;
+    use crate::db::Message;
     use crate::property_executor::PropertyExecutor;
     use crate::types::{
-        Decider, Decision, ForAllSuchThatInput, Integer, IntegerRangeQuantifierInput,
-        PreimageExistsInput, Property, PropertyFactory, Quantifier, QuantifierResultItem, Witness,
-        WitnessFactory,
+        Decider, Decision, ForAllSuchThatInput, HasLowerNonceInput, IntegerRangeQuantifierInput,
+        Property, Quantifier, Variable,
     };
+    use ethabi::Token;
+    use plasma_core::data_structure::abi::Decodable;
     use plasma_db::impls::kvs::CoreDbLevelDbImpl;
 
+    fn create_message(nonce: u64) -> Message {
+        Message::from_tuple(&[
+            Token::Bytes(b"channel".to_vec()),
+            Token::Address(Default::default()),
+            Token::Address(Default::default()),
+            Token::Uint(nonce.into()),
+            Token::Array(vec![]),
+            Token::Bytes(vec![]),
+            Token::Bytes(vec![]),
+        ])
+        .unwrap()
+    }
+
     #[test]
     fn test_decide() {
+        let variable = Variable::new("nonce");
         let input = ForAllSuchThatInput::new(
             Quantifier::IntegerRangeQuantifier(IntegerRangeQuantifierInput::new(5, 20)),
-            Some(PropertyFactory::new(Box::new(|item| {
-                if let QuantifierResultItem::Integer(number) = item {
-                    Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(
-                        Verifier::static_hash(&number.into()),
-                    )))
-                } else {
-                    panic!("invalid type of item");
-                }
-            }))),
-            Some(WitnessFactory::new(Box::new(|item| {
-                if let QuantifierResultItem::Integer(number) = item {
-                    Witness::Bytes(number.into())
-                } else {
-                    panic!("invalid type of item");
-                }
-            }))),
+            variable.clone(),
+            Property::HasLowerNonceDecider(HasLowerNonceInput::new(
+                create_message(4),
+                variable.placeholder(),
+            )),
+            None,
         );
         let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
-        let db = HashPreimageDb::new(decider.get_db());
-        for i in 5..20 {
-            let integer = Integer(i);
-            assert!(db
-                .store_witness(
-                    Verifier::static_hash(&integer.into()),
-                    &Witness::Bytes(integer.into())
-                )
-                .is_ok());
-        }
         let decided: Decision = ForAllSuchThatDecider::decide(&decider, &input, None).unwrap();
         assert_eq!(decided.get_outcome(), true);
     }
 
+    #[test]
+    fn test_decide_counterexample() {
+        let variable = Variable::new("nonce");
+        let input = ForAllSuchThatInput::new(
+            Quantifier::IntegerRangeQuantifier(IntegerRangeQuantifierInput::new(0, 20)),
+            variable.clone(),
+            Property::HasLowerNonceDecider(HasLowerNonceInput::new(
+                create_message(4),
+                variable.placeholder(),
+            )),
+            None,
+        );
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let decided: Decision = ForAllSuchThatDecider::decide(&decider, &input, None).unwrap();
+        assert_eq!(decided.get_outcome(), false);
+        // the counterexample follows even though its decider returns an empty proof
+        assert_eq!(decided.get_implication_proof().len(), 2);
+    }
+
 }
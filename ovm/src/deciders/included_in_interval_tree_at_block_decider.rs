@@ -1,3 +1,4 @@
+use crate::db::BlockRootDb;
 use crate::error::{Error, ErrorKind};
 use crate::property_executor::PropertyExecutor;
 use crate::types::{
@@ -11,7 +12,8 @@ use plasma_core::data_structure::Range;
 use plasma_db::traits::kvs::KeyValueStore;
 use plasma_db::traits::rangestore::RangeStore;
 
-/// IncludedInIntervalTreeAtBlock is decider which decide inclusion of data in merkle interval tree
+/// IncludedInIntervalTreeAtBlock is decider which decide inclusion of data in merkle interval tree.
+/// It's undecided until the root of the block is stored with `BlockRootDb::store_root`.
 pub struct IncludedInIntervalTreeAtBlock {}
 
 impl Default for IncludedInIntervalTreeAtBlock {
@@ -27,59 +29,85 @@ impl Decider for IncludedInIntervalTreeAtBlock {
         input: &IncludedInIntervalTreeAtBlockInput,
         witness: Option<Witness>,
     ) -> Result<Decision, Error> {
+        // the inclusion proof may be given later,
+        // an empty witness is the default of nested properties
+        match &witness {
+            None => return Err(Error::from(ErrorKind::Undecided)),
+            Some(Witness::Bytes(bytes)) if bytes.is_empty() => {
+                return Err(Error::from(ErrorKind::Undecided))
+            }
+            _ => (),
+        }
         if let Some(Witness::IncludedInIntervalTreeAtBlock(inclusion_proof, data_block)) =
             witness.clone()
         {
+            let root = BlockRootDb::new(decider.get_db())
+                .get_root(input.get_block_number())?
+                .ok_or_else(|| Error::from(ErrorKind::Undecided))?;
+            let updated_range = data_block.get_updated_range();
             let leaf = MerkleIntervalNode::Leaf {
-                end: input.get_coin_range().get_start(),
+                end: updated_range.get_end(),
                 data: Bytes::from(data_block.get_property().to_abi()),
             };
-            let root: Bytes = input.get_block_number().into();
-            let inclusion_bounds_result =
-                MerkleIntervalTree::verify(&leaf, 5, inclusion_proof, &root);
-            if inclusion_bounds_result.is_err() {
-                return Err(Error::from(ErrorKind::CannotDecide));
-            }
-            let implicit_bounds = inclusion_bounds_result.unwrap();
+            let implicit_bounds = MerkleIntervalTree::verify(
+                &leaf,
+                data_block.get_leaf_index().0 as usize,
+                inclusion_proof,
+                &root,
+            )
+            .map_err(|_| Error::from(ErrorKind::CannotDecide))?;
             let inclusion_bounds: Range =
                 Range::new(implicit_bounds.get_start(), implicit_bounds.get_end());
-            // Insert inclusion decision
-            let relevant_inclusion: Range = input
-                .get_coin_range()
-                .get_overlapping_range(&data_block.get_updated_range());
-            let inclusion_decision_value = DecisionValue::new(true, witness.clone().unwrap());
-            decider
-                .get_range_db()
-                .bucket(&Bytes::from("range_at_block"))
-                .bucket(&input.get_block_number().into())
-                .put(
-                    relevant_inclusion.get_start(),
-                    relevant_inclusion.get_end(),
-                    &inclusion_decision_value.to_abi(),
-                )
-                .map_err::<Error, _>(Into::into)?;
-            if input.get_coin_range().get_end() == data_block.get_updated_range().get_end() {
+            if updated_range.get_start() < inclusion_bounds.get_start() {
                 return Err(Error::from(ErrorKind::CannotDecide));
             }
-            // Insert exclusion decision
-            let relevant_exclusion = Range::new(
-                data_block.get_updated_range().get_end(),
-                inclusion_bounds.get_end(),
-            );
-            let exclusion_decision_value = DecisionValue::new(true, witness.clone().unwrap());
-            decider
+            // the proof says nothing about coins outside of the leaf and its implicit bounds
+            let coin_range = input.get_coin_range();
+            if coin_range.get_start() < updated_range.get_start()
+                || inclusion_bounds.get_end() < coin_range.get_end()
+            {
+                return Err(Error::from(ErrorKind::Undecided));
+            }
+            let decision_value = DecisionValue::new(true, witness.clone().unwrap());
+            let range_at_block = decider
                 .get_range_db()
-                .bucket(&Bytes::from("range_at_block"))
-                .bucket(&input.get_block_number().into())
-                .put(
-                    relevant_exclusion.get_start(),
-                    relevant_exclusion.get_end(),
-                    &exclusion_decision_value.to_abi(),
-                )
-                .map_err::<Error, _>(Into::into)?;
-            Ok(Decision::new(true, vec![]))
+                .bucket(&Bytes::from("range_at_block"));
+            let range_db = range_at_block.bucket(&input.get_block_number().into());
+            // Insert inclusion decision
+            let relevant_inclusion: Range =
+                input.get_coin_range().get_overlapping_range(&updated_range);
+            if relevant_inclusion.get_start() < relevant_inclusion.get_end() {
+                range_db
+                    .put(
+                        relevant_inclusion.get_start(),
+                        relevant_inclusion.get_end(),
+                        &decision_value.to_abi(),
+                    )
+                    .map_err::<Error, _>(Into::into)?;
+            }
+            // Insert exclusion decision, no other leaf updates the range up to the implicit end
+            let relevant_exclusion = input.get_coin_range().get_overlapping_range(&Range::new(
+                updated_range.get_end(),
+                inclusion_bounds.get_end(),
+            ));
+            if relevant_exclusion.get_start() < relevant_exclusion.get_end() {
+                range_db
+                    .put(
+                        relevant_exclusion.get_start(),
+                        relevant_exclusion.get_end(),
+                        &decision_value.to_abi(),
+                    )
+                    .map_err::<Error, _>(Into::into)?;
+            }
+            Ok(Decision::new(
+                true,
+                vec![ImplicationProofElement::new(
+                    Property::IncludedInIntervalTreeAtBlockDecider(input.clone()),
+                    witness,
+                )],
+            ))
         } else {
-            panic!("invalid witness")
+            Err(Error::from(ErrorKind::InvalidWitness))
         }
     }
     fn check_decision<T: KeyValueStore>(
@@ -93,6 +121,17 @@ impl Decider for IncludedInIntervalTreeAtBlock {
             .bucket(&input.get_block_number().into())
             .get(decision_key.get_start(), decision_key.get_end())
             .map_err::<Error, _>(Into::into)?;
+        // stored decisions must cover the whole coin range without gaps
+        let mut covered_end = decision_key.get_start();
+        for range in result.iter() {
+            if covered_end < range.get_start() {
+                break;
+            }
+            covered_end = covered_end.max(range.get_end());
+        }
+        if result.is_empty() || covered_end < decision_key.get_end() {
+            return Err(Error::from(ErrorKind::Undecided));
+        }
         let decision_value =
             DecisionValue::from_abi(result[0].get_value()).map_err::<Error, _>(Into::into)?;
         Ok(Decision::new(
@@ -104,3 +143,72 @@ impl Decider for IncludedInIntervalTreeAtBlock {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::IncludedInIntervalTreeAtBlock;
+    use crate::db::BlockRootDb;
+    use crate::error::ErrorKind;
+    use crate::property_executor::PropertyExecutor;
+    use crate::types::{
+        Decider, IncludedInIntervalTreeAtBlockInput, Integer, PlasmaDataBlock, PreimageExistsInput,
+        Property, Witness,
+    };
+    use bytes::Bytes;
+    use ethereum_types::H256;
+    use merkle_interval_tree::{MerkleIntervalNode, MerkleIntervalTree};
+    use plasma_core::data_structure::abi::Encodable;
+    use plasma_core::data_structure::Range;
+    use plasma_db::impls::kvs::CoreDbLevelDbImpl;
+
+    #[test]
+    fn test_decide_with_proof_of_other_leaf() {
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let block_number = Integer(1);
+        let property =
+            Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(H256::zero())));
+        let leaves: [MerkleIntervalNode<u64>; 2] = [
+            MerkleIntervalNode::Leaf {
+                end: 100,
+                data: Bytes::from(property.to_abi()),
+            },
+            MerkleIntervalNode::Leaf {
+                end: 200,
+                data: Bytes::from(property.to_abi()),
+            },
+        ];
+        let tree = MerkleIntervalTree::generate(&leaves);
+        assert!(BlockRootDb::new(decider.get_db())
+            .store_root(block_number, &tree.get_root())
+            .is_ok());
+        // a valid proof of the second leaf
+        let witness = Witness::IncludedInIntervalTreeAtBlock(
+            tree.get_inclusion_proof(1, 2),
+            PlasmaDataBlock::new(Integer(1), Range::new(100, 200), property),
+        );
+        for coin_range in [Range::new(0, 100), Range::new(50, 150)].iter() {
+            let input = IncludedInIntervalTreeAtBlockInput::new(block_number, *coin_range);
+            let result = decider.decide(
+                &Property::IncludedInIntervalTreeAtBlockDecider(input.clone()),
+                Some(witness.clone()),
+            );
+            match result.unwrap_err().kind() {
+                ErrorKind::Undecided => (),
+                kind => panic!("unexpected error {}", kind),
+            }
+        }
+        let input = IncludedInIntervalTreeAtBlockInput::new(block_number, Range::new(100, 200));
+        let decided = decider
+            .decide(
+                &Property::IncludedInIntervalTreeAtBlockDecider(input),
+                Some(witness),
+            )
+            .unwrap();
+        assert!(decided.get_outcome());
+        // the stored decision covers only a part of the range
+        let input = IncludedInIntervalTreeAtBlockInput::new(block_number, Range::new(50, 150));
+        assert!(IncludedInIntervalTreeAtBlock::check_decision(&decider, &input).is_err());
+    }
+
+}
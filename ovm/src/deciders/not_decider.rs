@@ -1,9 +1,8 @@
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 use crate::property_executor::PropertyExecutor;
 use crate::types::{
     Decider, Decision, ImplicationProofElement, NotDeciderInput, Property, Witness,
 };
-use crate::DecideMixin;
 use plasma_db::traits::kvs::KeyValueStore;
 
 pub struct NotDecider {}
@@ -27,20 +26,17 @@ impl Decider for NotDecider {
         input: &NotDeciderInput,
         _witness: Option<Witness>,
     ) -> Result<Decision, Error> {
-        let decision = input
-            .get_property()
-            .decide(decider, Some(input.get_witness().clone()))?;
+        let decision = decider
+            .try_decide(input.get_property(), Some(input.get_witness().clone()))?
+            .ok_or_else(|| Error::from(ErrorKind::Undecided))?;
 
+        // the negated property doesn't follow from this property, so its proof isn't chained
         Ok(Decision::new(
             !decision.get_outcome(),
-            [
-                &vec![ImplicationProofElement::new(
-                    Property::NotDecider(Box::new(input.clone())),
-                    None,
-                )][..],
-                &decision.get_implication_proof()[..],
-            ]
-            .concat(),
+            vec![ImplicationProofElement::new(
+                Property::NotDecider(Box::new(input.clone())),
+                None,
+            )],
         ))
     }
 
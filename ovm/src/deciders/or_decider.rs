@@ -1,7 +1,8 @@
-use crate::error::Error;
-use crate::property_executor::DecideMixin;
+use crate::error::{Error, ErrorKind};
 use crate::property_executor::PropertyExecutor;
-use crate::types::{Decider, Decision, OrDeciderInput, Witness};
+use crate::types::{Decider, Decision, ImplicationProofElement, OrDeciderInput, Property, Witness};
+use bytes::Bytes;
+use ethabi::Token;
 use plasma_db::traits::kvs::KeyValueStore;
 
 pub struct OrDecider {}
@@ -10,6 +11,27 @@ impl OrDecider {
     pub fn new() -> Self {
         OrDecider {}
     }
+    /// The implication proof has this property with the index of the true side as the witness,
+    /// followed by the proof of that side.
+    /// Both sides determine a false decision, so its proof only has this property.
+    fn get_decision(input: &OrDeciderInput, true_side: Option<(u64, &Decision)>) -> Decision {
+        let witness = true_side.map(|(index, _)| {
+            Witness::Bytes(Bytes::from(ethabi::encode(&[Token::Uint(index.into())])))
+        });
+        let mut justification = vec![ImplicationProofElement::new(
+            Property::OrDecider(Box::new(input.clone())),
+            witness,
+        )];
+        if let Some((index, decision)) = true_side {
+            let property = if index == 0 {
+                input.get_left()
+            } else {
+                input.get_right()
+            };
+            justification.extend(decision.get_implication_proof_of(property));
+        }
+        Decision::new(true_side.is_some(), justification)
+    }
 }
 
 impl Default for OrDecider {
@@ -20,31 +42,27 @@ impl Default for OrDecider {
 
 impl Decider for OrDecider {
     type Input = OrDeciderInput;
+    /// Decides true as soon as either side is true, even if the other side is undecided.
+    /// Otherwise it is undecided unless both sides are false.
     fn decide<T: KeyValueStore>(
         decider: &PropertyExecutor<T>,
         input: &OrDeciderInput,
         _witness: Option<Witness>,
     ) -> Result<Decision, Error> {
-        let left_decision = input
-            .get_left()
-            .decide(decider, Some(input.get_left_witness().clone()))?;
-        let right_decision = input
-            .get_right()
-            .decide(decider, Some(input.get_right_witness().clone()))?;
-        if left_decision.get_outcome() {
-            return Ok(left_decision);
+        let left_decision =
+            decider.try_decide(input.get_left(), Some(input.get_left_witness().clone()))?;
+        if let Some(decision) = left_decision.as_ref().filter(|d| d.get_outcome()) {
+            return Ok(Self::get_decision(input, Some((0, decision))));
+        }
+        let right_decision =
+            decider.try_decide(input.get_right(), Some(input.get_right_witness().clone()))?;
+        if let Some(decision) = right_decision.as_ref().filter(|d| d.get_outcome()) {
+            return Ok(Self::get_decision(input, Some((1, decision))));
         }
-        if right_decision.get_outcome() {
-            return Ok(right_decision);
+        match (left_decision, right_decision) {
+            (Some(_), Some(_)) => Ok(Self::get_decision(input, None)),
+            _ => Err(Error::from(ErrorKind::Undecided)),
         }
-        Ok(Decision::new(
-            false,
-            [
-                &left_decision.get_implication_proof()[..],
-                &right_decision.get_implication_proof()[..],
-            ]
-            .concat(),
-        ))
     }
 
     fn check_decision<T: KeyValueStore>(
@@ -60,6 +78,8 @@ mod tests {
     use super::OrDecider;
     use crate::db::HashPreimageDb;
     use crate::deciders::preimage_exists_decider::Verifier;
+    use crate::deciders::test_helpers::{create_lower_nonce_property, create_undecided_property};
+    use crate::error::ErrorKind;
     use crate::property_executor::PropertyExecutor;
     use crate::types::{
         Decider, Decision, NotDeciderInput, OrDeciderInput, PreimageExistsInput, Property, Witness,
@@ -96,4 +116,46 @@ mod tests {
         assert_eq!(status.get_outcome(), true);
     }
 
+    #[test]
+    fn test_decide_true_with_undecided() {
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let (undecided, undecided_witness) = create_undecided_property();
+        let input = OrDeciderInput::new(
+            undecided.clone(),
+            undecided_witness.clone(),
+            create_lower_nonce_property(0, 1),
+            Witness::Bytes(Bytes::new()),
+        );
+        let decided = OrDecider::decide(&decider, &input, None).unwrap();
+        assert_eq!(decided.get_outcome(), true);
+        // this property and the true side
+        assert_eq!(decided.get_implication_proof().len(), 2);
+        let input = OrDeciderInput::new(
+            create_lower_nonce_property(1, 0),
+            Witness::Bytes(Bytes::new()),
+            undecided,
+            undecided_witness,
+        );
+        match OrDecider::decide(&decider, &input, None)
+            .unwrap_err()
+            .kind()
+        {
+            ErrorKind::Undecided => (),
+            kind => panic!("unexpected error {}", kind),
+        }
+    }
+
+    #[test]
+    fn test_decide_false() {
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let input = OrDeciderInput::new(
+            create_lower_nonce_property(1, 0),
+            Witness::Bytes(Bytes::new()),
+            create_lower_nonce_property(2, 1),
+            Witness::Bytes(Bytes::new()),
+        );
+        let decided = OrDecider::decide(&decider, &input, None).unwrap();
+        assert_eq!(decided.get_outcome(), false);
+    }
+
 }
@@ -7,7 +7,7 @@ use crate::types::{
 };
 use bytes::Bytes;
 use ethereum_types::H256;
-use plasma_core::data_structure::abi::Decodable;
+use plasma_core::data_structure::abi::{Decodable, Encodable, Hashable};
 use plasma_db::traits::kvs::{BaseDbKey, KeyValueStore};
 use tiny_keccak::Keccak;
 
@@ -41,38 +41,55 @@ impl Decider for PreimageExistsDecider {
     fn decide<T: KeyValueStore>(
         decider: &PropertyExecutor<T>,
         input: &PreimageExistsInput,
-        _witness: Option<Witness>,
+        witness: Option<Witness>,
     ) -> Result<Decision, Error> {
-        let key = input.get_hash();
-        let db: HashPreimageDb<T> = HashPreimageDb::new(decider.get_db());
-        let witness = db.get_witness(key)?;
+        // the preimage is looked up from the db unless it is given as the witness,
+        // an empty witness is the default of nested properties
+        let witness = witness.filter(|witness| match witness {
+            Witness::Bytes(preimage) => !preimage.is_empty(),
+            _ => true,
+        });
+        let witness = match witness {
+            Some(witness) => witness,
+            None => HashPreimageDb::new(decider.get_db()).get_witness(input.get_hash())?,
+        };
         if let Witness::Bytes(preimage) = witness {
             if Verifier::hash(&preimage) != input.get_hash() {
                 return Err(Error::from(ErrorKind::InvalidPreimage));
             }
+            let decision_value = DecisionValue::new(true, Witness::Bytes(preimage));
+            decider
+                .get_db()
+                .bucket(&BaseDbKey::from(&b"preimage_exists_decider"[..]))
+                .put(
+                    &BaseDbKey::from(input.hash().as_bytes()),
+                    &decision_value.to_abi(),
+                )
+                .map_err::<Error, _>(Into::into)?;
             Ok(Decision::new(
                 true,
                 vec![ImplicationProofElement::new(
                     Property::PreimageExistsDecider(Box::new(input.clone())),
-                    Some(Witness::Bytes(preimage)),
+                    Some(decision_value.get_witness().clone()),
                 )],
             ))
         } else {
-            panic!("invalid witness")
+            Err(Error::from(ErrorKind::InvalidWitness))
         }
     }
     fn check_decision<T: KeyValueStore>(
         decider: &PropertyExecutor<T>,
         input: &PreimageExistsInput,
     ) -> Result<Decision, Error> {
-        let decision_key = input.get_hash();
+        let decision_key = input.hash();
         let result = decider
             .get_db()
             .bucket(&BaseDbKey::from(&b"preimage_exists_decider"[..]))
             .get(&BaseDbKey::from(decision_key.as_bytes()))
             .map_err::<Error, _>(Into::into)?;
         if let Some(decision_value_bytes) = result {
-            let decision_value = DecisionValue::from_abi(&decision_value_bytes).unwrap();
+            let decision_value =
+                DecisionValue::from_abi(&decision_value_bytes).map_err::<Error, _>(Into::into)?;
             return Ok(Decision::new(
                 decision_value.get_decision(),
                 vec![ImplicationProofElement::new(
@@ -89,9 +106,10 @@ impl Decider for PreimageExistsDecider {
 #[cfg(test)]
 mod tests {
     use crate::db::HashPreimageDb;
-    use crate::deciders::preimage_exists_decider::Verifier;
+    use crate::deciders::preimage_exists_decider::{PreimageExistsDecider, Verifier};
+    use crate::error::ErrorKind;
     use crate::property_executor::PropertyExecutor;
-    use crate::types::{Decision, PreimageExistsInput, Property, Witness};
+    use crate::types::{Decider, Decision, PreimageExistsInput, Property, Witness};
     use bytes::Bytes;
     use plasma_db::impls::kvs::CoreDbLevelDbImpl;
 
@@ -107,6 +125,19 @@ mod tests {
         assert!(db.store_witness(hash, &witness).is_ok());
         let decided: Decision = decider.decide(&property, None).unwrap();
         assert_eq!(decided.get_outcome(), true);
+        let status = PreimageExistsDecider::check_decision(&decider, &input).unwrap();
+        assert_eq!(status.get_outcome(), true);
+    }
+
+    #[test]
+    fn test_decide_missing_preimage() {
+        let hash = Verifier::static_hash(&Bytes::from("missing"));
+        let property = Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(hash)));
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        match decider.decide(&property, None).unwrap_err().kind() {
+            ErrorKind::MissingPreimage => (),
+            kind => panic!("unexpected error {}", kind),
+        }
     }
 
 }
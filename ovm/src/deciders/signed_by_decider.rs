@@ -6,7 +6,7 @@ use crate::types::{
 use bytes::Bytes;
 use ethereum_types::{Address, H256};
 use ethsign::{SecretKey, Signature};
-use plasma_core::data_structure::abi::{Decodable, Encodable};
+use plasma_core::data_structure::abi::{Decodable, Encodable, Hashable};
 use plasma_db::traits::kvs::{BaseDbKey, KeyValueStore};
 use tiny_keccak::Keccak;
 
@@ -16,14 +16,17 @@ pub fn signature_to_bytes(signature: &Signature) -> Bytes {
     bytes.to_vec().into()
 }
 
-pub fn bytes_to_signature(bytes: &Bytes) -> Signature {
+pub fn bytes_to_signature(bytes: &Bytes) -> Result<Signature, Error> {
+    if bytes.len() != 65 {
+        return Err(Error::from(ErrorKind::InvalidWitness));
+    }
     let buf = bytes.to_vec();
     let mut r = [0u8; 32];
     let mut s = [0u8; 32];
     let v = buf[0];
     r.copy_from_slice(&buf[1..33]);
     s.copy_from_slice(&buf[33..65]);
-    Signature { v, r, s }
+    Ok(Signature { v, r, s })
 }
 
 pub fn hash(preimage: &Bytes) -> H256 {
@@ -39,13 +42,12 @@ pub fn hash(preimage: &Bytes) -> H256 {
 pub struct Verifier {}
 
 impl Verifier {
-    pub fn recover(sig_bytes: &Bytes, message: &Bytes) -> Address {
-        let signature: Signature = bytes_to_signature(sig_bytes);
-        signature
+    pub fn recover(sig_bytes: &Bytes, message: &Bytes) -> Result<Address, Error> {
+        let signature: Signature = bytes_to_signature(sig_bytes)?;
+        let public = signature
             .recover(hash(message).as_bytes())
-            .unwrap()
-            .address()
-            .into()
+            .map_err(|_| Error::from(ErrorKind::InvalidWitness))?;
+        Ok(public.address().into())
     }
     pub fn sign(key: &SecretKey, message: &Bytes) -> Bytes {
         signature_to_bytes(&key.sign(hash(message).as_bytes()).unwrap())
@@ -67,9 +69,17 @@ impl Decider for SignedByDecider {
         input: &SignedByInput,
         witness: Option<Witness>,
     ) -> Result<Decision, Error> {
+        // the signature may be given later, an empty witness is the default of nested properties
+        match &witness {
+            None => return Err(Error::from(ErrorKind::Undecided)),
+            Some(Witness::Bytes(signature)) if signature.is_empty() => {
+                return Err(Error::from(ErrorKind::Undecided))
+            }
+            _ => (),
+        }
         if let Some(Witness::Bytes(signature)) = witness {
-            if Verifier::recover(&signature, input.get_message()) != input.get_public_key() {
-                return Err(Error::from(ErrorKind::InvalidPreimage));
+            if Verifier::recover(&signature, input.get_message())? != input.get_public_key() {
+                return Err(Error::from(ErrorKind::InvalidSignature));
             }
             let decision_key = input.hash();
             let decision_value = DecisionValue::new(true, Witness::Bytes(signature.clone()));
@@ -77,7 +87,7 @@ impl Decider for SignedByDecider {
                 .get_db()
                 .bucket(&BaseDbKey::from(&b"signed_by_decider"[..]))
                 .put(
-                    &BaseDbKey::from(decision_key.to_vec().as_slice()),
+                    &BaseDbKey::from(decision_key.as_bytes()),
                     &decision_value.to_abi(),
                 )
                 .map_err::<Error, _>(Into::into)?;
@@ -90,7 +100,7 @@ impl Decider for SignedByDecider {
                 )],
             ))
         } else {
-            panic!("invalid witness");
+            Err(Error::from(ErrorKind::InvalidWitness))
         }
     }
     fn check_decision<T: KeyValueStore>(
@@ -101,10 +111,11 @@ impl Decider for SignedByDecider {
         let result = decider
             .get_db()
             .bucket(&BaseDbKey::from(&b"signed_by_decider"[..]))
-            .get(&BaseDbKey::from(decision_key.to_vec().as_slice()))
+            .get(&BaseDbKey::from(decision_key.as_bytes()))
             .map_err::<Error, _>(Into::into)?;
         if let Some(decision_value_bytes) = result {
-            let decision_value = DecisionValue::from_abi(&decision_value_bytes).unwrap();
+            let decision_value =
+                DecisionValue::from_abi(&decision_value_bytes).map_err::<Error, _>(Into::into)?;
             return Ok(Decision::new(
                 decision_value.get_decision(),
                 vec![ImplicationProofElement::new(
@@ -121,6 +132,7 @@ impl Decider for SignedByDecider {
 #[cfg(test)]
 mod tests {
     use super::{SignedByDecider, Verifier};
+    use crate::error::ErrorKind;
     use crate::property_executor::PropertyExecutor;
     use crate::types::{Decider, Decision, Property, SignedByInput, Witness};
     use bytes::Bytes;
@@ -146,4 +158,24 @@ mod tests {
         assert_eq!(status.get_outcome(), true);
     }
 
+    #[test]
+    fn test_decide_with_signature_of_other_key() {
+        let secret_key = SecretKey::from_raw(&[1; 32]).unwrap();
+        let other_key = SecretKey::from_raw(&[2; 32]).unwrap();
+        let message = Bytes::from("message");
+        let signature = Verifier::sign(&other_key, &message);
+        let property = Property::SignedByDecider(SignedByInput::new(
+            message,
+            secret_key.public().address().into(),
+        ));
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let error = decider
+            .decide(&property, Some(Witness::Bytes(signature)))
+            .unwrap_err();
+        match error.kind() {
+            ErrorKind::InvalidSignature => (),
+            kind => panic!("unexpected error {}", kind),
+        }
+    }
+
 }
@@ -0,0 +1,39 @@
+use crate::db::Message;
+use crate::types::{
+    HasLowerNonceInput, IncludedInIntervalTreeAtBlockInput, Integer, PlasmaDataBlock, Property,
+    Witness,
+};
+use bytes::Bytes;
+use ethabi::Token;
+use plasma_core::data_structure::abi::Decodable;
+use plasma_core::data_structure::Range;
+
+pub fn create_lower_nonce_property(message_nonce: u64, nonce: u64) -> Property {
+    let message = Message::from_tuple(&[
+        Token::Bytes(b"channel".to_vec()),
+        Token::Address(Default::default()),
+        Token::Address(Default::default()),
+        Token::Uint(message_nonce.into()),
+        Token::Array(vec![]),
+        Token::Bytes(vec![]),
+        Token::Bytes(vec![]),
+    ])
+    .unwrap();
+    Property::HasLowerNonceDecider(HasLowerNonceInput::new(message, Integer(nonce)))
+}
+
+/// The root of the block isn't stored
+pub fn create_undecided_property() -> (Property, Witness) {
+    let property = Property::IncludedInIntervalTreeAtBlockDecider(
+        IncludedInIntervalTreeAtBlockInput::new(Integer(1), Range::new(0, 10)),
+    );
+    let witness = Witness::IncludedInIntervalTreeAtBlock(
+        Bytes::new(),
+        PlasmaDataBlock::new(
+            Integer(0),
+            Range::new(0, 10),
+            create_lower_nonce_property(0, 1),
+        ),
+    );
+    (property, witness)
+}
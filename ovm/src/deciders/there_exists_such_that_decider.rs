@@ -0,0 +1,152 @@
+use crate::error::{Error, ErrorKind};
+use crate::property_executor::PropertyExecutor;
+use crate::types::{
+    Decider, Decision, ImplicationProofElement, Property, QuantifierResultItem,
+    ThereExistsSuchThatInput, Witness,
+};
+use bytes::Bytes;
+use ethabi::Token;
+use plasma_db::traits::kvs::KeyValueStore;
+
+/// ThereExistsSuchThatDecider searches quantified results for one which makes the template true.
+/// The implication proof has the found result as the witness.
+pub struct ThereExistsSuchThatDecider {}
+
+impl ThereExistsSuchThatDecider {
+    fn get_true_decision(
+        input: &ThereExistsSuchThatInput,
+        item: &QuantifierResultItem,
+        decision: Decision,
+    ) -> Decision {
+        let mut justification = vec![ImplicationProofElement::new(
+            Property::ThereExistsSuchThatDecider(Box::new(input.clone())),
+            Some(Witness::Bytes(Bytes::from(ethabi::encode(&[Token::from(
+                item.clone(),
+            )])))),
+        )];
+        justification.extend(decision.get_implication_proof().clone());
+        Decision::new(true, justification)
+    }
+}
+
+impl Default for ThereExistsSuchThatDecider {
+    fn default() -> Self {
+        ThereExistsSuchThatDecider {}
+    }
+}
+
+impl Decider for ThereExistsSuchThatDecider {
+    type Input = ThereExistsSuchThatInput;
+    fn decide<T: KeyValueStore>(
+        decider: &PropertyExecutor<T>,
+        input: &ThereExistsSuchThatInput,
+        _witness: Option<Witness>,
+    ) -> Result<Decision, Error> {
+        // results are fetched lazily, so the remaining pages aren't fetched after an early exit
+        let mut quantified = decider.iter_quantified(input.get_quantifier());
+
+        let mut any_undecided: bool = false;
+        for res in &mut quantified {
+            let res = res?;
+            let (_, decision) = decider.decide_bound(
+                input.get_variable(),
+                input.get_property_template(),
+                input.get_witness_template(),
+                &res,
+            )?;
+            match decision {
+                Some(decision) => {
+                    if decision.get_outcome() {
+                        return Ok(Self::get_true_decision(input, &res, decision));
+                    }
+                }
+                None => any_undecided = true,
+            }
+        }
+
+        // the result which isn't quantified or decided may be true
+        if any_undecided || !quantified.get_all_results_quantified() {
+            return Err(Error::from(ErrorKind::Undecided));
+        }
+        Ok(Decision::new(
+            false,
+            vec![ImplicationProofElement::new(
+                Property::ThereExistsSuchThatDecider(Box::new(input.clone())),
+                None,
+            )],
+        ))
+    }
+    fn check_decision<T: KeyValueStore>(
+        decider: &PropertyExecutor<T>,
+        input: &ThereExistsSuchThatInput,
+    ) -> Result<Decision, Error> {
+        Self::decide(decider, input, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ThereExistsSuchThatDecider;
+    use crate::db::Message;
+    use crate::property_executor::PropertyExecutor;
+    use crate::types::{
+        Decider, HasLowerNonceInput, IntegerRangeQuantifierInput, Property, Quantifier,
+        ThereExistsSuchThatInput, Variable, Witness,
+    };
+    use ethabi::Token;
+    use plasma_core::data_structure::abi::{Decodable, Encodable};
+    use plasma_db::impls::kvs::CoreDbLevelDbImpl;
+
+    fn create_message(nonce: u64) -> Message {
+        Message::from_tuple(&[
+            Token::Bytes(b"channel".to_vec()),
+            Token::Address(Default::default()),
+            Token::Address(Default::default()),
+            Token::Uint(nonce.into()),
+            Token::Array(vec![]),
+            Token::Bytes(vec![]),
+            Token::Bytes(vec![]),
+        ])
+        .unwrap()
+    }
+
+    fn create_input(start: u64, end: u64) -> ThereExistsSuchThatInput {
+        let variable = Variable::new("nonce");
+        ThereExistsSuchThatInput::new(
+            Quantifier::IntegerRangeQuantifier(IntegerRangeQuantifierInput::new(start, end)),
+            variable.clone(),
+            Property::HasLowerNonceDecider(HasLowerNonceInput::new(
+                create_message(7),
+                variable.placeholder(),
+            )),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_decide() {
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let decided =
+            ThereExistsSuchThatDecider::decide(&decider, &create_input(5, 20), None).unwrap();
+        assert_eq!(decided.get_outcome(), true);
+        // the first result which is greater than the nonce 7
+        let proof = decided.get_implication_proof();
+        let witness = ethabi::encode(&[Token::Uint(8.into())]);
+        assert_eq!(
+            Token::from(proof[0].clone()),
+            Token::Tuple(vec![
+                Property::ThereExistsSuchThatDecider(Box::new(create_input(5, 20))).into(),
+                Token::Bytes(Witness::Bytes(witness.into()).to_abi()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_decide_false() {
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let decided =
+            ThereExistsSuchThatDecider::decide(&decider, &create_input(0, 5), None).unwrap();
+        assert_eq!(decided.get_outcome(), false);
+    }
+
+}
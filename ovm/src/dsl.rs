@@ -0,0 +1,57 @@
+//! A textual DSL of properties, e.g. `ForAll b < 10: ForAll p in BlockRange(b, 0..100): p`.
+//!
+//! ```text
+//! property   := ForAll quantified | ThereExists quantified
+//!             | And(operand, operand) | Or(operand, operand) | Not(operand)
+//!             | PreimageExists(hash) | SignedBy(bytes, address)
+//!             | HasLowerNonce(message, integer)
+//!             | ChannelUpdateSignatureExists(bytes, integer, address)
+//!             | IncludedInIntervalTreeAtBlock(integer, range)
+//!             | Custom(address, bytes) | variable
+//! quantified := variable (< integer | in quantifier) [with witness] : property
+//! quantifier := range | BlockRange(integer, range) | SignedBy(address)
+//! operand    := property [with witness]
+//! witness    := bytes | Inclusion(bytes, integer, range, property)
+//! message    := Message(bytes, address, address, integer, [address, ...], bytes, bytes) | variable
+//! range      := number..number
+//! bytes      := 0x.. | "string" | variable
+//! integer    := number | variable
+//! ```
+//!
+//! Variables are replaced with their placeholders, and the witness of an operand is empty bytes
+//! if it is omitted.
+
+mod lexer;
+mod parser;
+mod printer;
+
+pub use self::parser::parse_property;
+pub use self::printer::{print_item, print_property, print_witness};
+
+use crate::error::{Error, ErrorKind};
+
+/// Words which can't be names of variables
+const KEYWORDS: [&str; 16] = [
+    "ForAll",
+    "ThereExists",
+    "And",
+    "Or",
+    "Not",
+    "PreimageExists",
+    "SignedBy",
+    "HasLowerNonce",
+    "ChannelUpdateSignatureExists",
+    "IncludedInIntervalTreeAtBlock",
+    "Custom",
+    "BlockRange",
+    "Inclusion",
+    "Message",
+    "in",
+    "with",
+];
+
+fn syntax_error(message: &str, position: usize) -> Error {
+    Error::from(
+        failure::err_msg(format!("{} at {}", message, position)).context(ErrorKind::InvalidSyntax),
+    )
+}
@@ -0,0 +1,131 @@
+use super::syntax_error;
+use crate::error::Error;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Lexeme {
+    Ident(String),
+    Number(u64),
+    Hex(Vec<u8>),
+    Str(Vec<u8>),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    Colon,
+    LessThan,
+    DotDot,
+    Eof,
+}
+
+fn scan(source: &[u8], from: usize, accept: fn(u8) -> bool) -> usize {
+    source[from..]
+        .iter()
+        .position(|c| !accept(*c))
+        .map_or(source.len(), |n| from + n)
+}
+
+/// Splits the source into lexemes with their byte positions, the last one is `Eof`
+pub fn tokenize(source: &str) -> Result<Vec<(Lexeme, usize)>, Error> {
+    let bytes = source.as_bytes();
+    let mut lexemes = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let lexeme = match bytes[i] {
+            c if c.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            b'(' => Lexeme::LParen,
+            b')' => Lexeme::RParen,
+            b'[' => Lexeme::LBracket,
+            b']' => Lexeme::RBracket,
+            b',' => Lexeme::Comma,
+            b':' => Lexeme::Colon,
+            b'<' => Lexeme::LessThan,
+            b'.' if bytes.get(i + 1) == Some(&b'.') => {
+                i += 1;
+                Lexeme::DotDot
+            }
+            b'0' if bytes.get(i + 1) == Some(&b'x') => {
+                let end = scan(bytes, i + 2, |c| c.is_ascii_hexdigit());
+                let hex = hex::decode(&source[i + 2..end])
+                    .map_err(|_| syntax_error("invalid hex", start))?;
+                i = end - 1;
+                Lexeme::Hex(hex)
+            }
+            c if c.is_ascii_digit() => {
+                let end = scan(bytes, i, |c| c.is_ascii_digit());
+                let n = source[i..end]
+                    .parse()
+                    .map_err(|_| syntax_error("too large number", start))?;
+                i = end - 1;
+                Lexeme::Number(n)
+            }
+            b'"' => {
+                let end = scan(bytes, i + 1, |c| c != b'"');
+                if end == bytes.len() {
+                    return Err(syntax_error("unterminated string", start));
+                }
+                let string = bytes[i + 1..end].to_vec();
+                i = end;
+                Lexeme::Str(string)
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                let end = scan(bytes, i, |c| c.is_ascii_alphanumeric() || c == b'_');
+                let ident = source[i..end].to_string();
+                i = end - 1;
+                Lexeme::Ident(ident)
+            }
+            _ => return Err(syntax_error("unexpected character", start)),
+        };
+        i += 1;
+        lexemes.push((lexeme, start));
+    }
+    lexemes.push((Lexeme::Eof, bytes.len()));
+    Ok(lexemes)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{tokenize, Lexeme};
+
+    #[test]
+    fn test_tokenize() {
+        let lexemes: Vec<Lexeme> = tokenize("ForAll b < 10: f(0x01ff, \"ab\", 0..5)")
+            .unwrap()
+            .into_iter()
+            .map(|(lexeme, _)| lexeme)
+            .collect();
+        assert_eq!(
+            lexemes,
+            vec![
+                Lexeme::Ident("ForAll".to_string()),
+                Lexeme::Ident("b".to_string()),
+                Lexeme::LessThan,
+                Lexeme::Number(10),
+                Lexeme::Colon,
+                Lexeme::Ident("f".to_string()),
+                Lexeme::LParen,
+                Lexeme::Hex(vec![1, 255]),
+                Lexeme::Comma,
+                Lexeme::Str(b"ab".to_vec()),
+                Lexeme::Comma,
+                Lexeme::Number(0),
+                Lexeme::DotDot,
+                Lexeme::Number(5),
+                Lexeme::RParen,
+                Lexeme::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_invalid_literals() {
+        assert!(tokenize("0x123").is_err());
+        assert!(tokenize("\"open").is_err());
+    }
+
+}
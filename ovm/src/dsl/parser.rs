@@ -0,0 +1,445 @@
+use super::lexer::{tokenize, Lexeme};
+use super::{syntax_error, KEYWORDS};
+use crate::db::Message;
+use crate::error::Error;
+use crate::types::{
+    AndDeciderInput, BlockRangeQuantifierInput, ChannelUpdateSignatureExistsDeciderInput,
+    CustomDeciderInput, ForAllSuchThatInput, HasLowerNonceInput,
+    IncludedInIntervalTreeAtBlockInput, Integer, NotDeciderInput, OrDeciderInput, Placeholder,
+    PlasmaDataBlock, PreimageExistsInput, Property, Quantifier, SignedByInput,
+    ThereExistsSuchThatInput, Variable, Witness,
+};
+use bytes::Bytes;
+use ethabi::Token;
+use ethereum_types::{Address, H256};
+use plasma_core::data_structure::abi::Decodable;
+use plasma_core::data_structure::Range;
+
+/// Parses the property written in the DSL
+pub fn parse_property(source: &str) -> Result<Property, Error> {
+    let mut parser = Parser::new(tokenize(source)?);
+    let property = parser.parse_property()?;
+    parser.expect(&Lexeme::Eof)?;
+    Ok(property)
+}
+
+/// Quantifier, variable, property template and witness template of quantified properties
+type Quantified = (Quantifier, Variable, Property, Option<Witness>);
+
+struct Parser {
+    lexemes: Vec<(Lexeme, usize)>,
+    index: usize,
+    // variables bound by enclosing quantified properties, the innermost is the last
+    scope: Vec<Variable>,
+}
+
+impl Parser {
+    fn new(lexemes: Vec<(Lexeme, usize)>) -> Self {
+        Parser {
+            lexemes,
+            index: 0,
+            scope: vec![],
+        }
+    }
+    fn peek(&self) -> &Lexeme {
+        &self.lexemes[self.index].0
+    }
+    fn next(&mut self) -> Lexeme {
+        let lexeme = self.peek().clone();
+        if lexeme != Lexeme::Eof {
+            self.index += 1;
+        }
+        lexeme
+    }
+    fn error(&self, message: &str) -> Error {
+        syntax_error(message, self.lexemes[self.index].1)
+    }
+    fn eat(&mut self, lexeme: &Lexeme) -> bool {
+        if self.peek() == lexeme {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.eat(&Lexeme::Ident(keyword.to_string()))
+    }
+    fn expect(&mut self, lexeme: &Lexeme) -> Result<(), Error> {
+        if self.eat(lexeme) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {:?}", lexeme)))
+        }
+    }
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), Error> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", keyword)))
+        }
+    }
+    fn lookup<T: Placeholder>(&self, name: &str) -> Result<T, Error> {
+        self.scope
+            .iter()
+            .rev()
+            .find(|variable| &variable.get_name()[..] == name.as_bytes())
+            .map(|variable| variable.placeholder())
+            .ok_or_else(|| self.error(&format!("unknown variable `{}`", name)))
+    }
+    /// Parses the placeholder of a variable, the error message tells the expected value
+    fn parse_variable<T: Placeholder>(&mut self, expected: &str) -> Result<T, Error> {
+        if let Lexeme::Ident(name) = self.peek().clone() {
+            let placeholder = self.lookup(&name)?;
+            self.next();
+            Ok(placeholder)
+        } else {
+            Err(self.error(&format!("expected {}", expected)))
+        }
+    }
+    fn parse_property(&mut self) -> Result<Property, Error> {
+        let name = match self.peek().clone() {
+            Lexeme::Ident(name) => name,
+            _ => return Err(self.error("expected a property")),
+        };
+        if !KEYWORDS.contains(&name.as_str()) {
+            return self.parse_variable("a property");
+        }
+        self.next();
+        let property = match name.as_str() {
+            "ForAll" => {
+                let (quantifier, variable, template, witness) = self.parse_quantified()?;
+                Property::ForAllSuchThatDecider(Box::new(ForAllSuchThatInput::new(
+                    quantifier, variable, template, witness,
+                )))
+            }
+            "ThereExists" => {
+                let (quantifier, variable, template, witness) = self.parse_quantified()?;
+                Property::ThereExistsSuchThatDecider(Box::new(ThereExistsSuchThatInput::new(
+                    quantifier, variable, template, witness,
+                )))
+            }
+            "And" => {
+                self.expect(&Lexeme::LParen)?;
+                let (left, left_witness) = self.parse_operand()?;
+                self.expect(&Lexeme::Comma)?;
+                let (right, right_witness) = self.parse_operand()?;
+                self.expect(&Lexeme::RParen)?;
+                Property::AndDecider(Box::new(AndDeciderInput::new(
+                    left,
+                    left_witness,
+                    right,
+                    right_witness,
+                )))
+            }
+            "Or" => {
+                self.expect(&Lexeme::LParen)?;
+                let (left, left_witness) = self.parse_operand()?;
+                self.expect(&Lexeme::Comma)?;
+                let (right, right_witness) = self.parse_operand()?;
+                self.expect(&Lexeme::RParen)?;
+                Property::OrDecider(Box::new(OrDeciderInput::new(
+                    left,
+                    left_witness,
+                    right,
+                    right_witness,
+                )))
+            }
+            "Not" => {
+                self.expect(&Lexeme::LParen)?;
+                let (property, witness) = self.parse_operand()?;
+                self.expect(&Lexeme::RParen)?;
+                Property::NotDecider(Box::new(NotDeciderInput::new(property, witness)))
+            }
+            "PreimageExists" => {
+                self.expect(&Lexeme::LParen)?;
+                let hash = self.parse_hash()?;
+                self.expect(&Lexeme::RParen)?;
+                Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(hash)))
+            }
+            "SignedBy" => {
+                self.expect(&Lexeme::LParen)?;
+                let message = self.parse_bytes()?;
+                self.expect(&Lexeme::Comma)?;
+                let public_key = self.parse_address()?;
+                self.expect(&Lexeme::RParen)?;
+                Property::SignedByDecider(SignedByInput::new(message, public_key))
+            }
+            "HasLowerNonce" => {
+                self.expect(&Lexeme::LParen)?;
+                let message = self.parse_message()?;
+                self.expect(&Lexeme::Comma)?;
+                let nonce = self.parse_integer()?;
+                self.expect(&Lexeme::RParen)?;
+                Property::HasLowerNonceDecider(HasLowerNonceInput::new(message, nonce))
+            }
+            "ChannelUpdateSignatureExists" => {
+                self.expect(&Lexeme::LParen)?;
+                let channel_id = self.parse_bytes()?;
+                self.expect(&Lexeme::Comma)?;
+                let nonce = self.parse_integer()?;
+                self.expect(&Lexeme::Comma)?;
+                let participant = self.parse_address()?;
+                self.expect(&Lexeme::RParen)?;
+                Property::ChannelUpdateSignatureExistsDecider(
+                    ChannelUpdateSignatureExistsDeciderInput::new(channel_id, nonce, participant),
+                )
+            }
+            "IncludedInIntervalTreeAtBlock" => {
+                self.expect(&Lexeme::LParen)?;
+                let block_number = self.parse_integer()?;
+                self.expect(&Lexeme::Comma)?;
+                let coin_range = self.parse_range()?;
+                self.expect(&Lexeme::RParen)?;
+                Property::IncludedInIntervalTreeAtBlockDecider(
+                    IncludedInIntervalTreeAtBlockInput::new(block_number, coin_range),
+                )
+            }
+            "Custom" => {
+                self.expect(&Lexeme::LParen)?;
+                let decider_id = self.parse_address()?;
+                self.expect(&Lexeme::Comma)?;
+                let input = self.parse_bytes()?;
+                self.expect(&Lexeme::RParen)?;
+                Property::CustomDecider(CustomDeciderInput::new(decider_id, input))
+            }
+            _ => return Err(self.error(&format!("unexpected `{}`", name))),
+        };
+        Ok(property)
+    }
+    fn parse_quantified(&mut self) -> Result<Quantified, Error> {
+        let name = match self.next() {
+            Lexeme::Ident(ref name) if !KEYWORDS.contains(&name.as_str()) => name.clone(),
+            _ => return Err(self.error("expected a variable")),
+        };
+        // the quantifier can't refer to the variable which it binds
+        let quantifier = if self.eat(&Lexeme::LessThan) {
+            Quantifier::NonnegativeIntegerLessThanQuantifier(self.parse_integer()?)
+        } else {
+            self.expect_keyword("in")?;
+            self.parse_quantifier()?
+        };
+        let variable = Variable::new(&name);
+        self.scope.push(variable.clone());
+        let witness = if self.eat_keyword("with") {
+            Some(self.parse_witness()?)
+        } else {
+            None
+        };
+        self.expect(&Lexeme::Colon)?;
+        let template = self.parse_property()?;
+        self.scope.pop();
+        Ok((quantifier, variable, template, witness))
+    }
+    fn parse_quantifier(&mut self) -> Result<Quantifier, Error> {
+        if self.eat_keyword("BlockRange") {
+            self.expect(&Lexeme::LParen)?;
+            let block_number = self.parse_integer()?;
+            self.expect(&Lexeme::Comma)?;
+            let coin_range = self.parse_range()?;
+            self.expect(&Lexeme::RParen)?;
+            Ok(Quantifier::BlockRangeQuantifier(
+                BlockRangeQuantifierInput::new(block_number, coin_range),
+            ))
+        } else if self.eat_keyword("SignedBy") {
+            self.expect(&Lexeme::LParen)?;
+            let signer = self.parse_address()?;
+            self.expect(&Lexeme::RParen)?;
+            Ok(Quantifier::SignedByQuantifier(signer))
+        } else {
+            Ok(Quantifier::IntegerRangeQuantifier(self.parse_range()?))
+        }
+    }
+    fn parse_operand(&mut self) -> Result<(Property, Witness), Error> {
+        let property = self.parse_property()?;
+        let witness = if self.eat_keyword("with") {
+            self.parse_witness()?
+        } else {
+            Witness::Bytes(Bytes::new())
+        };
+        Ok((property, witness))
+    }
+    fn parse_witness(&mut self) -> Result<Witness, Error> {
+        if self.eat_keyword("Inclusion") {
+            self.expect(&Lexeme::LParen)?;
+            let inclusion_proof = self.parse_bytes()?;
+            self.expect(&Lexeme::Comma)?;
+            let leaf_index = self.parse_integer()?;
+            self.expect(&Lexeme::Comma)?;
+            let updated_range = self.parse_range()?;
+            self.expect(&Lexeme::Comma)?;
+            let property = self.parse_property()?;
+            self.expect(&Lexeme::RParen)?;
+            Ok(Witness::IncludedInIntervalTreeAtBlock(
+                inclusion_proof,
+                PlasmaDataBlock::new(leaf_index, updated_range, property),
+            ))
+        } else {
+            Ok(Witness::Bytes(self.parse_bytes()?))
+        }
+    }
+    fn parse_message(&mut self) -> Result<Message, Error> {
+        if !self.eat_keyword("Message") {
+            return self.parse_variable("a message");
+        }
+        self.expect(&Lexeme::LParen)?;
+        let channel_id = self.parse_bytes()?;
+        self.expect(&Lexeme::Comma)?;
+        let sender = self.parse_address()?;
+        self.expect(&Lexeme::Comma)?;
+        let recipient = self.parse_address()?;
+        self.expect(&Lexeme::Comma)?;
+        let nonce = self.parse_integer()?;
+        self.expect(&Lexeme::Comma)?;
+        self.expect(&Lexeme::LBracket)?;
+        let mut signers = vec![];
+        while !self.eat(&Lexeme::RBracket) {
+            if !signers.is_empty() {
+                self.expect(&Lexeme::Comma)?;
+            }
+            signers.push(Token::Address(self.parse_address()?));
+        }
+        self.expect(&Lexeme::Comma)?;
+        let message = self.parse_bytes()?;
+        self.expect(&Lexeme::Comma)?;
+        let signed_message = self.parse_bytes()?;
+        self.expect(&Lexeme::RParen)?;
+        Message::from_tuple(&[
+            Token::Bytes(channel_id.to_vec()),
+            Token::Address(sender),
+            Token::Address(recipient),
+            Token::Uint(nonce.0.into()),
+            Token::Array(signers),
+            Token::Bytes(message.to_vec()),
+            Token::Bytes(signed_message.to_vec()),
+        ])
+        .map_err(|_| self.error("invalid message"))
+    }
+    fn parse_integer(&mut self) -> Result<Integer, Error> {
+        if let Lexeme::Number(_) = self.peek() {
+            Ok(Integer(self.parse_number()?))
+        } else {
+            self.parse_variable("an integer")
+        }
+    }
+    fn parse_bytes(&mut self) -> Result<Bytes, Error> {
+        match self.peek().clone() {
+            Lexeme::Hex(bytes) | Lexeme::Str(bytes) => {
+                self.next();
+                Ok(Bytes::from(bytes))
+            }
+            _ => self.parse_variable("bytes"),
+        }
+    }
+    fn parse_fixed_hex(&mut self, len: usize, expected: &str) -> Result<Vec<u8>, Error> {
+        match self.peek().clone() {
+            Lexeme::Hex(ref bytes) if bytes.len() == len => {
+                self.next();
+                Ok(bytes.clone())
+            }
+            _ => Err(self.error(&format!("expected {}", expected))),
+        }
+    }
+    fn parse_address(&mut self) -> Result<Address, Error> {
+        Ok(Address::from_slice(
+            &self.parse_fixed_hex(20, "an address")?,
+        ))
+    }
+    fn parse_hash(&mut self) -> Result<H256, Error> {
+        Ok(H256::from_slice(&self.parse_fixed_hex(32, "a hash")?))
+    }
+    fn parse_number(&mut self) -> Result<u64, Error> {
+        if let Lexeme::Number(n) = self.peek() {
+            let n = *n;
+            self.next();
+            Ok(n)
+        } else {
+            Err(self.error("expected a number"))
+        }
+    }
+    fn parse_range(&mut self) -> Result<Range, Error> {
+        let start = self.parse_number()?;
+        self.expect(&Lexeme::DotDot)?;
+        let end = self.parse_number()?;
+        if end < start {
+            return Err(self.error("range ends before its start"));
+        }
+        Ok(Range::new(start, end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::parse_property;
+    use crate::error::ErrorKind;
+    use crate::statements::create_plasma_property;
+    use crate::types::{
+        ForAllSuchThatInput, HasLowerNonceInput, Integer, Property, Quantifier,
+        ThereExistsSuchThatInput, Variable,
+    };
+    use ethereum_types::Address;
+    use plasma_core::data_structure::abi::Encodable;
+    use plasma_core::data_structure::Range;
+
+    #[test]
+    fn test_parse_plasma_property() {
+        let property = parse_property(
+            "ForAll block_number < 10: \
+             ForAll property in BlockRange(block_number, 0..100): property",
+        )
+        .unwrap();
+        assert_eq!(
+            property.to_abi(),
+            create_plasma_property(Integer(10), Range::new(0, 100)).to_abi()
+        );
+    }
+
+    #[test]
+    fn test_parse_signed_messages() {
+        let property = parse_property(
+            "ForAll m in SignedBy(0x0000000000000000000000000000000000000001): \
+             ThereExists n < 5: HasLowerNonce(m, n)",
+        )
+        .unwrap();
+        let m = Variable::new("m");
+        let n = Variable::new("n");
+        let expected = Property::ForAllSuchThatDecider(Box::new(ForAllSuchThatInput::new(
+            Quantifier::SignedByQuantifier(Address::from_low_u64_be(1)),
+            m.clone(),
+            Property::ThereExistsSuchThatDecider(Box::new(ThereExistsSuchThatInput::new(
+                Quantifier::NonnegativeIntegerLessThanQuantifier(Integer(5)),
+                n.clone(),
+                Property::HasLowerNonceDecider(HasLowerNonceInput::new(
+                    m.placeholder(),
+                    n.placeholder(),
+                )),
+                None,
+            ))),
+            None,
+        )));
+        assert_eq!(property.to_abi(), expected.to_abi());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for source in [
+            "",
+            "ForAll b < 10 p",
+            "ForAll And < 10: p",
+            "ForAll b < b: p",
+            "Not(PreimageExists(0x01))",
+            "IncludedInIntervalTreeAtBlock(1, 10..0)",
+            "And(p, q)",
+            "Or(SignedBy(\"m\", 0x0000000000000000000000000000000000000001))",
+        ]
+        .iter()
+        {
+            match parse_property(source).unwrap_err().kind() {
+                ErrorKind::InvalidSyntax => (),
+                kind => panic!("unexpected error {}", kind),
+            }
+        }
+    }
+
+}
@@ -0,0 +1,269 @@
+use crate::db::Message;
+use crate::types::{
+    Integer, Placeholder, Property, Quantifier, QuantifierResultItem, Variable, Witness,
+};
+use bytes::Bytes;
+use ethabi::Token;
+use ethereum_types::Address;
+use plasma_core::data_structure::abi::Encodable;
+use plasma_core::data_structure::Range;
+
+/// Prints the property in the DSL, placeholders of variables are printed as their names
+pub fn print_property(property: &Property) -> String {
+    Printer { scope: vec![] }.property(property)
+}
+
+/// Prints the witness in the DSL
+pub fn print_witness(witness: &Witness) -> String {
+    Printer { scope: vec![] }.witness(witness)
+}
+
+/// Prints the quantified result as a value of the DSL
+pub fn print_item(item: &QuantifierResultItem) -> String {
+    let mut printer = Printer { scope: vec![] };
+    match item {
+        QuantifierResultItem::Integer(integer) => printer.integer(*integer),
+        QuantifierResultItem::Bytes(bytes) => printer.bytes(bytes),
+        QuantifierResultItem::Message(message) => printer.message(message),
+        QuantifierResultItem::Property(property) => printer.property(property),
+    }
+}
+
+struct Printer {
+    // variables bound by enclosing quantified properties, the innermost is the last
+    scope: Vec<Variable>,
+}
+
+impl Printer {
+    /// Returns the name of the innermost variable whose placeholder matches
+    fn lookup<T: Placeholder>(&self, matches: impl Fn(&T) -> bool) -> Option<String> {
+        self.scope
+            .iter()
+            .rev()
+            .find(|variable| matches(&variable.placeholder()))
+            .map(|variable| String::from_utf8_lossy(variable.get_name()).to_string())
+    }
+    fn property(&mut self, property: &Property) -> String {
+        match property {
+            Property::ForAllSuchThatDecider(input) => self.quantified(
+                "ForAll",
+                input.get_quantifier(),
+                input.get_variable(),
+                input.get_property_template(),
+                input.get_witness_template(),
+            ),
+            Property::ThereExistsSuchThatDecider(input) => self.quantified(
+                "ThereExists",
+                input.get_quantifier(),
+                input.get_variable(),
+                input.get_property_template(),
+                input.get_witness_template(),
+            ),
+            Property::AndDecider(input) => format!(
+                "And({}, {})",
+                self.operand(input.get_left(), input.get_left_witness()),
+                self.operand(input.get_right(), input.get_right_witness())
+            ),
+            Property::OrDecider(input) => format!(
+                "Or({}, {})",
+                self.operand(input.get_left(), input.get_left_witness()),
+                self.operand(input.get_right(), input.get_right_witness())
+            ),
+            Property::NotDecider(input) => format!(
+                "Not({})",
+                self.operand(input.get_property(), input.get_witness())
+            ),
+            Property::PreimageExistsDecider(input) => format!(
+                "PreimageExists(0x{})",
+                hex::encode(input.get_hash().as_bytes())
+            ),
+            Property::SignedByDecider(input) => format!(
+                "SignedBy({}, {})",
+                self.bytes(input.get_message()),
+                address(&input.get_public_key())
+            ),
+            Property::HasLowerNonceDecider(input) => format!(
+                "HasLowerNonce({}, {})",
+                self.message(input.get_message()),
+                self.integer(input.get_nonce())
+            ),
+            Property::ChannelUpdateSignatureExistsDecider(input) => format!(
+                "ChannelUpdateSignatureExists({}, {}, {})",
+                self.bytes(&input.channel_id),
+                self.integer(input.nonce),
+                address(&input.particilant)
+            ),
+            Property::IncludedInIntervalTreeAtBlockDecider(input) => format!(
+                "IncludedInIntervalTreeAtBlock({}, {})",
+                self.integer(input.get_block_number()),
+                range(&input.get_coin_range())
+            ),
+            Property::CustomDecider(input) => {
+                let name = self.lookup(|placeholder: &Property| match placeholder {
+                    Property::CustomDecider(placeholder) => placeholder == input,
+                    _ => false,
+                });
+                name.unwrap_or_else(|| {
+                    format!(
+                        "Custom({}, {})",
+                        address(&input.get_decider_id()),
+                        self.bytes(input.get_input())
+                    )
+                })
+            }
+        }
+    }
+    fn quantified(
+        &mut self,
+        name: &str,
+        quantifier: &Quantifier,
+        variable: &Variable,
+        template: &Property,
+        witness_template: &Option<Witness>,
+    ) -> String {
+        let quantifier = match quantifier {
+            Quantifier::NonnegativeIntegerLessThanQuantifier(upper_bound) => {
+                format!("< {}", self.integer(*upper_bound))
+            }
+            Quantifier::IntegerRangeQuantifier(input) => format!("in {}", range(input)),
+            Quantifier::BlockRangeQuantifier(input) => format!(
+                "in BlockRange({}, {})",
+                self.integer(input.get_block_number()),
+                range(&input.get_coin_range())
+            ),
+            Quantifier::SignedByQuantifier(signer) => format!("in SignedBy({})", address(signer)),
+        };
+        self.scope.push(variable.clone());
+        let witness = witness_template
+            .as_ref()
+            .map(|witness| format!(" with {}", self.witness(witness)))
+            .unwrap_or_default();
+        let template = self.property(template);
+        self.scope.pop();
+        format!(
+            "{} {} {}{}: {}",
+            name,
+            String::from_utf8_lossy(variable.get_name()),
+            quantifier,
+            witness,
+            template
+        )
+    }
+    fn operand(&mut self, property: &Property, witness: &Witness) -> String {
+        let property = self.property(property);
+        match witness {
+            Witness::Bytes(bytes) if bytes.is_empty() => property,
+            _ => format!("{} with {}", property, self.witness(witness)),
+        }
+    }
+    fn witness(&mut self, witness: &Witness) -> String {
+        match witness {
+            Witness::Bytes(bytes) => self.bytes(bytes),
+            Witness::IncludedInIntervalTreeAtBlock(inclusion_proof, data_block) => format!(
+                "Inclusion({}, {}, {}, {})",
+                self.bytes(inclusion_proof),
+                self.integer(data_block.get_leaf_index()),
+                range(&data_block.get_updated_range()),
+                self.property(data_block.get_property())
+            ),
+        }
+    }
+    fn message(&self, message: &Message) -> String {
+        let abi = message.to_abi();
+        if let Some(name) = self.lookup(|placeholder: &Message| placeholder.to_abi() == abi) {
+            return name;
+        }
+        let fields: Vec<String> = message
+            .to_tuple()
+            .into_iter()
+            .map(|token| self.token(token))
+            .collect();
+        format!("Message({})", fields.join(", "))
+    }
+    /// Prints fields of messages
+    fn token(&self, token: Token) -> String {
+        match token {
+            Token::Bytes(bytes) => self.bytes(&Bytes::from(bytes)),
+            Token::Address(a) => address(&a),
+            Token::Uint(n) => self.integer(Integer(n.low_u64())),
+            Token::Array(tokens) => format!(
+                "[{}]",
+                tokens
+                    .into_iter()
+                    .map(|token| self.token(token))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            token => format!("{:?}", token),
+        }
+    }
+    fn integer(&self, integer: Integer) -> String {
+        self.lookup(|placeholder: &Integer| *placeholder == integer)
+            .unwrap_or_else(|| integer.0.to_string())
+    }
+    fn bytes(&self, bytes: &Bytes) -> String {
+        if let Some(name) = self.lookup(|placeholder: &Bytes| placeholder == bytes) {
+            return name;
+        }
+        let printable = bytes
+            .iter()
+            .all(|c| (b' '..=b'~').contains(c) && *c != b'"');
+        if !bytes.is_empty() && printable {
+            format!("\"{}\"", String::from_utf8_lossy(bytes))
+        } else {
+            format!("0x{}", hex::encode(bytes))
+        }
+    }
+}
+
+fn address(address: &Address) -> String {
+    format!("0x{}", hex::encode(address.as_bytes()))
+}
+
+fn range(range: &Range) -> String {
+    format!("{}..{}", range.get_start(), range.get_end())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::print_property;
+    use crate::dsl::parse_property;
+    use crate::statements::create_plasma_property;
+    use crate::types::Integer;
+    use plasma_core::data_structure::abi::Encodable;
+    use plasma_core::data_structure::Range;
+
+    #[test]
+    fn test_print_plasma_property() {
+        assert_eq!(
+            print_property(&create_plasma_property(Integer(10), Range::new(0, 100))),
+            "ForAll block_number < 10: \
+             ForAll property in BlockRange(block_number, 0..100): property"
+        );
+    }
+
+    #[test]
+    fn test_print_and_parse() {
+        let sources = [
+            "Not(PreimageExists(0x0000000000000000000000000000000000000000000000000000000000000001) \
+             with \"preimage\")",
+            "ForAll m in SignedBy(0x0000000000000000000000000000000000000001) with m: \
+             Or(HasLowerNonce(m, 3), ChannelUpdateSignatureExists(\"channel\", 3, \
+             0x0000000000000000000000000000000000000002) with 0x00ff)",
+            "ThereExists n in 0..5: And(HasLowerNonce(Message(\"channel\", \
+             0x0000000000000000000000000000000000000001, \
+             0x0000000000000000000000000000000000000002, n, \
+             [0x0000000000000000000000000000000000000001], 0x, 0x), 7), \
+             IncludedInIntervalTreeAtBlock(n, 0..100) with Inclusion(0x01, n, 0..50, \
+             Custom(0x0000000000000000000000000000000000000003, \"input\")))",
+        ];
+        for source in sources.iter() {
+            let property = parse_property(source).unwrap();
+            assert_eq!(&print_property(&property), source);
+            let reparsed = parse_property(&print_property(&property)).unwrap();
+            assert_eq!(reparsed.to_abi(), property.to_abi());
+        }
+    }
+
+}
@@ -23,6 +23,28 @@ pub enum ErrorKind {
     Undecided,
     #[fail(display = "CannotDecide")]
     CannotDecide,
+    #[fail(display = "Invalid decider config")]
+    InvalidDeciderConfig,
+    #[fail(display = "Invalid witness")]
+    InvalidWitness,
+    #[fail(display = "Missing preimage")]
+    MissingPreimage,
+    #[fail(display = "Invalid quantifier input")]
+    InvalidQuantifierInput,
+    #[fail(display = "Invalid syntax")]
+    InvalidSyntax,
+    #[fail(display = "Invalid implication proof")]
+    InvalidImplicationProof,
+    #[fail(display = "Invalid contradiction")]
+    InvalidContradiction,
+    #[fail(display = "Invalid signature")]
+    InvalidSignature,
+    #[fail(display = "Invalid channel update")]
+    InvalidChannelUpdate,
+    #[fail(display = "Invalid channel status")]
+    InvalidChannelStatus,
+    #[fail(display = "Conflicting message")]
+    ConflictingMessage,
 }
 
 #[derive(Debug)]
@@ -54,6 +76,15 @@ impl Error {
     pub fn kind(&self) -> &ErrorKind {
         self.inner.get_context()
     }
+
+    /// Returns true if the property may be decided later by other witnesses or local data.
+    /// Invalid witnesses are errors of the caller, so they aren't undecided.
+    pub fn is_undecided(&self) -> bool {
+        match self.kind() {
+            ErrorKind::Undecided | ErrorKind::CannotDecide | ErrorKind::MissingPreimage => true,
+            _ => false,
+        }
+    }
 }
 
 impl From<ErrorKind> for Error {
@@ -1,32 +1,41 @@
 #[macro_use]
 extern crate lazy_static;
 
+pub mod channel;
+pub mod contradiction;
 pub mod db;
+pub mod decider_registry;
 pub mod deciders;
+pub mod dsl;
 pub mod error;
 pub mod property_executor;
 pub mod quantifiers;
 pub mod statements;
+pub mod trace;
 pub mod types;
+pub mod verifier;
 
 pub use self::property_executor::DecideMixin;
 
 #[cfg(test)]
 mod tests {
 
-    use crate::db::HashPreimageDb;
+    use crate::db::{BlockRootDb, HashPreimageDb, Message};
     use crate::deciders::preimage_exists_decider::Verifier;
     use crate::deciders::SignVerifier;
     use crate::property_executor::PropertyExecutor;
     use crate::statements::create_plasma_property;
     use crate::types::{
-        AndDeciderInput, Decision, ForAllSuchThatInput, HasLowerNonceInput, Integer,
-        IntegerRangeQuantifierInput, PreimageExistsInput, Property, PropertyFactory, Quantifier,
-        QuantifierResultItem, SignedByInput, Witness, WitnessFactory,
+        AndDeciderInput, Decision, ForAllSuchThatInput, HasLowerNonceInput,
+        IncludedInIntervalTreeAtBlockInput, Integer, IntegerRangeQuantifierInput, PlasmaDataBlock,
+        PreimageExistsInput, Property, Quantifier, SignedByInput, Variable, Witness,
     };
     use bytes::Bytes;
+    use ethabi::Token;
     use ethereum_types::Address;
     use ethsign::SecretKey;
+    use merkle_interval_tree::{MerkleIntervalNode, MerkleIntervalTree};
+    use plasma_core::data_structure::abi::{Decodable, Encodable};
     use plasma_core::data_structure::Range;
     use plasma_db::impls::kvs::CoreDbLevelDbImpl;
     use plasma_db::traits::kvs::KeyValueStore;
@@ -44,58 +53,53 @@ mod tests {
         }
     }
 
+    fn create_message(nonce: Integer) -> Message {
+        Message::from_tuple(&[
+            Token::Bytes(b"channel".to_vec()),
+            Token::Address(Address::zero()),
+            Token::Address(Address::zero()),
+            Token::Uint(nonce.0.into()),
+            Token::Array(vec![]),
+            Token::Bytes(vec![]),
+            Token::Bytes(vec![]),
+        ])
+        .unwrap()
+    }
+
     ///
     /// ```ignore
-    /// ForAllSuchThat(nonce, IntegerRangeQuantifier(0, 10), PropertyFactory((nonce) => {
-    ///   PreimageExistsDecider(nonce)
-    /// }))
+    /// ForAllSuchThat(nonce, IntegerRangeQuantifier(1, 10), (nonce) => {
+    ///   HasLowerNonceDecider(message_with_nonce_0, nonce)
+    /// })
     /// ```
     ///
     #[test]
-    fn test_decide_range_and_preimage() {
+    fn test_decide_range_and_lower_nonce() {
+        let nonce = Variable::new("nonce");
         let property = Property::ForAllSuchThatDecider(Box::new(ForAllSuchThatInput::new(
-            Quantifier::IntegerRangeQuantifier(IntegerRangeQuantifierInput::new(0, 10)),
-            Some(PropertyFactory::new(Box::new(|item| {
-                if let QuantifierResultItem::Integer(number) = item {
-                    Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(
-                        Verifier::static_hash(&number.into()),
-                    )))
-                } else {
-                    panic!("invalid type in PropertyFactory");
-                }
-            }))),
-            Some(WitnessFactory::new(Box::new(|item| {
-                if let QuantifierResultItem::Integer(number) = item {
-                    Witness::Bytes(number.into())
-                } else {
-                    panic!("invalid type in PropertyFactory");
-                }
-            }))),
+            Quantifier::IntegerRangeQuantifier(IntegerRangeQuantifierInput::new(1, 10)),
+            nonce.clone(),
+            Property::HasLowerNonceDecider(HasLowerNonceInput::new(
+                create_message(Integer(0)),
+                nonce.placeholder(),
+            )),
+            None,
         )));
         let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
-        store_preimage(&decider);
         let decided: Decision = decider.decide(&property, None).unwrap();
         assert_eq!(decided.get_outcome(), true);
     }
 
     /// Test to fail
     #[test]
-    #[should_panic]
     fn test_fail_to_decide_range_and_preimage() {
         let property = Property::ForAllSuchThatDecider(Box::new(ForAllSuchThatInput::new(
             Quantifier::IntegerRangeQuantifier(IntegerRangeQuantifierInput::new(0, 10)),
-            Some(PropertyFactory::new(Box::new(|item| {
-                if let QuantifierResultItem::Integer(number) = item {
-                    Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(
-                        Verifier::static_hash(&number.into()),
-                    )))
-                } else {
-                    panic!("invalid type in PropertyFactory");
-                }
-            }))),
-            Some(WitnessFactory::new(Box::new(|_item| {
-                Witness::Bytes(Bytes::from(&b"aaa"[..]))
-            }))),
+            Variable::new("nonce"),
+            Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(
+                Verifier::static_hash(&Integer(100).into()),
+            ))),
+            Some(Witness::Bytes(Bytes::from(&b"aaa"[..]))),
         )));
         let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
         let decided_result = decider.decide(&property, None);
@@ -104,34 +108,24 @@ mod tests {
 
     ///
     /// ```ignore
-    /// ForAllSuchThat(nonce, LessThanQuantifier(10), PropertyFactory((nonce) => {
-    ///   PreimageExistsDecider(nonce)
-    /// }))
+    /// ForAllSuchThat(nonce, LessThanQuantifier(10), (nonce) => {
+    ///   HasLowerNonceDecider(message_with_nonce(nonce), 10)
+    /// })
     /// ```
     ///
     #[test]
-    fn test_decide_less_than_and_preimage() {
+    fn test_decide_less_than_and_lower_nonce() {
+        let nonce = Variable::new("nonce");
         let property = Property::ForAllSuchThatDecider(Box::new(ForAllSuchThatInput::new(
             Quantifier::NonnegativeIntegerLessThanQuantifier(Integer(10)),
-            Some(PropertyFactory::new(Box::new(|item| {
-                if let QuantifierResultItem::Integer(number) = item {
-                    Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(
-                        Verifier::static_hash(&number.into()),
-                    )))
-                } else {
-                    panic!("invalid type in PropertyFactory");
-                }
-            }))),
-            Some(WitnessFactory::new(Box::new(|item| {
-                if let QuantifierResultItem::Integer(number) = item {
-                    Witness::Bytes(number.into())
-                } else {
-                    panic!("invalid type in PropertyFactory");
-                }
-            }))),
+            nonce.clone(),
+            Property::HasLowerNonceDecider(HasLowerNonceInput::new(
+                create_message(nonce.placeholder()),
+                Integer(10),
+            )),
+            None,
         )));
         let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
-        store_preimage(&decider);
         let decided: Decision = decider.decide(&property, None).unwrap();
         assert_eq!(decided.get_outcome(), true);
     }
@@ -152,15 +146,14 @@ mod tests {
         let alice: Address = secret_key_alice.public().address().into();
         let bob: Address = secret_key_bob.public().address().into();
         let _nonce = Integer(10);
+        let message_variable = Variable::new("message");
         let left_property = Property::ForAllSuchThatDecider(Box::new(ForAllSuchThatInput::new(
             Quantifier::SignedByQuantifier(alice),
-            Some(PropertyFactory::new(Box::new(|item| {
-                if let QuantifierResultItem::Message(message) = item {
-                    Property::HasLowerNonceDecider(HasLowerNonceInput::new(message, Integer(11)))
-                } else {
-                    panic!("invalid type in PropertyFactory");
-                }
-            }))),
+            message_variable.clone(),
+            Property::HasLowerNonceDecider(HasLowerNonceInput::new(
+                message_variable.placeholder(),
+                Integer(11),
+            )),
             None,
         )));
         let right_property =
@@ -178,6 +171,45 @@ mod tests {
     }
 
     /// plasma
+    #[test]
+    fn test_decide_plasma_checkpoint() {
+        let range = Range::new(0, 100);
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        store_preimage(&decider);
+        for i in 0..2 {
+            let block_number = Integer(i);
+            let property = Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(
+                Verifier::static_hash(&block_number.into()),
+            )));
+            let leaves: [MerkleIntervalNode<u64>; 2] = [
+                MerkleIntervalNode::Leaf {
+                    end: 100,
+                    data: Bytes::from(property.to_abi()),
+                },
+                MerkleIntervalNode::Leaf {
+                    end: 200,
+                    data: Bytes::from(&b"other"[..]),
+                },
+            ];
+            let tree = MerkleIntervalTree::generate(&leaves);
+            assert!(BlockRootDb::new(decider.get_db())
+                .store_root(block_number, &tree.get_root())
+                .is_ok());
+            let inclusion = Property::IncludedInIntervalTreeAtBlockDecider(
+                IncludedInIntervalTreeAtBlockInput::new(block_number, range),
+            );
+            let witness = Witness::IncludedInIntervalTreeAtBlock(
+                tree.get_inclusion_proof(0, 2),
+                PlasmaDataBlock::new(Integer(0), range, property),
+            );
+            let decided: Decision = decider.decide(&inclusion, Some(witness)).unwrap();
+            assert_eq!(decided.get_outcome(), true);
+        }
+        let checkpoint_property = create_plasma_property(Integer(2), range);
+        let decided: Decision = decider.decide(&checkpoint_property, None).unwrap();
+        assert_eq!(decided.get_outcome(), true);
+    }
+
     #[test]
     fn test_fail_to_decide_plasma_checkpoint() {
         let block_number = Integer(10);
@@ -1,18 +1,30 @@
 use crate::db::MessageDb;
+use crate::decider_registry::DeciderRegistry;
 use crate::deciders::{
-    AndDecider, ForAllSuchThatDecider, HasLowerNonceDecider, NotDecider, OrDecider,
-    PreimageExistsDecider, SignedByDecider,
+    AndDecider, ChannelUpdateSignatureExistsDecider, ForAllSuchThatDecider, HasLowerNonceDecider,
+    IncludedInIntervalTreeAtBlock, NotDecider, OrDecider, PreimageExistsDecider, SignedByDecider,
+    ThereExistsSuchThatDecider,
 };
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 use crate::quantifiers::{
     BlockRangeQuantifier, IntegerRangeQuantifier, NonnegativeIntegerLessThanQuantifier,
-    SignedByQuantifier,
+    QuantifiedItems, SignedByQuantifier, DEFAULT_PAGE_SIZE,
 };
+use crate::trace::{trace_leaf, DecisionTrace, Tracer};
+use crate::types::core::DeciderId;
+use crate::types::decider_id::get_decider_kind;
 use crate::types::Decider;
-use crate::types::{Decision, Property, Quantifier, QuantifierResult, Witness};
+use crate::types::{
+    Decision, Property, Quantifier, QuantifierCursor, QuantifierResult, QuantifierResultItem,
+    Variable, Witness,
+};
+use ethereum_types::H256;
+use plasma_core::data_structure::abi::{Decodable, Hashable};
 use plasma_db::traits::db::DatabaseTrait;
 use plasma_db::traits::kvs::KeyValueStore;
 use plasma_db::RangeDbImpl;
+use std::collections::HashMap;
+use std::sync::RwLock;
 
 /// Mixin for adding decide method to Property
 pub trait DecideMixin<KVS: KeyValueStore> {
@@ -45,6 +57,11 @@ pub struct PropertyExecutor<KVS: KeyValueStore> {
     db: KVS,
     message_db: MessageDb<KVS>,
     range_db: RangeDbImpl<KVS>,
+    decider_registry: DeciderRegistry<KVS>,
+    /// True decisions of cacheable properties keyed by the hash of properties
+    decision_cache: RwLock<HashMap<H256, Decision>>,
+    /// Records the decision tree while `decide_traced` is deciding
+    tracer: Tracer,
 }
 
 impl<KVS> Default for PropertyExecutor<KVS>
@@ -56,6 +73,9 @@ where
             db: KVS::open("kvs"),
             message_db: MessageDb::from(KVS::open("message")),
             range_db: RangeDbImpl::from(KVS::open("range")),
+            decider_registry: Default::default(),
+            decision_cache: Default::default(),
+            tracer: Default::default(),
         }
     }
 }
@@ -73,7 +93,154 @@ where
     pub fn get_range_db(&self) -> &RangeDbImpl<KVS> {
         &self.range_db
     }
+    pub fn get_decider_registry(&self) -> &DeciderRegistry<KVS> {
+        &self.decider_registry
+    }
+    /// Registers a custom decider which decides `Property::CustomDecider` with `decider_id`.
+    /// IDs of built-in deciders can't be registered.
+    pub fn register_decider<D>(&mut self, decider_id: DeciderId) -> Result<(), Error>
+    where
+        D: Decider,
+        D::Input: Decodable<Ok = D::Input>,
+    {
+        if get_decider_kind(decider_id).is_some() {
+            return Err(Error::from(ErrorKind::InvalidDeciderConfig));
+        }
+        self.decider_registry.register::<D>(decider_id)
+    }
+    /// Decides the property, true decisions of the property and nested properties are cached
+    /// if they are cacheable.
     pub fn decide(&self, property: &Property, witness: Option<Witness>) -> Result<Decision, Error> {
+        self.decide_with(property, witness, false)
+    }
+    /// Decides the property, `no_cache` decides it again instead of using its cached decision.
+    /// Nested properties use the cache, which only has decisions that can't turn false.
+    pub fn decide_with(
+        &self,
+        property: &Property,
+        witness: Option<Witness>,
+        no_cache: bool,
+    ) -> Result<Decision, Error> {
+        self.tracer.begin(property, &witness);
+        let key = self.get_cache_key(property);
+        let cached = match key {
+            Some(key) if !no_cache => self.get_cached(key),
+            _ => None,
+        };
+        let result = match cached {
+            Some(decision) => Ok(decision),
+            None => {
+                let result = self.dispatch_decide(property, witness);
+                self.cache(key, &result);
+                result
+            }
+        };
+        self.tracer.end(&result);
+        result
+    }
+    /// Decides the property again without its cached decision, and records the decision tree
+    /// of each nested property, its witness and outcome.
+    pub fn decide_traced(
+        &self,
+        property: &Property,
+        witness: Option<Witness>,
+    ) -> (Result<Decision, Error>, DecisionTrace) {
+        self.tracer.start();
+        let result = self.decide_with(property, witness.clone(), true);
+        let trace = self
+            .tracer
+            .finish()
+            .unwrap_or_else(|| trace_leaf(property, &witness, &result));
+        (result, trace)
+    }
+    /// Labels the next traced decision with the quantified result bound to the variable.
+    /// Quantifying deciders call it before deciding the bound property.
+    pub fn trace_binding(&self, variable: &Variable, item: &QuantifierResultItem) {
+        self.tracer.bind(variable, item);
+    }
+    /// Binds the template and the witness template with the quantified result, and decides
+    /// the bound property labeled with the binding. The decision is `None` if it is undecided.
+    /// Quantifying deciders call it for each quantified result.
+    pub fn decide_bound(
+        &self,
+        variable: &Variable,
+        template: &Property,
+        witness_template: &Option<Witness>,
+        item: &QuantifierResultItem,
+    ) -> Result<(Property, Option<Decision>), Error> {
+        let property = variable.bind_property(template, item)?;
+        let witness = witness_template
+            .as_ref()
+            .map(|template| variable.bind_witness(template, item))
+            .transpose()?;
+        self.trace_binding(variable, item);
+        let decision = self.try_decide(&property, witness)?;
+        Ok((property, decision))
+    }
+    /// Decides the property, `Ok(None)` if it is undecided.
+    /// Deciders use it to tell false from undecided.
+    pub fn try_decide(
+        &self,
+        property: &Property,
+        witness: Option<Witness>,
+    ) -> Result<Option<Decision>, Error> {
+        match self.decide(property, witness) {
+            Ok(decision) => Ok(Some(decision)),
+            Err(ref error) if error.is_undecided() => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+    /// Returns the cached decision if any, otherwise asks the decider of the property
+    pub fn check_decision(&self, property: &Property) -> Result<Decision, Error> {
+        let key = self.get_cache_key(property);
+        if let Some(decision) = key.and_then(|key| self.get_cached(key)) {
+            return Ok(decision);
+        }
+        let result = self.dispatch_check_decision(property);
+        self.cache(key, &result);
+        result
+    }
+    pub fn get_cached_decision(&self, property: &Property) -> Option<Decision> {
+        self.get_cache_key(property)
+            .and_then(|key| self.get_cached(key))
+    }
+    pub fn clear_decision_cache(&self) {
+        self.decision_cache.write().unwrap().clear();
+    }
+    /// Returns true if true decisions of the property are cached,
+    /// custom deciders are cacheable only if they opt in with `Decider::IS_CACHEABLE`.
+    pub fn is_cacheable(&self, property: &Property) -> bool {
+        property.is_cacheable(&|decider_id| self.decider_registry.is_cacheable(decider_id))
+    }
+    /// Returns None if decisions of the property aren't cacheable
+    fn get_cache_key(&self, property: &Property) -> Option<H256> {
+        if self.is_cacheable(property) {
+            Some(property.hash())
+        } else {
+            None
+        }
+    }
+    fn get_cached(&self, key: H256) -> Option<Decision> {
+        self.decision_cache.read().unwrap().get(&key).cloned()
+    }
+    /// Caches true decisions only, stored messages, preimages and block roots
+    /// can turn a false decision true.
+    fn cache(&self, key: Option<H256>, result: &Result<Decision, Error>) {
+        match (key, result) {
+            (Some(key), Ok(decision)) if decision.get_outcome() => {
+                self.decision_cache
+                    .write()
+                    .unwrap()
+                    .insert(key, decision.clone());
+            }
+            _ => (),
+        }
+    }
+    fn dispatch_decide(
+        &self,
+        property: &Property,
+        witness: Option<Witness>,
+    ) -> Result<Decision, Error> {
         match property {
             Property::AndDecider(input) => AndDecider::decide(self, input, witness),
             Property::NotDecider(input) => NotDecider::decide(self, input, witness),
@@ -88,10 +255,24 @@ where
             Property::HasLowerNonceDecider(input) => {
                 HasLowerNonceDecider::decide(self, input, witness)
             }
-            _ => panic!("not implemented!!"),
+            Property::ChannelUpdateSignatureExistsDecider(input) => {
+                ChannelUpdateSignatureExistsDecider::decide(self, input, witness)
+            }
+            Property::IncludedInIntervalTreeAtBlockDecider(input) => {
+                IncludedInIntervalTreeAtBlock::decide(self, input, witness)
+            }
+            Property::ThereExistsSuchThatDecider(input) => {
+                ThereExistsSuchThatDecider::decide(self, input, witness)
+            }
+            Property::CustomDecider(input) => self.decider_registry.decide(
+                self,
+                input.get_decider_id(),
+                input.get_input(),
+                witness,
+            ),
         }
     }
-    pub fn check_decision(&self, property: &Property) -> Result<Decision, Error> {
+    fn dispatch_check_decision(&self, property: &Property) -> Result<Decision, Error> {
         match property {
             Property::AndDecider(input) => AndDecider::check_decision(self, input),
             Property::NotDecider(input) => NotDecider::check_decision(self, input),
@@ -106,22 +287,334 @@ where
             Property::HasLowerNonceDecider(input) => {
                 HasLowerNonceDecider::check_decision(self, input)
             }
-            _ => panic!("not implemented!!"),
+            Property::ChannelUpdateSignatureExistsDecider(input) => {
+                ChannelUpdateSignatureExistsDecider::check_decision(self, input)
+            }
+            Property::IncludedInIntervalTreeAtBlockDecider(input) => {
+                IncludedInIntervalTreeAtBlock::check_decision(self, input)
+            }
+            Property::ThereExistsSuchThatDecider(input) => {
+                ThereExistsSuchThatDecider::check_decision(self, input)
+            }
+            Property::CustomDecider(input) => self.decider_registry.check_decision(
+                self,
+                input.get_decider_id(),
+                input.get_input(),
+            ),
         }
     }
-    pub fn get_all_quantified(&self, quantifier: &Quantifier) -> QuantifierResult {
+    pub fn get_all_quantified(&self, quantifier: &Quantifier) -> Result<QuantifierResult, Error> {
+        self.get_quantified(quantifier, None, usize::max_value())
+    }
+    /// Returns a page of at most `limit` results from the cursor of the previous page
+    pub fn get_quantified(
+        &self,
+        quantifier: &Quantifier,
+        cursor: Option<&QuantifierCursor>,
+        limit: usize,
+    ) -> Result<QuantifierResult, Error> {
         match quantifier {
             Quantifier::IntegerRangeQuantifier(input) => {
-                IntegerRangeQuantifier::get_all_quantified(*input)
-            }
-            Quantifier::NonnegativeIntegerLessThanQuantifier(upper_bound) => {
-                NonnegativeIntegerLessThanQuantifier::get_all_quantified(*upper_bound)
+                IntegerRangeQuantifier::get_quantified(*input, cursor, limit)
             }
+            Quantifier::NonnegativeIntegerLessThanQuantifier(upper_bound) => Ok(
+                NonnegativeIntegerLessThanQuantifier::get_quantified(*upper_bound, cursor, limit),
+            ),
             Quantifier::BlockRangeQuantifier(input) => {
-                BlockRangeQuantifier::get_all_quantified(self, &*input)
+                BlockRangeQuantifier::get_quantified(self, &*input, cursor, limit)
             }
             Quantifier::SignedByQuantifier(signer) => {
-                SignedByQuantifier::get_all_quantified(self, *signer)
+                SignedByQuantifier::get_quantified(self, *signer, cursor, limit)
+            }
+        }
+    }
+    /// Iterates results of the quantifier lazily by pages of `DEFAULT_PAGE_SIZE`
+    pub fn iter_quantified<'a>(&'a self, quantifier: &'a Quantifier) -> QuantifiedItems<'a, KVS> {
+        QuantifiedItems::new(self, quantifier, DEFAULT_PAGE_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::PropertyExecutor;
+    use crate::deciders::BlockDeadlineDecider;
+    use crate::dsl::parse_property;
+    use crate::error::{Error, ErrorKind};
+    use crate::types::{
+        AndDeciderInput, BlockDeadlineInput, ChannelUpdateSignatureExistsDeciderInput,
+        CustomDeciderInput, Decider, Decision, ForAllSuchThatInput,
+        IncludedInIntervalTreeAtBlockInput, Integer, NotDeciderInput, PlasmaDataBlock,
+        PreimageExistsInput, Property, Quantifier, SignedByInput, Variable, Witness,
+    };
+    use abi_derive::{AbiDecodable, AbiEncodable};
+    use bytes::Bytes;
+    use ethereum_types::{Address, H256};
+    use plasma_core::data_structure::abi::Encodable;
+    use plasma_core::data_structure::Range;
+    use plasma_db::impls::kvs::CoreDbLevelDbImpl;
+    use plasma_db::traits::kvs::KeyValueStore;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const MESSAGE: &str = "Message(\"channel\", 0x0000000000000000000000000000000000000000, \
+                           0x0000000000000000000000000000000000000000, 3, [], 0x, 0x)";
+
+    // counts of each input number, tests use different numbers to run in parallel
+    static DECIDED_COUNTS: [AtomicUsize; 4] = [
+        AtomicUsize::new(0),
+        AtomicUsize::new(0),
+        AtomicUsize::new(0),
+        AtomicUsize::new(0),
+    ];
+
+    #[derive(Clone, Debug, AbiDecodable, AbiEncodable)]
+    struct CountingInput {
+        number: Integer,
+    }
+
+    /// Counts how many times it decides
+    struct CountingDecider;
+
+    impl Decider for CountingDecider {
+        type Input = CountingInput;
+        const IS_CACHEABLE: bool = true;
+        fn decide<T: KeyValueStore>(
+            _decider: &PropertyExecutor<T>,
+            input: &CountingInput,
+            _witness: Option<Witness>,
+        ) -> Result<Decision, Error> {
+            DECIDED_COUNTS[input.number.0 as usize].fetch_add(1, Ordering::SeqCst);
+            Ok(Decision::new(true, vec![]))
+        }
+        fn check_decision<T: KeyValueStore>(
+            decider: &PropertyExecutor<T>,
+            input: &CountingInput,
+        ) -> Result<Decision, Error> {
+            Self::decide(decider, input, None)
+        }
+    }
+
+    /// Counts like `CountingDecider` without opting in to the cache
+    struct UncachedCountingDecider;
+
+    impl Decider for UncachedCountingDecider {
+        type Input = CountingInput;
+        fn decide<T: KeyValueStore>(
+            decider: &PropertyExecutor<T>,
+            input: &CountingInput,
+            witness: Option<Witness>,
+        ) -> Result<Decision, Error> {
+            CountingDecider::decide(decider, input, witness)
+        }
+        fn check_decision<T: KeyValueStore>(
+            decider: &PropertyExecutor<T>,
+            input: &CountingInput,
+        ) -> Result<Decision, Error> {
+            Self::decide(decider, input, None)
+        }
+    }
+
+    #[test]
+    fn test_decision_cache() {
+        let mut decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let decider_id = Address::from_low_u64_be(200);
+        assert!(decider
+            .register_decider::<CountingDecider>(decider_id)
+            .is_ok());
+        let property = Property::CustomDecider(CustomDeciderInput::new(
+            decider_id,
+            Bytes::from(CountingInput { number: Integer(1) }.to_abi()),
+        ));
+        let not_property = Property::NotDecider(Box::new(NotDeciderInput::new(
+            property.clone(),
+            Witness::Bytes(Bytes::new()),
+        )));
+        assert!(decider.get_cached_decision(&property).is_none());
+        assert!(decider.decide(&property, None).is_ok());
+        assert!(decider.decide(&property, None).is_ok());
+        assert!(decider.check_decision(&property).is_ok());
+        assert_eq!(DECIDED_COUNTS[1].load(Ordering::SeqCst), 1);
+        // the negation is decided false with the cached decision, and isn't cached
+        assert!(!decider.decide(&not_property, None).unwrap().get_outcome());
+        assert_eq!(DECIDED_COUNTS[1].load(Ordering::SeqCst), 1);
+        assert!(decider.get_cached_decision(&not_property).is_none());
+        // opting out decides the property again
+        assert!(decider.decide_with(&property, None, true).is_ok());
+        assert_eq!(DECIDED_COUNTS[1].load(Ordering::SeqCst), 2);
+        decider.clear_decision_cache();
+        assert!(decider.decide(&property, None).is_ok());
+        assert_eq!(DECIDED_COUNTS[1].load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_custom_decider_is_not_cacheable_without_opting_in() {
+        let mut decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let decider_id = Address::from_low_u64_be(204);
+        assert!(decider
+            .register_decider::<UncachedCountingDecider>(decider_id)
+            .is_ok());
+        let property = Property::CustomDecider(CustomDeciderInput::new(
+            decider_id,
+            Bytes::from(CountingInput { number: Integer(3) }.to_abi()),
+        ));
+        assert!(!decider.is_cacheable(&property));
+        assert!(decider.decide(&property, None).unwrap().get_outcome());
+        assert!(decider.decide(&property, None).unwrap().get_outcome());
+        assert!(decider.get_cached_decision(&property).is_none());
+        assert_eq!(DECIDED_COUNTS[3].load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_for_all_such_that_is_not_cacheable() {
+        let mut decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let decider_id = Address::from_low_u64_be(201);
+        assert!(decider
+            .register_decider::<CountingDecider>(decider_id)
+            .is_ok());
+        let property = Property::ForAllSuchThatDecider(Box::new(ForAllSuchThatInput::new(
+            Quantifier::NonnegativeIntegerLessThanQuantifier(Integer(2)),
+            Variable::new("n"),
+            Property::CustomDecider(CustomDeciderInput::new(
+                decider_id,
+                Bytes::from(CountingInput { number: Integer(2) }.to_abi()),
+            )),
+            None,
+        )));
+        assert!(!decider.is_cacheable(&property));
+        assert!(decider.decide(&property, None).unwrap().get_outcome());
+        assert!(decider.get_cached_decision(&property).is_none());
+        // the quantified property is the same for each result, and its decision is cached
+        assert!(decider.decide(&property, None).is_ok());
+        assert_eq!(DECIDED_COUNTS[2].load(Ordering::SeqCst), 1);
+    }
+
+    fn create_witnesses() -> (Witness, Witness) {
+        let bytes_witness = Witness::Bytes(Bytes::from(&b"short"[..]));
+        let inclusion_witness = Witness::IncludedInIntervalTreeAtBlock(
+            Bytes::from(&b"proof"[..]),
+            PlasmaDataBlock::new(
+                Integer(0),
+                Range::new(0, 100),
+                Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(H256::zero()))),
+            ),
+        );
+        (bytes_witness, inclusion_witness)
+    }
+
+    #[test]
+    fn test_decide_with_invalid_witnesses() {
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let (bytes_witness, inclusion_witness) = create_witnesses();
+        let signed_by = Property::SignedByDecider(SignedByInput::new(
+            Bytes::from(&b"message"[..]),
+            Address::zero(),
+        ));
+        let inclusion = Property::IncludedInIntervalTreeAtBlockDecider(
+            IncludedInIntervalTreeAtBlockInput::new(Integer(1), Range::new(0, 100)),
+        );
+        let cases = vec![
+            (
+                Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(H256::zero()))),
+                vec![Some(inclusion_witness.clone())],
+            ),
+            (
+                signed_by.clone(),
+                vec![Some(bytes_witness.clone()), Some(inclusion_witness)],
+            ),
+            (inclusion.clone(), vec![Some(bytes_witness.clone())]),
+            // invalid witnesses of nested properties are reported too
+            (
+                Property::AndDecider(Box::new(AndDeciderInput::new(
+                    parse_property(&format!("HasLowerNonce({}, 5)", MESSAGE)).unwrap(),
+                    Witness::Bytes(Bytes::new()),
+                    signed_by.clone(),
+                    bytes_witness.clone(),
+                ))),
+                vec![None],
+            ),
+            (
+                Property::NotDecider(Box::new(NotDeciderInput::new(
+                    signed_by.clone(),
+                    bytes_witness,
+                ))),
+                vec![None],
+            ),
+        ];
+        for (property, witnesses) in cases.iter() {
+            for witness in witnesses.iter() {
+                match decider
+                    .decide(property, witness.clone())
+                    .unwrap_err()
+                    .kind()
+                {
+                    ErrorKind::InvalidWitness => (),
+                    kind => panic!("unexpected error {}", kind),
+                }
+            }
+        }
+        // missing witnesses may be given later
+        for property in [signed_by, inclusion].iter() {
+            for witness in [None, Some(Witness::Bytes(Bytes::new()))].iter() {
+                assert!(decider
+                    .decide(property, witness.clone())
+                    .unwrap_err()
+                    .is_undecided());
+            }
+        }
+    }
+
+    #[test]
+    fn test_decide_ignoring_witnesses() {
+        let mut decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let deadline_decider_id = Address::from_low_u64_be(202);
+        assert!(decider
+            .register_decider::<BlockDeadlineDecider>(deadline_decider_id)
+            .is_ok());
+        let (bytes_witness, inclusion_witness) = create_witnesses();
+        let parse = |source: &str| parse_property(&source.replace("{m}", MESSAGE)).unwrap();
+        // the outcome, None if it is undecided
+        let cases = vec![
+            (
+                parse("And(HasLowerNonce({m}, 5), HasLowerNonce({m}, 6))"),
+                Some(true),
+            ),
+            (
+                parse("Or(HasLowerNonce({m}, 1), HasLowerNonce({m}, 5))"),
+                Some(true),
+            ),
+            (parse("Not(HasLowerNonce({m}, 5))"), Some(false)),
+            (
+                parse("ForAll n in 4..10: HasLowerNonce({m}, n)"),
+                Some(true),
+            ),
+            (
+                parse("ThereExists n in 0..3: HasLowerNonce({m}, n)"),
+                Some(false),
+            ),
+            (parse("HasLowerNonce({m}, 1)"), Some(false)),
+            (
+                Property::ChannelUpdateSignatureExistsDecider(
+                    ChannelUpdateSignatureExistsDeciderInput::new(
+                        Bytes::from(&b"channel"[..]),
+                        Integer(1),
+                        Address::zero(),
+                    ),
+                ),
+                None,
+            ),
+            (
+                Property::CustomDecider(CustomDeciderInput::new(
+                    deadline_decider_id,
+                    Bytes::from(BlockDeadlineInput::new(Integer(1)).to_abi()),
+                )),
+                Some(false),
+            ),
+        ];
+        let witnesses = vec![None, Some(bytes_witness), Some(inclusion_witness)];
+        for (property, outcome) in cases.iter() {
+            for witness in witnesses.iter() {
+                let decided = decider.try_decide(property, witness.clone()).unwrap();
+                assert_eq!(decided.map(|d| d.get_outcome()), *outcome);
             }
         }
     }
@@ -1,7 +1,9 @@
 pub mod block_range_quantifier;
 pub mod integer_quantifiers;
+pub mod quantified_items;
 pub mod signed_by_quantifier;
 
 pub use self::block_range_quantifier::BlockRangeQuantifier;
 pub use self::integer_quantifiers::{IntegerRangeQuantifier, NonnegativeIntegerLessThanQuantifier};
+pub use self::quantified_items::{QuantifiedItems, DEFAULT_PAGE_SIZE};
 pub use self::signed_by_quantifier::SignedByQuantifier;
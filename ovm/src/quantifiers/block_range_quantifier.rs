@@ -1,12 +1,16 @@
+use crate::error::{Error, ErrorKind};
 use crate::property_executor::PropertyExecutor;
 use crate::types::{
-    BlockRangeQuantifierInput, DecisionValue, Property, QuantifierResult, QuantifierResultItem,
-    Witness,
+    BlockRangeQuantifierInput, DecisionValue, Property, QuantifierCursor, QuantifierResult,
+    QuantifierResultItem, Witness,
 };
 use bytes::Bytes;
 use plasma_core::data_structure::abi::Decodable;
+use plasma_core::data_structure::Range;
+use plasma_db::range::Range as RangeDbRange;
 use plasma_db::traits::kvs::KeyValueStore;
 use plasma_db::traits::rangestore::RangeStore;
+use std::cmp::max;
 
 pub struct BlockRangeQuantifier {}
 
@@ -20,41 +24,78 @@ impl BlockRangeQuantifier {
     pub fn get_all_quantified<KVS>(
         decider: &PropertyExecutor<KVS>,
         input: &BlockRangeQuantifierInput,
-    ) -> QuantifierResult
+    ) -> Result<QuantifierResult, Error>
+    where
+        KVS: KeyValueStore,
+    {
+        Self::get_quantified(decider, input, None, usize::max_value())
+    }
+    /// Returns properties of at most `limit` stored ranges from the coin position of the cursor
+    pub fn get_quantified<KVS>(
+        decider: &PropertyExecutor<KVS>,
+        input: &BlockRangeQuantifierInput,
+        cursor: Option<&QuantifierCursor>,
+        limit: usize,
+    ) -> Result<QuantifierResult, Error>
     where
         KVS: KeyValueStore,
     {
         let block_number = input.get_block_number();
-        let range = input.get_coin_range();
-        let result = decider
+        let coin_range = input.get_coin_range();
+        let start = match cursor {
+            Some(QuantifierCursor::Integer(next)) => max(*next, coin_range.get_start()),
+            _ => coin_range.get_start(),
+        };
+        let mut result: Vec<RangeDbRange> = decider
             .get_range_db()
             .bucket(&Bytes::from("range_at_block"))
             .bucket(&block_number.into())
-            .get(range.get_start(), range.get_end())
-            .unwrap();
-        let sum = result
+            .get(start, coin_range.get_end())
+            .map_err::<Error, _>(Into::into)?
             .iter()
-            .fold(0, |acc, r| acc + r.get_end() - r.get_start());
+            .filter(|r| r.get_end() > start)
+            .cloned()
+            .collect();
+        result.sort_by_key(|r| r.get_start());
+        let next_cursor = if result.len() > limit {
+            result.truncate(limit);
+            result
+                .last()
+                .map(|r| QuantifierCursor::Integer(r.get_end()))
+        } else {
+            None
+        };
+        let end = match next_cursor {
+            Some(QuantifierCursor::Integer(next)) => next,
+            _ => coin_range.get_end(),
+        };
+        let range = Range::new(start, end);
+        // stored decisions can be wider than the range
+        let sum = result.iter().fold(0, |acc, r| {
+            let overlapping = range.get_overlapping_range(&Range::new(r.get_start(), r.get_end()));
+            acc + overlapping.get_end() - overlapping.get_start()
+        });
         let full_range_included: bool = sum == (range.get_end() - range.get_start());
         let properties: Vec<Property> = result
             .iter()
-            .map(|r| DecisionValue::from_abi(r.get_value()).unwrap())
-            .map(|d| {
+            .map(|r| {
+                let d = DecisionValue::from_abi(r.get_value()).map_err::<Error, _>(Into::into)?;
                 if let Witness::IncludedInIntervalTreeAtBlock(_, plasma_data_block) =
                     d.get_witness()
                 {
-                    plasma_data_block.get_property().clone()
+                    Ok(plasma_data_block.get_property().clone())
                 } else {
-                    panic!("invalid witness")
+                    Err(Error::from(ErrorKind::InvalidWitness))
                 }
             })
-            .collect();
-        QuantifierResult::new(
+            .collect::<Result<Vec<Property>, Error>>()?;
+        Ok(QuantifierResult::with_cursor(
             properties
                 .iter()
                 .map(|p| QuantifierResultItem::Property(p.clone()))
                 .collect(),
             full_range_included,
-        )
+            next_cursor,
+        ))
     }
 }
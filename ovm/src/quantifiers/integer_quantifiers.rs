@@ -1,9 +1,33 @@
-use crate::types::{Integer, IntegerRangeQuantifierInput, QuantifierResult, QuantifierResultItem};
+use crate::error::{Error, ErrorKind};
+use crate::types::{
+    Integer, IntegerRangeQuantifierInput, QuantifierCursor, QuantifierResult, QuantifierResultItem,
+};
+use std::cmp::{max, min};
 
-fn get_range(start: u64, end: u64) -> Vec<QuantifierResultItem> {
-    (start..end)
-        .map(|n| QuantifierResultItem::Integer(Integer::new(n)))
-        .collect()
+/// Returns at most `limit` integers of start to end, from the cursor
+fn get_range(
+    start: u64,
+    end: u64,
+    cursor: Option<&QuantifierCursor>,
+    limit: usize,
+) -> QuantifierResult {
+    let from = match cursor {
+        Some(QuantifierCursor::Integer(next)) => max(*next, start),
+        _ => start,
+    };
+    let to = min(end, from.saturating_add(limit as u64));
+    let next_cursor = if to < end {
+        Some(QuantifierCursor::Integer(to))
+    } else {
+        None
+    };
+    QuantifierResult::with_cursor(
+        (from..to)
+            .map(|n| QuantifierResultItem::Integer(Integer::new(n)))
+            .collect(),
+        true,
+        next_cursor,
+    )
 }
 
 /// IntegerRangeQuantifier quantify specific range
@@ -16,12 +40,20 @@ impl Default for IntegerRangeQuantifier {
 }
 
 impl IntegerRangeQuantifier {
-    pub fn get_all_quantified(range: IntegerRangeQuantifierInput) -> QuantifierResult {
-        // let integer_range_parameters = IntegerRangeParameters::from_abi(&parameters).unwrap();
+    pub fn get_all_quantified(
+        range: IntegerRangeQuantifierInput,
+    ) -> Result<QuantifierResult, Error> {
+        Self::get_quantified(range, None, usize::max_value())
+    }
+    pub fn get_quantified(
+        range: IntegerRangeQuantifierInput,
+        cursor: Option<&QuantifierCursor>,
+        limit: usize,
+    ) -> Result<QuantifierResult, Error> {
         if range.get_end() < range.get_start() {
-            panic!("invalid start and end");
+            return Err(Error::from(ErrorKind::InvalidQuantifierInput));
         }
-        QuantifierResult::new(get_range(range.get_start(), range.get_end()), true)
+        Ok(get_range(range.get_start(), range.get_end(), cursor, limit))
     }
 }
 
@@ -36,9 +68,46 @@ impl Default for NonnegativeIntegerLessThanQuantifier {
 
 impl NonnegativeIntegerLessThanQuantifier {
     pub fn get_all_quantified(upper_bound: Integer) -> QuantifierResult {
-        if upper_bound < Integer(0) {
-            panic!("upper_bound shouldn't negative value.");
+        Self::get_quantified(upper_bound, None, usize::max_value())
+    }
+    pub fn get_quantified(
+        upper_bound: Integer,
+        cursor: Option<&QuantifierCursor>,
+        limit: usize,
+    ) -> QuantifierResult {
+        get_range(0, upper_bound.0, cursor, limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::IntegerRangeQuantifier;
+    use crate::error::ErrorKind;
+    use crate::types::{IntegerRangeQuantifierInput, QuantifierCursor};
+
+    #[test]
+    fn test_get_quantified_pages() {
+        let range = IntegerRangeQuantifierInput::new(5, 12);
+        let first = IntegerRangeQuantifier::get_quantified(range, None, 4).unwrap();
+        assert_eq!(first.get_results().len(), 4);
+        assert_eq!(first.get_next_cursor(), &Some(QuantifierCursor::Integer(9)));
+        let second =
+            IntegerRangeQuantifier::get_quantified(range, first.get_next_cursor().as_ref(), 4)
+                .unwrap();
+        assert_eq!(second.get_results().len(), 3);
+        assert_eq!(second.get_next_cursor(), &None);
+    }
+
+    #[test]
+    fn test_get_quantified_invalid_range() {
+        let range = IntegerRangeQuantifierInput::new(12, 5);
+        match IntegerRangeQuantifier::get_all_quantified(range)
+            .unwrap_err()
+            .kind()
+        {
+            ErrorKind::InvalidQuantifierInput => (),
+            kind => panic!("unexpected error {}", kind),
         }
-        QuantifierResult::new(get_range(0, upper_bound.0), true)
     }
 }
@@ -0,0 +1,137 @@
+use crate::error::Error;
+use crate::property_executor::PropertyExecutor;
+use crate::types::{Quantifier, QuantifierCursor, QuantifierResultItem};
+use plasma_db::traits::kvs::KeyValueStore;
+use std::collections::VecDeque;
+
+/// The number of results which `QuantifiedItems` fetches at once
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Iterates quantified results lazily, fetching a page when the previous page is consumed.
+/// Results which aren't iterated are never fetched, so deciders can exit early.
+/// The iteration ends after an error of fetching a page.
+pub struct QuantifiedItems<'a, KVS: KeyValueStore> {
+    decider: &'a PropertyExecutor<KVS>,
+    quantifier: &'a Quantifier,
+    page_size: usize,
+    items: VecDeque<QuantifierResultItem>,
+    cursor: Option<QuantifierCursor>,
+    finished: bool,
+    all_results_quantified: bool,
+}
+
+impl<'a, KVS> QuantifiedItems<'a, KVS>
+where
+    KVS: KeyValueStore,
+{
+    pub fn new(
+        decider: &'a PropertyExecutor<KVS>,
+        quantifier: &'a Quantifier,
+        page_size: usize,
+    ) -> Self {
+        QuantifiedItems {
+            decider,
+            quantifier,
+            page_size,
+            items: VecDeque::new(),
+            cursor: None,
+            finished: false,
+            all_results_quantified: true,
+        }
+    }
+    /// Returns false if any fetched page may miss results, which is final after iteration
+    pub fn get_all_results_quantified(&self) -> bool {
+        self.all_results_quantified
+    }
+}
+
+impl<'a, KVS> Iterator for QuantifiedItems<'a, KVS>
+where
+    KVS: KeyValueStore,
+{
+    type Item = Result<QuantifierResultItem, Error>;
+    fn next(&mut self) -> Option<Result<QuantifierResultItem, Error>> {
+        while self.items.is_empty() && !self.finished {
+            let page = match self.decider.get_quantified(
+                self.quantifier,
+                self.cursor.as_ref(),
+                self.page_size,
+            ) {
+                Ok(page) => page,
+                Err(e) => {
+                    self.finished = true;
+                    self.all_results_quantified = false;
+                    return Some(Err(e));
+                }
+            };
+            self.all_results_quantified &= page.get_all_results_quantified();
+            self.cursor = page.get_next_cursor().clone();
+            self.finished = self.cursor.is_none();
+            self.items.extend(page.into_results());
+        }
+        self.items.pop_front().map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::QuantifiedItems;
+    use crate::db::Message;
+    use crate::property_executor::PropertyExecutor;
+    use crate::types::{Integer, IntegerRangeQuantifierInput, Quantifier, QuantifierResultItem};
+    use bytes::Bytes;
+    use ethereum_types::Address;
+    use ethsign::SecretKey;
+    use plasma_db::impls::kvs::CoreDbLevelDbImpl;
+
+    #[test]
+    fn test_iterate_pages() {
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let quantifier =
+            Quantifier::IntegerRangeQuantifier(IntegerRangeQuantifierInput::new(0, 250));
+        let mut items = QuantifiedItems::new(&decider, &quantifier, 100);
+        let mut count = 0;
+        for (i, item) in (&mut items).enumerate() {
+            if let Ok(QuantifierResultItem::Integer(n)) = item {
+                assert_eq!(n, Integer(i as u64));
+            }
+            count += 1;
+        }
+        assert_eq!(count, 250);
+        assert!(items.get_all_results_quantified());
+    }
+
+    #[test]
+    fn test_iterate_signed_messages() {
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let key = SecretKey::from_raw(&[1; 32]).unwrap();
+        let signer: Address = key.public().address().into();
+        for (i, signed) in [true, false, true, true].iter().enumerate() {
+            let mut message = Message::new(
+                Bytes::from(vec![i as u8]),
+                Address::zero(),
+                Address::zero(),
+                Integer(0),
+                Bytes::new(),
+            );
+            if *signed {
+                message.sign(&key);
+            }
+            assert!(decider.get_message_db().store_message(&message).is_ok());
+        }
+        let quantifier = Quantifier::SignedByQuantifier(signer);
+        let page = decider.get_quantified(&quantifier, None, 2).unwrap();
+        assert!(page.get_next_cursor().is_some());
+        assert_eq!(
+            QuantifiedItems::new(&decider, &quantifier, 2).count(),
+            decider
+                .get_all_quantified(&quantifier)
+                .unwrap()
+                .get_results()
+                .len()
+        );
+        assert_eq!(QuantifiedItems::new(&decider, &quantifier, 2).count(), 3);
+    }
+
+}
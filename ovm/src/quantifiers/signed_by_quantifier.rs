@@ -1,5 +1,6 @@
+use crate::error::Error;
 use crate::property_executor::PropertyExecutor;
-use crate::types::{QuantifierResult, QuantifierResultItem};
+use crate::types::{QuantifierCursor, QuantifierResult, QuantifierResultItem};
 use ethereum_types::Address;
 use plasma_db::traits::kvs::KeyValueStore;
 
@@ -15,19 +16,36 @@ impl SignedByQuantifier {
     pub fn get_all_quantified<KVS>(
         decider: &PropertyExecutor<KVS>,
         signed_by: Address,
-    ) -> QuantifierResult
+    ) -> Result<QuantifierResult, Error>
     where
         KVS: KeyValueStore,
     {
-        let messages = decider
+        Self::get_quantified(decider, signed_by, None, usize::max_value())
+    }
+    /// Reads at most `limit` messages from the cursor with the index by signers
+    pub fn get_quantified<KVS>(
+        decider: &PropertyExecutor<KVS>,
+        signed_by: Address,
+        cursor: Option<&QuantifierCursor>,
+        limit: usize,
+    ) -> Result<QuantifierResult, Error>
+    where
+        KVS: KeyValueStore,
+    {
+        let from = match cursor {
+            Some(QuantifierCursor::Key(key)) => Some(key),
+            _ => None,
+        };
+        let (messages, last_key) = decider
             .get_message_db()
-            .get_messages_signed_by(signed_by, None, None);
-        QuantifierResult::new(
+            .get_messages_signed_by_page(signed_by, from, limit)?;
+        Ok(QuantifierResult::with_cursor(
             messages
-                .iter()
-                .map(|m| QuantifierResultItem::Message(m.clone()))
+                .into_iter()
+                .map(QuantifierResultItem::Message)
                 .collect(),
             true,
-        )
+            last_key.map(QuantifierCursor::Key),
+        ))
     }
 }
@@ -1,3 +1,9 @@
+pub mod htlc;
 pub mod plasma;
+pub mod state_channel;
 
+pub use self::htlc::{create_hashlock_claim_property, create_timelock_refund_property};
 pub use self::plasma::create_plasma_property;
+pub use self::state_channel::{
+    create_state_channel_dispute_property, create_state_channel_exit_property,
+};
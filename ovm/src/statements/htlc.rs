@@ -0,0 +1,133 @@
+use crate::types::core::DeciderId;
+use crate::types::{
+    AndDeciderInput, BlockDeadlineInput, CustomDeciderInput, Integer, NotDeciderInput,
+    PreimageExistsInput, Property, Witness,
+};
+use bytes::Bytes;
+use ethereum_types::H256;
+use plasma_core::data_structure::abi::Encodable;
+
+/// The recipient of the conditional payment claims it with the preimage of `hash`
+/// before the chain reaches `deadline`. The preimage is read from `HashPreimageDb`.
+pub fn create_hashlock_claim_property(
+    hash: H256,
+    deadline: Integer,
+    deadline_decider_id: DeciderId,
+) -> Property {
+    Property::AndDecider(Box::new(AndDeciderInput::new(
+        create_preimage_exists_property(hash),
+        Witness::Bytes(Bytes::new()),
+        create_not_property(create_block_deadline_property(
+            deadline,
+            deadline_decider_id,
+        )),
+        Witness::Bytes(Bytes::new()),
+    )))
+}
+
+/// The sender of the conditional payment takes it back after the chain reaches `deadline`
+/// unless the preimage of `hash` is revealed. Whoever knows the preimage can contradict it.
+pub fn create_timelock_refund_property(
+    hash: H256,
+    deadline: Integer,
+    deadline_decider_id: DeciderId,
+) -> Property {
+    Property::AndDecider(Box::new(AndDeciderInput::new(
+        create_block_deadline_property(deadline, deadline_decider_id),
+        Witness::Bytes(Bytes::new()),
+        create_not_property(create_preimage_exists_property(hash)),
+        Witness::Bytes(Bytes::new()),
+    )))
+}
+
+/// The property of `BlockDeadlineDecider` registered as `deadline_decider_id`
+pub fn create_block_deadline_property(
+    deadline: Integer,
+    deadline_decider_id: DeciderId,
+) -> Property {
+    Property::CustomDecider(CustomDeciderInput::new(
+        deadline_decider_id,
+        Bytes::from(BlockDeadlineInput::new(deadline).to_abi()),
+    ))
+}
+
+pub fn create_preimage_exists_property(hash: H256) -> Property {
+    Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(hash)))
+}
+
+fn create_not_property(property: Property) -> Property {
+    Property::NotDecider(Box::new(NotDeciderInput::new(
+        property,
+        Witness::Bytes(Bytes::new()),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{
+        create_hashlock_claim_property, create_preimage_exists_property,
+        create_timelock_refund_property,
+    };
+    use crate::contradiction::find_contradiction;
+    use crate::db::{BlockRootDb, HashPreimageDb};
+    use crate::deciders::preimage_exists_decider::Verifier;
+    use crate::deciders::BlockDeadlineDecider;
+    use crate::property_executor::PropertyExecutor;
+    use crate::types::{Integer, Property, Witness};
+    use bytes::Bytes;
+    use ethereum_types::Address;
+    use plasma_db::impls::kvs::CoreDbLevelDbImpl;
+
+    #[test]
+    fn test_claim_and_refund() {
+        let deadline_decider_id = Address::from_low_u64_be(100);
+        let mut decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        assert!(decider
+            .register_decider::<BlockDeadlineDecider>(deadline_decider_id)
+            .is_ok());
+        let preimage = Bytes::from("secret");
+        let hash = Verifier::static_hash(&preimage);
+        let claim = create_hashlock_claim_property(hash, Integer(10), deadline_decider_id);
+        let refund = create_timelock_refund_property(hash, Integer(10), deadline_decider_id);
+        // the preimage isn't revealed yet
+        assert!(decider.decide(&claim, None).is_err());
+        assert!(HashPreimageDb::new(decider.get_db())
+            .store_witness(hash, &Witness::Bytes(preimage))
+            .is_ok());
+        let decide = |property: &Property| decider.decide(property, None).unwrap().get_outcome();
+        assert_eq!(decide(&claim), true);
+        assert_eq!(decide(&refund), false);
+        assert!(BlockRootDb::new(decider.get_db())
+            .store_root(Integer(10), &Bytes::from(&b"root"[..]))
+            .is_ok());
+        assert_eq!(decide(&claim), false);
+        // the revealed preimage contradicts the refund after the deadline
+        assert_eq!(decide(&refund), false);
+        let contradiction =
+            find_contradiction(&decider, &refund, &create_preimage_exists_property(hash))
+                .unwrap()
+                .unwrap();
+        assert!(contradiction.verify().is_ok());
+    }
+
+    #[test]
+    fn test_refund_without_preimage() {
+        let deadline_decider_id = Address::from_low_u64_be(100);
+        let mut decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        assert!(decider
+            .register_decider::<BlockDeadlineDecider>(deadline_decider_id)
+            .is_ok());
+        let hash = Verifier::static_hash(&Bytes::from("secret"));
+        let refund = create_timelock_refund_property(hash, Integer(10), deadline_decider_id);
+        let decided = decider.decide(&refund, None).unwrap();
+        assert_eq!(decided.get_outcome(), false);
+        assert!(BlockRootDb::new(decider.get_db())
+            .store_root(Integer(10), &Bytes::from(&b"root"[..]))
+            .is_ok());
+        // the preimage may exist somewhere, so the refund is settled by disputes
+        let result = decider.decide(&refund, None);
+        assert!(result.unwrap_err().is_undecided());
+    }
+
+}
@@ -0,0 +1,85 @@
+use crate::error::{Error, ErrorKind};
+use crate::types::{
+    AndDeciderInput, ChannelUpdateSignatureExistsDeciderInput, Integer, NotDeciderInput, Property,
+    Witness,
+};
+use bytes::Bytes;
+use ethereum_types::Address;
+
+/// The exit of the channel with the update at `nonce`, claimed by `exiter`.
+/// The counterparty has signed the update, and the exiter hasn't signed the next one.
+pub fn create_state_channel_exit_property(
+    channel_id: Bytes,
+    nonce: Integer,
+    exiter: Address,
+    counterparty: Address,
+) -> Result<Property, Error> {
+    let dispute = create_state_channel_dispute_property(channel_id.clone(), nonce, exiter)?;
+    Ok(Property::AndDecider(Box::new(AndDeciderInput::new(
+        Property::ChannelUpdateSignatureExistsDecider(
+            ChannelUpdateSignatureExistsDeciderInput::new(channel_id.clone(), nonce, counterparty),
+        ),
+        Witness::Bytes(Bytes::new()),
+        Property::NotDecider(Box::new(NotDeciderInput::new(
+            dispute,
+            Witness::Bytes(Bytes::new()),
+        ))),
+        Witness::Bytes(Bytes::new()),
+    ))))
+}
+
+/// The exiter has signed the update after `nonce`, which contradicts the exit.
+/// No update follows the largest nonce.
+pub fn create_state_channel_dispute_property(
+    channel_id: Bytes,
+    nonce: Integer,
+    exiter: Address,
+) -> Result<Property, Error> {
+    let next_nonce = nonce
+        .0
+        .checked_add(1)
+        .ok_or_else(|| Error::from(ErrorKind::InvalidChannelUpdate))?;
+    Ok(Property::ChannelUpdateSignatureExistsDecider(
+        ChannelUpdateSignatureExistsDeciderInput::new(channel_id, Integer(next_nonce), exiter),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{create_state_channel_dispute_property, create_state_channel_exit_property};
+    use crate::error::ErrorKind;
+    use crate::types::Integer;
+    use bytes::Bytes;
+    use ethereum_types::Address;
+
+    #[test]
+    fn test_create_properties_at_max_nonce() {
+        let channel_id = Bytes::from("channel");
+        let exiter = Address::from_low_u64_be(1);
+        let counterparty = Address::from_low_u64_be(2);
+        let nonce = Integer(u64::max_value());
+        match create_state_channel_dispute_property(channel_id.clone(), nonce, exiter)
+            .unwrap_err()
+            .kind()
+        {
+            ErrorKind::InvalidChannelUpdate => (),
+            kind => panic!("unexpected error {}", kind),
+        }
+        assert!(create_state_channel_exit_property(
+            channel_id.clone(),
+            nonce,
+            exiter,
+            counterparty
+        )
+        .is_err());
+        assert!(create_state_channel_exit_property(
+            channel_id,
+            Integer(u64::max_value() - 1),
+            exiter,
+            counterparty
+        )
+        .is_ok());
+    }
+
+}
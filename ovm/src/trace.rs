@@ -0,0 +1,381 @@
+use crate::dsl::{print_item, print_property, print_witness};
+use crate::error::Error;
+use crate::types::{Decision, Property, QuantifierResultItem, Variable, Witness};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread::{self, ThreadId};
+
+/// The outcome of a traced decision, with the error message if it isn't decided
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub enum TraceOutcome {
+    Decided(bool),
+    Undecided(String),
+    Failed(String),
+}
+
+impl TraceOutcome {
+    fn from_result(result: &Result<Decision, Error>) -> Self {
+        match result {
+            Ok(decision) => TraceOutcome::Decided(decision.get_outcome()),
+            Err(error) if error.is_undecided() => TraceOutcome::Undecided(error.to_string()),
+            Err(error) => TraceOutcome::Failed(error.to_string()),
+        }
+    }
+    fn get_name(&self) -> &str {
+        match self {
+            TraceOutcome::Decided(true) => "true",
+            TraceOutcome::Decided(false) => "false",
+            TraceOutcome::Undecided(_) => "undecided",
+            TraceOutcome::Failed(_) => "error",
+        }
+    }
+    fn get_reason(&self) -> Option<&str> {
+        match self {
+            TraceOutcome::Decided(_) => None,
+            TraceOutcome::Undecided(reason) | TraceOutcome::Failed(reason) => Some(reason),
+        }
+    }
+}
+
+impl fmt::Display for TraceOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.get_reason() {
+            Some(reason) => write!(f, "{} ({})", self.get_name(), reason),
+            None => write!(f, "{}", self.get_name()),
+        }
+    }
+}
+
+/// A node of the decision tree recorded by `PropertyExecutor::decide_traced`.
+/// Properties decided for quantified results have the variable and the result.
+/// With the `json` feature it's serialized with properties, witnesses and results in the DSL.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct DecisionTrace {
+    #[cfg_attr(feature = "json", serde(serialize_with = "serialize_property"))]
+    property: Property,
+    #[cfg_attr(feature = "json", serde(serialize_with = "serialize_witness"))]
+    witness: Option<Witness>,
+    #[cfg_attr(feature = "json", serde(serialize_with = "serialize_binding"))]
+    binding: Option<(Variable, QuantifierResultItem)>,
+    outcome: TraceOutcome,
+    children: Vec<DecisionTrace>,
+}
+
+impl DecisionTrace {
+    fn new(
+        property: &Property,
+        witness: &Option<Witness>,
+        binding: Option<(Variable, QuantifierResultItem)>,
+        outcome: TraceOutcome,
+    ) -> Self {
+        DecisionTrace {
+            property: property.clone(),
+            witness: witness.clone(),
+            binding,
+            outcome,
+            children: vec![],
+        }
+    }
+    pub fn get_property(&self) -> &Property {
+        &self.property
+    }
+    pub fn get_witness(&self) -> &Option<Witness> {
+        &self.witness
+    }
+    pub fn get_binding(&self) -> &Option<(Variable, QuantifierResultItem)> {
+        &self.binding
+    }
+    pub fn get_outcome(&self) -> &TraceOutcome {
+        &self.outcome
+    }
+    pub fn get_children(&self) -> &Vec<DecisionTrace> {
+        &self.children
+    }
+    /// Renders the tree as lines of `[variable = result] property with witness => outcome`
+    /// indented by the depth
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        self.write_text(&mut out, 0);
+        out
+    }
+    fn write_text(&self, out: &mut String, depth: usize) {
+        out.push_str(&"  ".repeat(depth));
+        if let Some((variable, item)) = &self.binding {
+            out.push_str(&format!(
+                "[{} = {}] ",
+                String::from_utf8_lossy(variable.get_name()),
+                print_item(item)
+            ));
+        }
+        out.push_str(&print_property(&self.property));
+        if let Some(witness) = &self.witness {
+            out.push_str(&format!(" with {}", print_witness(witness)));
+        }
+        out.push_str(&format!(" => {}\n", self.outcome));
+        for child in self.children.iter() {
+            child.write_text(out, depth + 1);
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+fn serialize_property<S: serde::Serializer>(
+    property: &Property,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&print_property(property))
+}
+
+#[cfg(feature = "json")]
+fn serialize_witness<S: serde::Serializer>(
+    witness: &Option<Witness>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match witness {
+        Some(witness) => serializer.serialize_some(&print_witness(witness)),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(feature = "json")]
+fn serialize_binding<S: serde::Serializer>(
+    binding: &Option<(Variable, QuantifierResultItem)>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeMap;
+    match binding {
+        Some((variable, item)) => {
+            let mut map = serializer.serialize_map(Some(2))?;
+            map.serialize_entry("variable", &String::from_utf8_lossy(variable.get_name()))?;
+            map.serialize_entry("item", &print_item(item))?;
+            map.end()
+        }
+        None => serializer.serialize_none(),
+    }
+}
+
+struct TraceState {
+    // decisions which are being decided, the innermost is the last
+    frames: Vec<DecisionTrace>,
+    root: Option<DecisionTrace>,
+    binding: Option<(Variable, QuantifierResultItem)>,
+}
+
+/// Records decisions of `PropertyExecutor` while tracing, otherwise does nothing.
+/// Each thread has its own stack of traces, so decisions on other threads aren't recorded,
+/// and `decide_traced` in a custom decider records its own tree.
+#[derive(Default)]
+pub(crate) struct Tracer {
+    // the number of traces being recorded, which skips the lock if it is zero
+    active: AtomicUsize,
+    states: Mutex<HashMap<ThreadId, Vec<TraceState>>>,
+}
+
+impl Tracer {
+    pub fn start(&self) {
+        let mut states = self.states.lock().unwrap_or_else(PoisonError::into_inner);
+        states
+            .entry(thread::current().id())
+            .or_insert_with(Vec::new)
+            .push(TraceState {
+                frames: vec![],
+                root: None,
+                binding: None,
+            });
+        self.active.fetch_add(1, Ordering::SeqCst);
+    }
+    /// Stops the innermost trace of the thread and returns the tree of its outermost decision
+    pub fn finish(&self) -> Option<DecisionTrace> {
+        let mut states = self.states.lock().unwrap_or_else(PoisonError::into_inner);
+        let id = thread::current().id();
+        let state = states.get_mut(&id).and_then(Vec::pop);
+        if states.get(&id).map_or(false, Vec::is_empty) {
+            states.remove(&id);
+        }
+        if state.is_some() {
+            self.active.fetch_sub(1, Ordering::SeqCst);
+        }
+        state.and_then(|state| state.root)
+    }
+    /// Labels the next decision with the quantified result bound to the variable
+    pub fn bind(&self, variable: &Variable, item: &QuantifierResultItem) {
+        self.with_state(|state| state.binding = Some((variable.clone(), item.clone())));
+    }
+    pub fn begin(&self, property: &Property, witness: &Option<Witness>) {
+        self.with_state(|state| {
+            let binding = state.binding.take();
+            state.frames.push(DecisionTrace::new(
+                property,
+                witness,
+                binding,
+                TraceOutcome::Undecided(String::new()),
+            ));
+        });
+    }
+    pub fn end(&self, result: &Result<Decision, Error>) {
+        self.with_state(|state| {
+            if let Some(mut trace) = state.frames.pop() {
+                trace.outcome = TraceOutcome::from_result(result);
+                match state.frames.last_mut() {
+                    Some(parent) => parent.children.push(trace),
+                    None => state.root = Some(trace),
+                }
+            }
+        });
+    }
+    /// Runs `f` with the innermost trace of the thread, if it is tracing
+    fn with_state<F: FnOnce(&mut TraceState)>(&self, f: F) {
+        if self.active.load(Ordering::SeqCst) == 0 {
+            return;
+        }
+        let mut states = self.states.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(state) = states
+            .get_mut(&thread::current().id())
+            .and_then(|states| states.last_mut())
+        {
+            f(state);
+        }
+    }
+}
+
+/// Returns the trace of the decision without its nested decisions
+pub(crate) fn trace_leaf(
+    property: &Property,
+    witness: &Option<Witness>,
+    result: &Result<Decision, Error>,
+) -> DecisionTrace {
+    DecisionTrace::new(property, witness, None, TraceOutcome::from_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::TraceOutcome;
+    use crate::dsl::parse_property;
+    use crate::error::Error;
+    use crate::property_executor::PropertyExecutor;
+    use crate::types::{CustomDeciderInput, Decider, Decision, Integer, Property, Witness};
+    use abi_derive::{AbiDecodable, AbiEncodable};
+    use bytes::Bytes;
+    use ethereum_types::Address;
+    use plasma_core::data_structure::abi::Encodable;
+    use plasma_db::impls::kvs::memory::CoreDbMemoryImpl;
+    use plasma_db::impls::kvs::CoreDbLevelDbImpl;
+    use plasma_db::traits::kvs::KeyValueStore;
+
+    const MESSAGE: &str = "Message(\"channel\", 0x0000000000000000000000000000000000000000, \
+                           0x0000000000000000000000000000000000000000, 3, [], 0x, 0x)";
+
+    #[test]
+    fn test_trace_counterexample() {
+        let property =
+            parse_property(&format!("ForAll n in 2..5: HasLowerNonce({}, n)", MESSAGE)).unwrap();
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let (result, trace) = decider.decide_traced(&property, None);
+        assert_eq!(result.unwrap().get_outcome(), false);
+        assert_eq!(trace.get_outcome(), &TraceOutcome::Decided(false));
+        assert_eq!(
+            trace.to_text(),
+            format!(
+                "ForAll n in 2..5: HasLowerNonce({m}, n) => false\n  \
+                 [n = 2] HasLowerNonce({m}, 2) => false\n",
+                m = MESSAGE
+            )
+        );
+    }
+
+    #[test]
+    fn test_trace_undecided() {
+        let property = parse_property(&format!(
+            "Or(IncludedInIntervalTreeAtBlock(1, 0..10), HasLowerNonce({}, 2))",
+            MESSAGE
+        ))
+        .unwrap();
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let (result, trace) = decider.decide_traced(&property, None);
+        assert!(result.is_err());
+        let children = trace.get_children();
+        assert_eq!(children.len(), 2);
+        assert_eq!(
+            children[0].get_outcome(),
+            &TraceOutcome::Undecided("Undecided".to_string())
+        );
+        assert_eq!(children[1].get_outcome(), &TraceOutcome::Decided(false));
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_trace_json() {
+        let property = parse_property(&format!(
+            "ForAll n in 2..5: Or(IncludedInIntervalTreeAtBlock(1, 0..10), HasLowerNonce({}, n))",
+            MESSAGE
+        ))
+        .unwrap();
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let (_, trace) = decider.decide_traced(&property, None);
+        let json = serde_json::to_string(&trace).unwrap();
+        assert!(json.starts_with(r#"{"property":"ForAll n in 2..5: Or("#));
+        assert!(json.contains(r#""binding":{"variable":"n","item":"2"}"#));
+        assert!(json.contains(r#""outcome":{"Undecided":"Undecided"}"#));
+    }
+
+    #[test]
+    fn test_executor_is_sync() {
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<PropertyExecutor<CoreDbMemoryImpl>>();
+    }
+
+    #[derive(Clone, Debug, AbiDecodable, AbiEncodable)]
+    struct NonceInput {
+        nonce: Integer,
+    }
+
+    /// Decides `HasLowerNonce` of the nonce with its own trace
+    struct TracingDecider;
+
+    impl Decider for TracingDecider {
+        type Input = NonceInput;
+        fn decide<T: KeyValueStore>(
+            decider: &PropertyExecutor<T>,
+            input: &NonceInput,
+            _witness: Option<Witness>,
+        ) -> Result<Decision, Error> {
+            let property = parse_property(&format!(
+                "Not(HasLowerNonce({}, {}))",
+                MESSAGE, input.nonce.0
+            ))?;
+            let (result, trace) = decider.decide_traced(&property, None);
+            assert_eq!(trace.get_children().len(), 1);
+            result
+        }
+        fn check_decision<T: KeyValueStore>(
+            decider: &PropertyExecutor<T>,
+            input: &NonceInput,
+        ) -> Result<Decision, Error> {
+            Self::decide(decider, input, None)
+        }
+    }
+
+    #[test]
+    fn test_trace_in_custom_decider() {
+        let mut decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let decider_id = Address::from_low_u64_be(203);
+        assert!(decider
+            .register_decider::<TracingDecider>(decider_id)
+            .is_ok());
+        let property = Property::CustomDecider(CustomDeciderInput::new(
+            decider_id,
+            Bytes::from(NonceInput { nonce: Integer(2) }.to_abi()),
+        ));
+        let (result, trace) = decider.decide_traced(&property, None);
+        assert_eq!(result.unwrap().get_outcome(), true);
+        // the nested trace is returned to the custom decider
+        assert_eq!(trace.get_outcome(), &TraceOutcome::Decided(true));
+        assert!(trace.get_children().is_empty());
+    }
+
+}
@@ -1,17 +1,22 @@
 pub mod core;
+pub mod decider_id;
 pub mod decision_value;
 pub mod inputs;
+pub mod schema;
+pub mod template;
 pub mod witness;
 
 pub use self::core::{
-    Decider, Decision, ImplicationProofElement, Integer, Property, PropertyFactory, Quantifier,
-    QuantifierResult, QuantifierResultItem, WitnessFactory,
+    Decider, Decision, ImplicationProofElement, Integer, Property, Quantifier, QuantifierCursor,
+    QuantifierResult, QuantifierResultItem,
 };
+pub use self::decider_id::{set_decider_ids, DeciderIdRegistry, DeciderKind};
 pub use self::decision_value::DecisionValue;
 pub use self::inputs::{
-    AndDeciderInput, BlockRangeQuantifierInput, ChannelUpdateSignatureExistsDeciderInput,
-    ForAllSuchThatInput, HasLowerNonceInput, IncludedInIntervalTreeAtBlockInput,
-    IntegerRangeQuantifierInput, NotDeciderInput, OrDeciderInput, PreimageExistsInput,
-    SignedByInput,
+    AndDeciderInput, BlockDeadlineInput, BlockRangeQuantifierInput,
+    ChannelUpdateSignatureExistsDeciderInput, CustomDeciderInput, ForAllSuchThatInput,
+    HasLowerNonceInput, IncludedInIntervalTreeAtBlockInput, IntegerRangeQuantifierInput,
+    NotDeciderInput, OrDeciderInput, PreimageExistsInput, SignedByInput, ThereExistsSuchThatInput,
 };
+pub use self::template::{Placeholder, Variable};
 pub use self::witness::{PlasmaDataBlock, Witness};
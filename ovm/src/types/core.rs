@@ -1,28 +1,27 @@
+use super::decider_id::{get_decider_id, get_decider_kind, DeciderKind};
 use super::inputs::{
     AndDeciderInput, BlockRangeQuantifierInput, ChannelUpdateSignatureExistsDeciderInput,
-    ForAllSuchThatInput, HasLowerNonceInput, IncludedInIntervalTreeAtBlockInput,
-    IntegerRangeQuantifierInput, NotDeciderInput, OrDeciderInput, PreimageExistsInput,
-    SignedByInput,
+    CustomDeciderInput, ForAllSuchThatInput, HasLowerNonceInput,
+    IncludedInIntervalTreeAtBlockInput, IntegerRangeQuantifierInput, NotDeciderInput,
+    OrDeciderInput, PreimageExistsInput, SignedByInput, ThereExistsSuchThatInput,
 };
 use super::witness::Witness;
 use crate::db::Message;
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 use crate::property_executor::PropertyExecutor;
+use abi_derive::{AbiDecodable, AbiEncodable, AbiSchema};
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 use ethabi::{ParamType, Token};
-use ethereum_types::Address;
-use plasma_core::data_structure::abi::{Decodable, Encodable};
-use plasma_core::data_structure::error::{
-    Error as PlasmaCoreError, ErrorKind as PlasmaCoreErrorKind,
-};
+use ethereum_types::{Address, U256};
+use plasma_core::data_structure::abi::{Decodable, Encodable, VariantTag};
 use plasma_db::traits::kvs::KeyValueStore;
-use std::sync::Arc;
 
 pub type DeciderId = Address;
 pub type QuantifierId = Address;
 pub trait Input {}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct Integer(pub u64);
 
 impl Integer {
@@ -46,24 +45,16 @@ impl From<Bytes> for Integer {
     }
 }
 
-lazy_static! {
-    static ref DECIDER_LIST: Vec<Address> = {
-        let mut list = vec![];
-        for _ in 0..10 {
-            list.push(Address::random())
-        }
-        list
-    };
-}
-
 /// The property which will be decided by Decider
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, AbiDecodable, AbiEncodable, AbiSchema)]
+#[abi(tag = "DeciderIdTag")]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum Property {
     // left, left_witness, right, right_witness
     AndDecider(Box<AndDeciderInput>),
     // property, witness
     NotDecider(Box<NotDeciderInput>),
-    // quantifier, quantifier_parameters, property_factory, witness_factory?
+    // quantifier, variable, property_template, witness_template?
     ForAllSuchThatDecider(Box<ForAllSuchThatInput>),
     // hash
     PreimageExistsDecider(Box<PreimageExistsInput>),
@@ -76,9 +67,15 @@ pub enum Property {
     // channelId, nonce, participant
     ChannelUpdateSignatureExistsDecider(ChannelUpdateSignatureExistsDeciderInput),
     IncludedInIntervalTreeAtBlockDecider(IncludedInIntervalTreeAtBlockInput),
+    // quantifier, variable, property_template, witness_template?
+    ThereExistsSuchThatDecider(Box<ThereExistsSuchThatInput>),
+    // decider_id, input of a decider registered to DeciderRegistry
+    #[abi(other)]
+    CustomDecider(CustomDeciderInput),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum Quantifier {
     // start to end
     IntegerRangeQuantifier(IntegerRangeQuantifierInput),
@@ -90,84 +87,71 @@ pub enum Quantifier {
     BlockRangeQuantifier(BlockRangeQuantifierInput),
 }
 
-impl Property {
-    pub fn get_decider_id(&self) -> DeciderId {
-        match self {
-            Property::AndDecider(_) => DECIDER_LIST[0],
-            Property::OrDecider(_) => DECIDER_LIST[1],
-            Property::ForAllSuchThatDecider(_) => DECIDER_LIST[2],
-            Property::NotDecider(_) => DECIDER_LIST[3],
-            Property::PreimageExistsDecider(_) => DECIDER_LIST[4],
-            Property::SignedByDecider(_) => DECIDER_LIST[5],
-            Property::HasLowerNonceDecider(_) => DECIDER_LIST[6],
-            Property::ChannelUpdateSignatureExistsDecider(_) => DECIDER_LIST[7],
-            Property::IncludedInIntervalTreeAtBlockDecider(_) => DECIDER_LIST[8],
-        }
+/// Tags Property variants by the address of their decider
+pub struct DeciderIdTag;
+
+impl VariantTag for DeciderIdTag {
+    fn get_param_type() -> ParamType {
+        ParamType::Address
     }
-    pub fn to_bytes(&self) -> Vec<u8> {
-        match self {
-            Property::AndDecider(input) => input.to_abi(),
-            Property::OrDecider(input) => input.to_abi(),
-            Property::ForAllSuchThatDecider(input) => input.to_abi(),
-            Property::NotDecider(input) => input.to_abi(),
-            Property::PreimageExistsDecider(input) => input.to_abi(),
-            Property::SignedByDecider(input) => input.to_abi(),
-            Property::HasLowerNonceDecider(input) => input.to_abi(),
-            Property::ChannelUpdateSignatureExistsDecider(input) => input.to_abi(),
-            Property::IncludedInIntervalTreeAtBlockDecider(input) => input.to_abi(),
-        }
+    fn to_token(index: u64) -> Token {
+        Token::Address(get_decider_id(DeciderKind::all()[index as usize]))
     }
-    fn from_bytes(decider_id: Address, data: &[u8]) -> Result<Self, PlasmaCoreError> {
-        if decider_id == DECIDER_LIST[0] {
-            AndDeciderInput::from_abi(data).map(|input| Property::AndDecider(Box::new(input)))
-        } else if decider_id == DECIDER_LIST[1] {
-            OrDeciderInput::from_abi(data).map(|input| Property::OrDecider(Box::new(input)))
-        } else if decider_id == DECIDER_LIST[2] {
-            ForAllSuchThatInput::from_abi(data)
-                .map(|input| Property::ForAllSuchThatDecider(Box::new(input)))
-        } else if decider_id == DECIDER_LIST[3] {
-            NotDeciderInput::from_abi(data).map(|input| Property::NotDecider(Box::new(input)))
-        } else if decider_id == DECIDER_LIST[4] {
-            PreimageExistsInput::from_abi(data)
-                .map(|input| Property::PreimageExistsDecider(Box::new(input)))
-        } else if decider_id == DECIDER_LIST[5] {
-            SignedByInput::from_abi(data).map(Property::SignedByDecider)
-        } else if decider_id == DECIDER_LIST[6] {
-            HasLowerNonceInput::from_abi(data).map(Property::HasLowerNonceDecider)
-        } else if decider_id == DECIDER_LIST[7] {
-            ChannelUpdateSignatureExistsDeciderInput::from_abi(data)
-                .map(Property::ChannelUpdateSignatureExistsDecider)
-        } else if decider_id == DECIDER_LIST[8] {
-            IncludedInIntervalTreeAtBlockInput::from_abi(data)
-                .map(Property::IncludedInIntervalTreeAtBlockDecider)
-        } else {
-            panic!("unknown decider")
-        }
+    fn from_token(token: Token) -> Option<u64> {
+        token
+            .to_address()
+            .and_then(get_decider_kind)
+            .map(|kind| kind.get_index() as u64)
     }
 }
 
-impl Encodable for Property {
-    fn to_tuple(&self) -> Vec<Token> {
-        vec![
-            Token::Address(self.get_decider_id()),
-            Token::Bytes(self.to_bytes()),
-        ]
+impl Property {
+    /// Returns the kind of built-in decider, `None` for custom deciders
+    pub fn get_kind(&self) -> Option<DeciderKind> {
+        Some(match self {
+            Property::AndDecider(_) => DeciderKind::And,
+            Property::NotDecider(_) => DeciderKind::Not,
+            Property::ForAllSuchThatDecider(_) => DeciderKind::ForAllSuchThat,
+            Property::PreimageExistsDecider(_) => DeciderKind::PreimageExists,
+            Property::SignedByDecider(_) => DeciderKind::SignedBy,
+            Property::OrDecider(_) => DeciderKind::Or,
+            Property::HasLowerNonceDecider(_) => DeciderKind::HasLowerNonce,
+            Property::ChannelUpdateSignatureExistsDecider(_) => {
+                DeciderKind::ChannelUpdateSignatureExists
+            }
+            Property::IncludedInIntervalTreeAtBlockDecider(_) => {
+                DeciderKind::IncludedInIntervalTreeAtBlock
+            }
+            Property::ThereExistsSuchThatDecider(_) => DeciderKind::ThereExistsSuchThat,
+            Property::CustomDecider(_) => return None,
+        })
     }
-}
-
-impl Decodable for Property {
-    type Ok = Property;
-    fn from_tuple(tuple: &[Token]) -> Result<Self, PlasmaCoreError> {
-        let decider_id = tuple[0].clone().to_address();
-        let input_data = tuple[1].clone().to_bytes();
-        if let (Some(decider_id), Some(input_data)) = (decider_id, input_data) {
-            Ok(Property::from_bytes(decider_id, &input_data).unwrap())
-        } else {
-            Err(PlasmaCoreError::from(PlasmaCoreErrorKind::AbiDecode))
+    /// Returns false if the property contains `NotDecider` or a quantifier,
+    /// whose true decisions can turn false when more local data is stored.
+    /// `is_custom_cacheable` tells whether the decider of a custom property opts in.
+    pub fn is_cacheable(&self, is_custom_cacheable: &dyn Fn(DeciderId) -> bool) -> bool {
+        match self {
+            Property::AndDecider(input) => {
+                input.get_left().is_cacheable(is_custom_cacheable)
+                    && input.get_right().is_cacheable(is_custom_cacheable)
+            }
+            Property::OrDecider(input) => {
+                input.get_left().is_cacheable(is_custom_cacheable)
+                    && input.get_right().is_cacheable(is_custom_cacheable)
+            }
+            Property::NotDecider(_)
+            | Property::ForAllSuchThatDecider(_)
+            | Property::ThereExistsSuchThatDecider(_) => false,
+            Property::CustomDecider(input) => is_custom_cacheable(input.get_decider_id()),
+            _ => true,
         }
     }
-    fn get_param_types() -> Vec<ParamType> {
-        vec![ParamType::Address, ParamType::Bytes]
+    /// Returns the address of decider configured by `set_decider_ids`
+    pub fn get_decider_id(&self) -> DeciderId {
+        match self {
+            Property::CustomDecider(input) => input.get_decider_id(),
+            _ => get_decider_id(self.get_kind().unwrap()),
+        }
     }
 }
 
@@ -186,54 +170,6 @@ impl Quantifier {
             Quantifier::BlockRangeQuantifier(_) => 3,
         }
     }
-    pub fn to_bytes(&self) -> Vec<u8> {
-        match self {
-            Quantifier::IntegerRangeQuantifier(input) => input.to_abi(),
-            Quantifier::NonnegativeIntegerLessThanQuantifier(input) => Bytes::from(*input).to_vec(),
-            Quantifier::SignedByQuantifier(input) => input.as_bytes().to_vec(),
-            Quantifier::BlockRangeQuantifier(input) => input.to_abi(),
-        }
-    }
-    fn from_bytes(id: u64, data: &[u8]) -> Result<Self, PlasmaCoreError> {
-        if id == 0 {
-            IntegerRangeQuantifierInput::from_abi(data).map(Quantifier::IntegerRangeQuantifier)
-        } else if id == 1 {
-            Ok(Quantifier::NonnegativeIntegerLessThanQuantifier(
-                Integer::from(Bytes::from(data)),
-            ))
-        } else if id == 2 {
-            Ok(Quantifier::SignedByQuantifier(Address::from_slice(data)))
-        } else if id == 3 {
-            BlockRangeQuantifierInput::from_abi(data).map(Quantifier::BlockRangeQuantifier)
-        } else {
-            panic!("unknown decider")
-        }
-    }
-}
-
-impl Encodable for Quantifier {
-    fn to_tuple(&self) -> Vec<Token> {
-        vec![
-            Token::Uint(self.get_id().into()),
-            Token::Bytes(self.to_bytes()),
-        ]
-    }
-}
-
-impl Decodable for Quantifier {
-    type Ok = Quantifier;
-    fn from_tuple(tuple: &[Token]) -> Result<Self, PlasmaCoreError> {
-        let id = tuple[0].clone().to_uint();
-        let input_data = tuple[1].clone().to_bytes();
-        if let (Some(id), Some(input_data)) = (id, input_data) {
-            Ok(Quantifier::from_bytes(id.as_u64(), &input_data).unwrap())
-        } else {
-            Err(PlasmaCoreError::from(PlasmaCoreErrorKind::AbiDecode))
-        }
-    }
-    fn get_param_types() -> Vec<ethabi::ParamType> {
-        vec![ethabi::ParamType::Uint(256), ethabi::ParamType::Bytes]
-    }
 }
 
 /// Implication proof element has the property which is decided by Decider
@@ -250,6 +186,12 @@ impl ImplicationProofElement {
             implication_witness,
         }
     }
+    pub fn get_implication(&self) -> &Property {
+        &self.implication
+    }
+    pub fn get_implication_witness(&self) -> &Option<Witness> {
+        &self.implication_witness
+    }
 }
 
 impl From<ImplicationProofElement> for Token {
@@ -284,46 +226,22 @@ impl Decision {
     pub fn get_implication_proof(&self) -> &Vec<ImplicationProofElement> {
         &self.implication_proof
     }
-}
-
-#[derive(Clone)]
-pub struct PropertyFactory(Arc<dyn Fn(QuantifierResultItem) -> Property>);
-
-impl PropertyFactory {
-    pub fn new(handler: Box<dyn Fn(QuantifierResultItem) -> Property>) -> Self {
-        PropertyFactory(Arc::new(handler))
-    }
-    pub fn call(&self, item: QuantifierResultItem) -> Property {
-        self.0(item)
-    }
-}
-
-impl std::fmt::Debug for PropertyFactory {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "PropertyFactory")
-    }
-}
-
-#[derive(Clone)]
-pub struct WitnessFactory(Arc<dyn Fn(QuantifierResultItem) -> Witness>);
-
-impl WitnessFactory {
-    pub fn new(handler: Box<dyn Fn(QuantifierResultItem) -> Witness>) -> Self {
-        WitnessFactory(Arc::new(handler))
-    }
-    pub fn call(&self, item: QuantifierResultItem) -> Witness {
-        self.0(item)
-    }
-}
-
-impl std::fmt::Debug for WitnessFactory {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "WitnessFactory")
+    /// Returns the implication proof of the decided property,
+    /// which is the property itself if its decider returns an empty proof.
+    pub fn get_implication_proof_of(&self, property: &Property) -> Vec<ImplicationProofElement> {
+        if self.implication_proof.is_empty() {
+            vec![ImplicationProofElement::new(property.clone(), None)]
+        } else {
+            self.implication_proof.clone()
+        }
     }
 }
 
 pub trait Decider {
     type Input;
+    /// Whether true decisions are cached when the decider is registered for custom properties.
+    /// Deciders opt in only if their true decisions never turn false.
+    const IS_CACHEABLE: bool = false;
     fn decide<T: KeyValueStore>(
         decider: &PropertyExecutor<T>,
         input: &Self::Input,
@@ -343,33 +261,110 @@ pub enum QuantifierResultItem {
     Property(Property),
 }
 
+impl QuantifierResultItem {
+    /// Decodes the result of `quantifier` which is encoded as a witness
+    pub fn decode(quantifier: &Quantifier, data: &[u8]) -> Result<Self, Error> {
+        let decode = |param_type: ParamType| -> Result<Token, Error> {
+            let mut tokens = ethabi::decode(&[param_type], data).map_err::<Error, _>(Into::into)?;
+            Ok(tokens.remove(0))
+        };
+        let invalid = || Error::from(ErrorKind::AbiDecode);
+        match quantifier {
+            Quantifier::IntegerRangeQuantifier(_)
+            | Quantifier::NonnegativeIntegerLessThanQuantifier(_) => {
+                match decode(ParamType::Uint(256))? {
+                    Token::Uint(n) if n <= U256::from(u64::max_value()) => {
+                        Ok(QuantifierResultItem::Integer(Integer(n.low_u64())))
+                    }
+                    _ => Err(invalid()),
+                }
+            }
+            Quantifier::SignedByQuantifier(_) => {
+                match decode(ParamType::Tuple(Message::get_param_types()))? {
+                    Token::Tuple(tuple) => Ok(QuantifierResultItem::Message(
+                        Message::from_tuple(&tuple).map_err::<Error, _>(Into::into)?,
+                    )),
+                    _ => Err(invalid()),
+                }
+            }
+            Quantifier::BlockRangeQuantifier(_) => {
+                match decode(ParamType::Tuple(Property::get_param_types()))? {
+                    Token::Tuple(tuple) => Ok(QuantifierResultItem::Property(
+                        Property::from_tuple(&tuple).map_err::<Error, _>(Into::into)?,
+                    )),
+                    _ => Err(invalid()),
+                }
+            }
+        }
+    }
+}
+
+/// Results are encoded as witnesses of `ThereExistsSuchThatDecider`
+impl From<QuantifierResultItem> for Token {
+    fn from(item: QuantifierResultItem) -> Token {
+        match item {
+            QuantifierResultItem::Integer(integer) => Token::Uint(integer.0.into()),
+            QuantifierResultItem::Bytes(bytes) => Token::Bytes(bytes.to_vec()),
+            QuantifierResultItem::Message(message) => Token::Tuple(message.to_tuple()),
+            QuantifierResultItem::Property(property) => property.into(),
+        }
+    }
+}
+
+/// Position in the domain of a quantifier, the next page starts from it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QuantifierCursor {
+    // the next integer or coin position
+    Integer(u64),
+    // the last key which is read from a database
+    Key(Bytes),
+}
+
+/// A page of quantified results. `next_cursor` is `None` for the last page.
 pub struct QuantifierResult {
     results: Vec<QuantifierResultItem>,
     all_results_quantified: bool,
+    next_cursor: Option<QuantifierCursor>,
 }
 
 impl QuantifierResult {
     pub fn new(results: Vec<QuantifierResultItem>, all_results_quantified: bool) -> Self {
+        Self::with_cursor(results, all_results_quantified, None)
+    }
+    pub fn with_cursor(
+        results: Vec<QuantifierResultItem>,
+        all_results_quantified: bool,
+        next_cursor: Option<QuantifierCursor>,
+    ) -> Self {
         QuantifierResult {
             results,
             all_results_quantified,
+            next_cursor,
         }
     }
     pub fn get_results(&self) -> &Vec<QuantifierResultItem> {
         &self.results
     }
+    /// Returns false if results in the span of this page may be missing locally
     pub fn get_all_results_quantified(&self) -> bool {
         self.all_results_quantified
     }
+    pub fn get_next_cursor(&self) -> &Option<QuantifierCursor> {
+        &self.next_cursor
+    }
+    pub fn into_results(self) -> Vec<QuantifierResultItem> {
+        self.results
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use super::Property;
-    use crate::types::PreimageExistsInput;
-    use ethereum_types::H256;
+    use super::{Integer, Property, Quantifier};
+    use crate::types::{DeciderKind, IncludedInIntervalTreeAtBlockInput, PreimageExistsInput};
+    use ethereum_types::{Address, H256};
     use plasma_core::data_structure::abi::{Decodable, Encodable};
+    use plasma_core::data_structure::Range;
 
     #[test]
     fn test_encode_and_decode_property() {
@@ -384,4 +379,97 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encode_and_decode_quantifier() {
+        let quantifiers = vec![
+            Quantifier::IntegerRangeQuantifier(Range::new(0, 10)),
+            Quantifier::NonnegativeIntegerLessThanQuantifier(Integer(10)),
+            Quantifier::SignedByQuantifier(Address::random()),
+            Quantifier::BlockRangeQuantifier(IncludedInIntervalTreeAtBlockInput::new(
+                Integer(1),
+                Range::new(0, 10),
+            )),
+        ];
+        for quantifier in quantifiers {
+            let decoded = Quantifier::from_abi(&quantifier.to_abi()).unwrap();
+            assert_eq!(decoded.get_id(), quantifier.get_id());
+            assert_eq!(decoded.to_abi(), quantifier.to_abi());
+        }
+    }
+
+    #[test]
+    fn test_quantifier_golden_vector() {
+        let quantifier = Quantifier::NonnegativeIntegerLessThanQuantifier(Integer(10));
+        let expected = concat!(
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "000000000000000000000000000000000000000000000000000000000000000a",
+        );
+        assert_eq!(hex::encode(quantifier.to_abi()), expected);
+    }
+
+    #[test]
+    fn test_decider_id_is_stable() {
+        let property =
+            Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(H256::zero())));
+        let encoded = property.to_abi();
+        assert_eq!(
+            &encoded[12..32],
+            DeciderKind::PreimageExists.get_default_id().as_bytes()
+        );
+        assert_eq!(
+            property.get_decider_id(),
+            DeciderKind::PreimageExists.get_default_id()
+        );
+    }
+
+    #[test]
+    fn test_decode_unknown_decider() {
+        // unknown IDs are decoded as custom deciders, which are dispatched by DeciderRegistry
+        let decider_id = Address::random();
+        let encoded = ethabi::encode(&[
+            ethabi::Token::Address(decider_id),
+            ethabi::Token::Bytes(vec![1, 2, 3]),
+        ]);
+        match Property::from_abi(&encoded).unwrap() {
+            Property::CustomDecider(input) => {
+                assert_eq!(input.get_decider_id(), decider_id);
+                assert_eq!(&input.get_input()[..], &[1, 2, 3]);
+            }
+            _ => panic!("unknown decider must be decoded as CustomDecider"),
+        }
+    }
+
+    #[test]
+    fn test_decode_malformed_quantifier() {
+        let encoded = ethabi::encode(&[
+            ethabi::Token::Uint(2.into()),
+            ethabi::Token::Bytes(vec![0; 3]),
+        ]);
+        assert!(Quantifier::from_abi(&encoded).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_property_json() {
+        use crate::types::{AndDeciderInput, SignedByInput, Witness};
+        use bytes::Bytes;
+
+        let property = Property::AndDecider(Box::new(AndDeciderInput::new(
+            Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(H256::zero()))),
+            Witness::Bytes(Bytes::from(&b"preimage"[..])),
+            Property::SignedByDecider(SignedByInput::new(
+                Bytes::from(&b"message"[..]),
+                Address::zero(),
+            )),
+            Witness::Bytes(Bytes::from(&b"signature"[..])),
+        )));
+        let json = serde_json::to_string(&property).unwrap();
+        assert!(json.starts_with(r#"{"AndDecider":{"left":{"PreimageExistsDecider":"#));
+        assert!(json.contains(r#""leftWitness":{"Bytes":"0x707265696d616765"}"#));
+        let decoded: Property = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.to_abi(), property.to_abi());
+    }
+
 }
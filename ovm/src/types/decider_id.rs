@@ -0,0 +1,237 @@
+use super::core::DeciderId;
+use crate::error::{Error, ErrorKind};
+use plasma_core::data_structure::abi::keccak256;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+
+/// Built-in deciders, in the declaration order of `Property` variants
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DeciderKind {
+    And,
+    Not,
+    ForAllSuchThat,
+    PreimageExists,
+    SignedBy,
+    Or,
+    HasLowerNonce,
+    ChannelUpdateSignatureExists,
+    IncludedInIntervalTreeAtBlock,
+    ThereExistsSuchThat,
+}
+
+const DECIDER_KINDS: [DeciderKind; 10] = [
+    DeciderKind::And,
+    DeciderKind::Not,
+    DeciderKind::ForAllSuchThat,
+    DeciderKind::PreimageExists,
+    DeciderKind::SignedBy,
+    DeciderKind::Or,
+    DeciderKind::HasLowerNonce,
+    DeciderKind::ChannelUpdateSignatureExists,
+    DeciderKind::IncludedInIntervalTreeAtBlock,
+    DeciderKind::ThereExistsSuchThat,
+];
+
+impl DeciderKind {
+    pub fn all() -> &'static [DeciderKind] {
+        &DECIDER_KINDS
+    }
+    pub fn get_index(self) -> usize {
+        self as usize
+    }
+    pub fn get_name(self) -> &'static str {
+        match self {
+            DeciderKind::And => "AndDecider",
+            DeciderKind::Not => "NotDecider",
+            DeciderKind::ForAllSuchThat => "ForAllSuchThatDecider",
+            DeciderKind::PreimageExists => "PreimageExistsDecider",
+            DeciderKind::SignedBy => "SignedByDecider",
+            DeciderKind::Or => "OrDecider",
+            DeciderKind::HasLowerNonce => "HasLowerNonceDecider",
+            DeciderKind::ChannelUpdateSignatureExists => "ChannelUpdateSignatureExistsDecider",
+            DeciderKind::IncludedInIntervalTreeAtBlock => "IncludedInIntervalTreeAtBlockDecider",
+            DeciderKind::ThereExistsSuchThat => "ThereExistsSuchThatDecider",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .cloned()
+            .find(|kind| kind.get_name() == name)
+    }
+    /// Returns the last 20 bytes of keccak256 of the name, which every process agrees on.
+    pub fn get_default_id(self) -> DeciderId {
+        DeciderId::from_slice(&keccak256(self.get_name().as_bytes()).as_bytes()[12..])
+    }
+}
+
+/// Maps decider kinds to IDs, which are the addresses of deployed decider contracts.
+/// Kinds which aren't configured use their default IDs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeciderIdRegistry {
+    ids: HashMap<DeciderKind, DeciderId>,
+}
+
+impl Default for DeciderIdRegistry {
+    fn default() -> Self {
+        DeciderIdRegistry {
+            ids: DeciderKind::all()
+                .iter()
+                .map(|kind| (*kind, kind.get_default_id()))
+                .collect(),
+        }
+    }
+}
+
+impl DeciderIdRegistry {
+    /// Sets the ID of `kind`, fails if another kind already uses the ID
+    pub fn set_id(&mut self, kind: DeciderKind, id: DeciderId) -> Result<(), Error> {
+        if self.get_kind(id).filter(|k| *k != kind).is_some() {
+            return Err(Error::from(ErrorKind::InvalidDeciderConfig));
+        }
+        self.ids.insert(kind, id);
+        Ok(())
+    }
+    pub fn get_id(&self, kind: DeciderKind) -> DeciderId {
+        self.ids[&kind]
+    }
+    pub fn get_kind(&self, id: DeciderId) -> Option<DeciderKind> {
+        self.ids
+            .iter()
+            .find(|(_, v)| **v == id)
+            .map(|(kind, _)| *kind)
+    }
+    /// Parses lines like `AndDecider = 0x...`, `#` starts a comment.
+    /// IDs must be unique after all lines are applied, so lines can swap IDs.
+    pub fn parse(config: &str) -> Result<Self, Error> {
+        let mut registry = Self::default();
+        for line in config.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut parts = line.splitn(2, '=').map(str::trim);
+            let kind = parts.next().and_then(DeciderKind::from_name);
+            let id = parts.next().and_then(parse_address);
+            if let (Some(kind), Some(id)) = (kind, id) {
+                registry.ids.insert(kind, id);
+            } else {
+                return Err(Error::from(ErrorKind::InvalidDeciderConfig));
+            }
+        }
+        registry.check_unique()?;
+        Ok(registry)
+    }
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+    fn check_unique(&self) -> Result<(), Error> {
+        let ids: HashSet<&DeciderId> = self.ids.values().collect();
+        if ids.len() == self.ids.len() {
+            Ok(())
+        } else {
+            Err(Error::from(ErrorKind::InvalidDeciderConfig))
+        }
+    }
+}
+
+fn parse_address(s: &str) -> Option<DeciderId> {
+    hex::decode(s.trim_start_matches("0x"))
+        .ok()
+        .filter(|bytes| bytes.len() == 20)
+        .map(|bytes| DeciderId::from_slice(&bytes))
+}
+
+lazy_static! {
+    static ref DECIDER_IDS: RwLock<DeciderIdRegistry> = RwLock::new(DeciderIdRegistry::default());
+}
+
+/// Replaces decider IDs which every `Property` of this process is encoded with
+pub fn set_decider_ids(registry: DeciderIdRegistry) {
+    *DECIDER_IDS.write().unwrap() = registry;
+}
+
+pub fn get_decider_id(kind: DeciderKind) -> DeciderId {
+    DECIDER_IDS.read().unwrap().get_id(kind)
+}
+
+pub fn get_decider_kind(id: DeciderId) -> Option<DeciderKind> {
+    DECIDER_IDS.read().unwrap().get_kind(id)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{DeciderIdRegistry, DeciderKind};
+    use ethereum_types::Address;
+
+    #[test]
+    fn test_default_id() {
+        assert_eq!(
+            hex::encode(DeciderKind::And.get_default_id().as_bytes()),
+            "110f5d49909af8f54ab67e9d8fb80470e3b5142a"
+        );
+        let registry = DeciderIdRegistry::default();
+        for kind in DeciderKind::all() {
+            assert_eq!(registry.get_kind(registry.get_id(*kind)), Some(*kind));
+        }
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let registry = DeciderIdRegistry::parse(
+            "# deployed deciders\n\
+             AndDecider = 0x0000000000000000000000000000000000000001\n\
+             \n\
+             OrDecider = 0000000000000000000000000000000000000002 # without prefix\n",
+        )
+        .unwrap();
+        assert_eq!(
+            registry.get_id(DeciderKind::And),
+            Address::from_low_u64_be(1)
+        );
+        assert_eq!(
+            registry.get_id(DeciderKind::Or),
+            Address::from_low_u64_be(2)
+        );
+        assert_eq!(
+            registry.get_id(DeciderKind::Not),
+            DeciderKind::Not.get_default_id()
+        );
+        assert_eq!(
+            registry.get_kind(Address::from_low_u64_be(1)),
+            Some(DeciderKind::And)
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_config() {
+        assert!(DeciderIdRegistry::parse("UnknownDecider = 0x00").is_err());
+        assert!(DeciderIdRegistry::parse("AndDecider = 0x1234").is_err());
+        assert!(DeciderIdRegistry::parse("AndDecider").is_err());
+        let duplicated = "AndDecider = 0x0000000000000000000000000000000000000001\n\
+                          OrDecider = 0x0000000000000000000000000000000000000001";
+        assert!(DeciderIdRegistry::parse(duplicated).is_err());
+        let default_and = hex::encode(DeciderKind::And.get_default_id().as_bytes());
+        let taken = format!("OrDecider = 0x{}", default_and);
+        assert!(DeciderIdRegistry::parse(&taken).is_err());
+    }
+
+    #[test]
+    fn test_parse_swapped_ids() {
+        let and_id = DeciderKind::And.get_default_id();
+        let not_id = DeciderKind::Not.get_default_id();
+        let config = format!(
+            "AndDecider = 0x{}\nNotDecider = 0x{}",
+            hex::encode(not_id.as_bytes()),
+            hex::encode(and_id.as_bytes())
+        );
+        let registry = DeciderIdRegistry::parse(&config).unwrap();
+        assert_eq!(registry.get_id(DeciderKind::And), not_id);
+        assert_eq!(registry.get_id(DeciderKind::Not), and_id);
+        assert_eq!(registry.get_kind(and_id), Some(DeciderKind::Not));
+    }
+
+}
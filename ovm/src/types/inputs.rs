@@ -1,14 +1,15 @@
-use super::core::{Integer, Property, PropertyFactory, Quantifier, WitnessFactory};
+use super::core::{Integer, Property, Quantifier};
+use super::template::Variable;
 use super::witness::Witness;
 use crate::db::Message;
-use abi_derive::{AbiDecodable, AbiEncodable};
+use abi_derive::{AbiDecodable, AbiEncodable, AbiSchema};
 use bytes::Bytes;
-use ethabi::{ParamType, Token};
 use ethereum_types::{Address, H256};
-use plasma_core::data_structure::abi::{Decodable, Encodable};
 use plasma_core::data_structure::Range;
 
-#[derive(Clone, Debug, AbiDecodable, AbiEncodable)]
+#[derive(Clone, Debug, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 pub struct AndDeciderInput {
     left: Property,
     left_witness: Witness,
@@ -44,7 +45,9 @@ impl AndDeciderInput {
     }
 }
 
-#[derive(Clone, Debug, AbiDecodable, AbiEncodable)]
+#[derive(Clone, Debug, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 pub struct OrDeciderInput {
     left: Property,
     left_witness: Witness,
@@ -80,7 +83,9 @@ impl OrDeciderInput {
     }
 }
 
-#[derive(Clone, Debug, AbiDecodable, AbiEncodable)]
+#[derive(Clone, Debug, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 pub struct NotDeciderInput {
     property: Property,
     witness: Witness,
@@ -98,41 +103,89 @@ impl NotDeciderInput {
     }
 }
 
-#[allow(unused_attributes)]
-#[derive(Clone, Debug, AbiDecodable, AbiEncodable)]
+/// Decides `property_template` for each result of `quantifier`,
+/// whose placeholders of `variable` are replaced with the result.
+#[derive(Clone, Debug, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 pub struct ForAllSuchThatInput {
     quantifier: Quantifier,
-    // PropertyFactory and WitnessFactory isn't serializable. Clients don't send these to smart contract directly
-    #[ignore]
-    property_factory: Option<PropertyFactory>,
-    #[ignore]
-    witness_factory: Option<WitnessFactory>,
+    variable: Variable,
+    property_template: Property,
+    witness_template: Option<Witness>,
 }
 
 impl ForAllSuchThatInput {
     pub fn new(
         quantifier: Quantifier,
-        property_factory: Option<PropertyFactory>,
-        witness_factory: Option<WitnessFactory>,
+        variable: Variable,
+        property_template: Property,
+        witness_template: Option<Witness>,
     ) -> Self {
         ForAllSuchThatInput {
             quantifier,
-            property_factory,
-            witness_factory,
+            variable,
+            property_template,
+            witness_template,
         }
     }
     pub fn get_quantifier(&self) -> &Quantifier {
         &self.quantifier
     }
-    pub fn get_property_factory(&self) -> &Option<PropertyFactory> {
-        &self.property_factory
+    pub fn get_variable(&self) -> &Variable {
+        &self.variable
     }
-    pub fn get_witness_factory(&self) -> &Option<WitnessFactory> {
-        &self.witness_factory
+    pub fn get_property_template(&self) -> &Property {
+        &self.property_template
+    }
+    pub fn get_witness_template(&self) -> &Option<Witness> {
+        &self.witness_template
+    }
+}
+
+/// Decides whether `property_template` is true for any result of `quantifier`,
+/// whose placeholders of `variable` are replaced with the result.
+#[derive(Clone, Debug, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
+pub struct ThereExistsSuchThatInput {
+    quantifier: Quantifier,
+    variable: Variable,
+    property_template: Property,
+    witness_template: Option<Witness>,
+}
+
+impl ThereExistsSuchThatInput {
+    pub fn new(
+        quantifier: Quantifier,
+        variable: Variable,
+        property_template: Property,
+        witness_template: Option<Witness>,
+    ) -> Self {
+        ThereExistsSuchThatInput {
+            quantifier,
+            variable,
+            property_template,
+            witness_template,
+        }
+    }
+    pub fn get_quantifier(&self) -> &Quantifier {
+        &self.quantifier
+    }
+    pub fn get_variable(&self) -> &Variable {
+        &self.variable
+    }
+    pub fn get_property_template(&self) -> &Property {
+        &self.property_template
+    }
+    pub fn get_witness_template(&self) -> &Option<Witness> {
+        &self.witness_template
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, AbiDecodable, AbiEncodable)]
+#[derive(Clone, Debug, PartialEq, Eq, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 pub struct PreimageExistsInput {
     hash: H256,
 }
@@ -146,8 +199,14 @@ impl PreimageExistsInput {
     }
 }
 
-#[derive(Clone, Debug, AbiDecodable, AbiEncodable)]
+#[derive(Clone, Debug, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 pub struct SignedByInput {
+    #[cfg_attr(
+        feature = "json",
+        serde(with = "plasma_core::data_structure::serde_hex")
+    )]
     message: Bytes,
     public_key: Address,
 }
@@ -165,12 +224,11 @@ impl SignedByInput {
     pub fn get_public_key(&self) -> Address {
         self.public_key
     }
-    pub fn hash(&self) -> &Bytes {
-        &self.message
-    }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, AbiDecodable, AbiEncodable)]
+#[derive(Clone, Debug, PartialEq, Eq, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 pub struct IncludedInIntervalTreeAtBlockInput {
     block_number: Integer,
     coin_range: Range,
@@ -191,7 +249,26 @@ impl IncludedInIntervalTreeAtBlockInput {
     }
 }
 
-#[derive(Clone, Debug, AbiDecodable, AbiEncodable)]
+/// Input of `BlockDeadlineDecider`, which is registered as a custom decider
+#[derive(Clone, Debug, PartialEq, Eq, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
+pub struct BlockDeadlineInput {
+    deadline: Integer,
+}
+
+impl BlockDeadlineInput {
+    pub fn new(deadline: Integer) -> Self {
+        Self { deadline }
+    }
+    pub fn get_deadline(&self) -> Integer {
+        self.deadline
+    }
+}
+
+#[derive(Clone, Debug, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 pub struct HasLowerNonceInput {
     message: Message,
     nonce: Integer,
@@ -209,8 +286,14 @@ impl HasLowerNonceInput {
     }
 }
 
-#[derive(Clone, Debug, AbiDecodable, AbiEncodable)]
+#[derive(Clone, Debug, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 pub struct ChannelUpdateSignatureExistsDeciderInput {
+    #[cfg_attr(
+        feature = "json",
+        serde(with = "plasma_core::data_structure::serde_hex")
+    )]
     pub channel_id: Bytes,
     pub nonce: Integer,
     pub particilant: Address,
@@ -226,6 +309,32 @@ impl ChannelUpdateSignatureExistsDeciderInput {
     }
 }
 
+/// Input of a decider registered to `DeciderRegistry`.
+/// It is encoded as `(decider_id, input)`, which is the same layout as built-in properties.
+#[derive(Clone, Debug, PartialEq, Eq, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
+pub struct CustomDeciderInput {
+    decider_id: Address,
+    #[cfg_attr(
+        feature = "json",
+        serde(with = "plasma_core::data_structure::serde_hex")
+    )]
+    input: Bytes,
+}
+
+impl CustomDeciderInput {
+    pub fn new(decider_id: Address, input: Bytes) -> Self {
+        CustomDeciderInput { decider_id, input }
+    }
+    pub fn get_decider_id(&self) -> Address {
+        self.decider_id
+    }
+    pub fn get_input(&self) -> &Bytes {
+        &self.input
+    }
+}
+
 pub type IntegerRangeQuantifierInput = Range;
 pub type BlockRangeQuantifierInput = IncludedInIntervalTreeAtBlockInput;
 
@@ -234,12 +343,47 @@ mod tests {
 
     use super::ChannelUpdateSignatureExistsDeciderInput;
     use super::IncludedInIntervalTreeAtBlockInput;
+    use super::SignedByInput;
     use crate::types::Integer;
+    use abi_derive::{AbiDecodable, AbiEncodable, AbiSchema};
     use bytes::Bytes;
-    use ethereum_types::Address;
-    use plasma_core::data_structure::abi::{Decodable, Encodable};
+    use ethabi::{ParamType, Token};
+    use ethereum_types::{Address, H256};
+    use plasma_core::data_structure::abi::{Decodable, Encodable, Hashable};
+    use plasma_core::data_structure::schema::{AbiSchema as _, SchemaField, SchemaType};
     use plasma_core::data_structure::Range;
 
+    #[derive(Clone, Debug, PartialEq, AbiDecodable, AbiEncodable)]
+    enum SampleEnum {
+        Empty,
+        Pair(Integer, Bytes),
+        Named { range: Range, flag: bool },
+    }
+
+    #[derive(Clone, Debug, PartialEq, AbiDecodable, AbiEncodable)]
+    struct SampleStruct {
+        signers: Vec<Address>,
+        nonce: Option<Integer>,
+        numbers: [u64; 2],
+        hash: H256,
+        raw: Vec<u8>,
+        items: Vec<SampleEnum>,
+        boxed: Box<Range>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, AbiDecodable, AbiEncodable, AbiSchema)]
+    struct AttributedStruct {
+        /// Documented fields are encoded as well
+        #[abi(type = "uint64")]
+        nonce: u64,
+        #[abi(type = "bytes32", rename = "root")]
+        hash: H256,
+        #[abi(skip)]
+        cache: Option<Bytes>,
+        #[abi(skip, default = "Integer(7)")]
+        version: Integer,
+    }
+
     #[test]
     fn test_channel_update_signature_exists_decider_input() {
         let input = ChannelUpdateSignatureExistsDeciderInput::new(
@@ -252,6 +396,16 @@ mod tests {
         assert_eq!(decoded.channel_id, input.channel_id);
     }
 
+    #[test]
+    fn test_signed_by_input_hash() {
+        let message = Bytes::from(&b"message"[..]);
+        let input = SignedByInput::new(message.clone(), Address::random());
+        let same = SignedByInput::new(message.clone(), input.get_public_key());
+        let other_signer = SignedByInput::new(message, Address::random());
+        assert_eq!(input.hash(), same.hash());
+        assert_ne!(input.hash(), other_signer.hash());
+    }
+
     #[test]
     fn test_included_in_interval_tree_at_block_input() {
         let input = IncludedInIntervalTreeAtBlockInput::new(Integer(10), Range::new(500, 700));
@@ -263,4 +417,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_derive_collections_and_enums() {
+        let sample = SampleStruct {
+            signers: vec![Address::random(), Address::random()],
+            nonce: Some(Integer(3)),
+            numbers: [1, 2],
+            hash: H256::zero(),
+            raw: b"raw".to_vec(),
+            items: vec![
+                SampleEnum::Empty,
+                SampleEnum::Pair(Integer(1), Bytes::from(&b"pair"[..])),
+                SampleEnum::Named {
+                    range: Range::new(0, 10),
+                    flag: true,
+                },
+            ],
+            boxed: Box::new(Range::new(5, 15)),
+        };
+        let decoded = SampleStruct::from_abi(&sample.to_abi()).unwrap();
+        assert_eq!(decoded, sample);
+        let without_nonce = SampleStruct {
+            nonce: None,
+            ..sample
+        };
+        let decoded = SampleStruct::from_abi(&without_nonce.to_abi()).unwrap();
+        assert_eq!(decoded, without_nonce);
+    }
+
+    #[test]
+    fn test_derive_rejects_unknown_variant_and_long_option() {
+        let encoded = ethabi::encode(&[Token::Uint(3.into()), Token::Bytes(vec![])]);
+        assert!(SampleEnum::from_abi(&encoded).is_err());
+        let mut tuple = SampleStruct {
+            signers: vec![],
+            nonce: None,
+            numbers: [0, 0],
+            hash: H256::zero(),
+            raw: vec![],
+            items: vec![],
+            boxed: Box::new(Range::new(0, 0)),
+        }
+        .to_tuple();
+        tuple[1] = Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]);
+        assert!(SampleStruct::from_tuple(&tuple).is_err());
+    }
+
+    #[test]
+    fn test_derive_field_attributes() {
+        let sample = AttributedStruct {
+            nonce: 10,
+            hash: H256::zero(),
+            cache: Some(Bytes::from(&b"cache"[..])),
+            version: Integer(1),
+        };
+        assert_eq!(
+            AttributedStruct::get_param_types(),
+            vec![ParamType::Uint(64), ParamType::FixedBytes(32)]
+        );
+        let decoded = AttributedStruct::from_abi(&sample.to_abi()).unwrap();
+        assert_eq!(decoded.nonce, 10);
+        assert_eq!(decoded.hash, H256::zero());
+        assert_eq!(decoded.cache, None);
+        assert_eq!(decoded.version, Integer(7));
+        assert_eq!(
+            AttributedStruct::get_schema().fields,
+            vec![
+                SchemaField::new("nonce", SchemaType::Elementary(ParamType::Uint(64))),
+                SchemaField::new("root", SchemaType::Elementary(ParamType::FixedBytes(32))),
+            ]
+        );
+    }
+
 }
@@ -0,0 +1,108 @@
+use super::{
+    AndDeciderInput, ChannelUpdateSignatureExistsDeciderInput, DecisionValue, ForAllSuchThatInput,
+    HasLowerNonceInput, IncludedInIntervalTreeAtBlockInput, NotDeciderInput, OrDeciderInput,
+    PlasmaDataBlock, PreimageExistsInput, Property, Quantifier, SignedByInput,
+    ThereExistsSuchThatInput, Witness,
+};
+use crate::db::Message;
+use plasma_core::data_structure::schema::{AbiSchema, Schema};
+use plasma_core::data_structure::{Range, StateObject, StateUpdate, Transaction};
+
+/// Returns schemas of all types which contracts decode.
+/// `examples/abi_schema.rs` prints them as Solidity structs or ABI JSON.
+pub fn get_schemas() -> Vec<Schema> {
+    vec![
+        Range::get_schema(),
+        StateObject::get_schema(),
+        StateUpdate::get_schema(),
+        Transaction::get_schema(),
+        Property::get_schema(),
+        Quantifier::get_schema(),
+        Witness::get_schema(),
+        PlasmaDataBlock::get_schema(),
+        DecisionValue::get_schema(),
+        AndDeciderInput::get_schema(),
+        OrDeciderInput::get_schema(),
+        NotDeciderInput::get_schema(),
+        ForAllSuchThatInput::get_schema(),
+        ThereExistsSuchThatInput::get_schema(),
+        PreimageExistsInput::get_schema(),
+        SignedByInput::get_schema(),
+        IncludedInIntervalTreeAtBlockInput::get_schema(),
+        HasLowerNonceInput::get_schema(),
+        ChannelUpdateSignatureExistsDeciderInput::get_schema(),
+        Message::get_schema(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::get_schemas;
+    use crate::db::Message;
+    use crate::types::{
+        AndDeciderInput, ChannelUpdateSignatureExistsDeciderInput, DecisionValue,
+        ForAllSuchThatInput, HasLowerNonceInput, IncludedInIntervalTreeAtBlockInput,
+        NotDeciderInput, OrDeciderInput, PlasmaDataBlock, PreimageExistsInput, Property,
+        Quantifier, SignedByInput, ThereExistsSuchThatInput, Witness,
+    };
+    use plasma_core::data_structure::abi::Decodable;
+    use plasma_core::data_structure::schema::{to_solidity, AbiSchema};
+
+    fn assert_schema<T: AbiSchema + Decodable>() {
+        assert_eq!(T::get_schema().get_param_types(), T::get_param_types());
+    }
+
+    #[test]
+    fn test_schemas_match_param_types() {
+        assert_schema::<Property>();
+        assert_schema::<Quantifier>();
+        assert_schema::<Witness>();
+        assert_schema::<PlasmaDataBlock>();
+        assert_schema::<DecisionValue>();
+        assert_schema::<AndDeciderInput>();
+        assert_schema::<OrDeciderInput>();
+        assert_schema::<NotDeciderInput>();
+        assert_schema::<ForAllSuchThatInput>();
+        assert_schema::<ThereExistsSuchThatInput>();
+        assert_schema::<PreimageExistsInput>();
+        assert_schema::<SignedByInput>();
+        assert_schema::<IncludedInIntervalTreeAtBlockInput>();
+        assert_schema::<HasLowerNonceInput>();
+        assert_schema::<ChannelUpdateSignatureExistsDeciderInput>();
+        assert_schema::<Message>();
+    }
+
+    #[test]
+    fn test_and_decider_input_solidity() {
+        assert_eq!(
+            to_solidity(&[AndDeciderInput::get_schema()]),
+            concat!(
+                "struct Property {\n",
+                "    address tag;\n",
+                "    bytes data;\n",
+                "}\n",
+                "\n",
+                "struct Witness {\n",
+                "    uint256 tag;\n",
+                "    bytes data;\n",
+                "}\n",
+                "\n",
+                "struct AndDeciderInput {\n",
+                "    Property left;\n",
+                "    Witness leftWitness;\n",
+                "    Property right;\n",
+                "    Witness rightWitness;\n",
+                "}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_struct_names_are_unique() {
+        let solidity = to_solidity(&get_schemas());
+        assert_eq!(solidity.matches("struct Range {").count(), 1);
+        assert_eq!(solidity.matches("struct Message {").count(), 1);
+    }
+
+}
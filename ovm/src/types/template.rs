@@ -0,0 +1,386 @@
+use super::core::{Integer, Property, Quantifier, QuantifierResultItem};
+use super::inputs::{
+    AndDeciderInput, CustomDeciderInput, ForAllSuchThatInput, NotDeciderInput, OrDeciderInput,
+    ThereExistsSuchThatInput,
+};
+use super::witness::{PlasmaDataBlock, Witness};
+use crate::db::Message;
+use crate::error::{Error, ErrorKind};
+use abi_derive::{AbiDecodable, AbiEncodable, AbiSchema};
+use bytes::Bytes;
+use ethabi::Token;
+use ethereum_types::{Address, H256};
+use plasma_core::data_structure::abi::{keccak256, Decodable, Encodable};
+
+/// A variable bound by `ForAllSuchThatDecider` to each quantified result.
+/// Templates refer to the variable by placeholders, which are replaced with the result.
+#[derive(Clone, Debug, PartialEq, Eq, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Variable {
+    #[cfg_attr(
+        feature = "json",
+        serde(with = "plasma_core::data_structure::serde_hex")
+    )]
+    name: Bytes,
+}
+
+/// Values which can be a placeholder of a variable in templates
+pub trait Placeholder {
+    fn placeholder(variable: &Variable) -> Self;
+}
+
+impl Placeholder for Integer {
+    fn placeholder(variable: &Variable) -> Self {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(&variable.get_placeholder_hash().as_bytes()[24..]);
+        Integer(u64::from_be_bytes(buf))
+    }
+}
+
+impl Placeholder for Bytes {
+    fn placeholder(variable: &Variable) -> Self {
+        Bytes::from(variable.get_placeholder_hash().as_bytes())
+    }
+}
+
+impl Placeholder for Property {
+    fn placeholder(variable: &Variable) -> Self {
+        Property::CustomDecider(CustomDeciderInput::new(
+            Address::from_slice(&variable.get_placeholder_hash().as_bytes()[12..]),
+            Bytes::new(),
+        ))
+    }
+}
+
+impl Variable {
+    pub fn new(name: &str) -> Self {
+        Variable {
+            name: Bytes::from(name),
+        }
+    }
+    pub fn get_name(&self) -> &Bytes {
+        &self.name
+    }
+    fn get_placeholder_hash(&self) -> H256 {
+        keccak256(&[&b"__VARIABLE__"[..], &self.name[..]].concat())
+    }
+    /// Returns the placeholder of this variable, e.g. `variable.placeholder::<Integer>()`
+    pub fn placeholder<T: Placeholder>(&self) -> T {
+        T::placeholder(self)
+    }
+    /// Replaces placeholders in the property template with the quantified result.
+    /// Nested `ForAllSuchThatDecider` which binds the same variable shadows it.
+    pub fn bind_property(
+        &self,
+        template: &Property,
+        item: &QuantifierResultItem,
+    ) -> Result<Property, Error> {
+        Binding::new(self, item).bind_property(template)
+    }
+    pub fn bind_witness(
+        &self,
+        template: &Witness,
+        item: &QuantifierResultItem,
+    ) -> Result<Witness, Error> {
+        Binding::new(self, item).bind_witness(template)
+    }
+}
+
+/// Placeholder and value as ABI tokens of the type of the quantified result
+struct Binding<'a> {
+    variable: &'a Variable,
+    item: &'a QuantifierResultItem,
+    placeholder: Token,
+    value: Token,
+}
+
+impl<'a> Binding<'a> {
+    fn new(variable: &'a Variable, item: &'a QuantifierResultItem) -> Self {
+        let placeholder = match item {
+            QuantifierResultItem::Integer(_) => {
+                Token::Uint(variable.placeholder::<Integer>().0.into())
+            }
+            QuantifierResultItem::Bytes(_) => {
+                Token::Bytes(variable.placeholder::<Bytes>().to_vec())
+            }
+            QuantifierResultItem::Message(_) => {
+                Token::Tuple(variable.placeholder::<Message>().to_tuple())
+            }
+            QuantifierResultItem::Property(_) => {
+                Token::Tuple(variable.placeholder::<Property>().to_tuple())
+            }
+        };
+        let value = Token::from(item.clone());
+        Binding {
+            variable,
+            item,
+            placeholder,
+            value,
+        }
+    }
+
+    fn replace(&self, token: Token) -> Token {
+        if token == self.placeholder {
+            return self.value.clone();
+        }
+        match token {
+            Token::Tuple(tokens) => Token::Tuple(self.replace_all(tokens)),
+            Token::Array(tokens) => Token::Array(self.replace_all(tokens)),
+            Token::FixedArray(tokens) => Token::FixedArray(self.replace_all(tokens)),
+            _ => token,
+        }
+    }
+
+    fn replace_all(&self, tokens: Vec<Token>) -> Vec<Token> {
+        tokens.into_iter().map(|t| self.replace(t)).collect()
+    }
+
+    /// Binds values which don't contain enums, so placeholders appear in their tuple
+    fn bind_tokens<T>(&self, value: &T) -> Result<T, Error>
+    where
+        T: Encodable + Decodable<Ok = T>,
+    {
+        T::from_tuple(&self.replace_all(value.to_tuple())).map_err(Into::into)
+    }
+
+    fn bind_integer(&self, integer: Integer) -> Result<Integer, Error> {
+        match self.replace(Token::Uint(integer.0.into())) {
+            Token::Uint(n) if n.bits() <= 64 => Ok(Integer(n.low_u64())),
+            _ => Err(Error::from(ErrorKind::AbiDecode)),
+        }
+    }
+
+    fn bind_property(&self, template: &Property) -> Result<Property, Error> {
+        if let QuantifierResultItem::Property(property) = self.item {
+            if Token::Tuple(template.to_tuple()) == self.placeholder {
+                return Ok(property.clone());
+            }
+        }
+        Ok(match template {
+            Property::AndDecider(input) => Property::AndDecider(Box::new(AndDeciderInput::new(
+                self.bind_property(input.get_left())?,
+                self.bind_witness(input.get_left_witness())?,
+                self.bind_property(input.get_right())?,
+                self.bind_witness(input.get_right_witness())?,
+            ))),
+            Property::OrDecider(input) => Property::OrDecider(Box::new(OrDeciderInput::new(
+                self.bind_property(input.get_left())?,
+                self.bind_witness(input.get_left_witness())?,
+                self.bind_property(input.get_right())?,
+                self.bind_witness(input.get_right_witness())?,
+            ))),
+            Property::NotDecider(input) => Property::NotDecider(Box::new(NotDeciderInput::new(
+                self.bind_property(input.get_property())?,
+                self.bind_witness(input.get_witness())?,
+            ))),
+            Property::ForAllSuchThatDecider(input) => {
+                let (quantifier, property_template, witness_template) = self.bind_quantified(
+                    input.get_quantifier(),
+                    input.get_variable(),
+                    input.get_property_template(),
+                    input.get_witness_template(),
+                )?;
+                Property::ForAllSuchThatDecider(Box::new(ForAllSuchThatInput::new(
+                    quantifier,
+                    input.get_variable().clone(),
+                    property_template,
+                    witness_template,
+                )))
+            }
+            Property::ThereExistsSuchThatDecider(input) => {
+                let (quantifier, property_template, witness_template) = self.bind_quantified(
+                    input.get_quantifier(),
+                    input.get_variable(),
+                    input.get_property_template(),
+                    input.get_witness_template(),
+                )?;
+                Property::ThereExistsSuchThatDecider(Box::new(ThereExistsSuchThatInput::new(
+                    quantifier,
+                    input.get_variable().clone(),
+                    property_template,
+                    witness_template,
+                )))
+            }
+            Property::PreimageExistsDecider(input) => {
+                Property::PreimageExistsDecider(Box::new(self.bind_tokens(&**input)?))
+            }
+            Property::SignedByDecider(input) => Property::SignedByDecider(self.bind_tokens(input)?),
+            Property::HasLowerNonceDecider(input) => {
+                Property::HasLowerNonceDecider(self.bind_tokens(input)?)
+            }
+            Property::ChannelUpdateSignatureExistsDecider(input) => {
+                Property::ChannelUpdateSignatureExistsDecider(self.bind_tokens(input)?)
+            }
+            Property::IncludedInIntervalTreeAtBlockDecider(input) => {
+                Property::IncludedInIntervalTreeAtBlockDecider(self.bind_tokens(input)?)
+            }
+            Property::CustomDecider(input) => Property::CustomDecider(self.bind_tokens(input)?),
+        })
+    }
+
+    /// Binds a nested quantified property, whose variable shadows the same variable
+    fn bind_quantified(
+        &self,
+        quantifier: &Quantifier,
+        variable: &Variable,
+        property_template: &Property,
+        witness_template: &Option<Witness>,
+    ) -> Result<(Quantifier, Property, Option<Witness>), Error> {
+        let quantifier = self.bind_quantifier(quantifier)?;
+        if variable == self.variable {
+            return Ok((
+                quantifier,
+                property_template.clone(),
+                witness_template.clone(),
+            ));
+        }
+        Ok((
+            quantifier,
+            self.bind_property(property_template)?,
+            witness_template
+                .as_ref()
+                .map(|witness| self.bind_witness(witness))
+                .transpose()?,
+        ))
+    }
+
+    fn bind_quantifier(&self, quantifier: &Quantifier) -> Result<Quantifier, Error> {
+        Ok(match quantifier {
+            Quantifier::IntegerRangeQuantifier(range) => {
+                Quantifier::IntegerRangeQuantifier(self.bind_tokens(range)?)
+            }
+            Quantifier::NonnegativeIntegerLessThanQuantifier(upper_bound) => {
+                Quantifier::NonnegativeIntegerLessThanQuantifier(self.bind_integer(*upper_bound)?)
+            }
+            Quantifier::SignedByQuantifier(signer) => Quantifier::SignedByQuantifier(*signer),
+            Quantifier::BlockRangeQuantifier(input) => {
+                Quantifier::BlockRangeQuantifier(self.bind_tokens(input)?)
+            }
+        })
+    }
+
+    fn bind_witness(&self, template: &Witness) -> Result<Witness, Error> {
+        Ok(match template {
+            Witness::Bytes(bytes) => match self.replace(Token::Bytes(bytes.to_vec())) {
+                Token::Bytes(bytes) => Witness::Bytes(Bytes::from(bytes)),
+                _ => return Err(Error::from(ErrorKind::AbiDecode)),
+            },
+            Witness::IncludedInIntervalTreeAtBlock(inclusion_proof, data_block) => {
+                Witness::IncludedInIntervalTreeAtBlock(
+                    inclusion_proof.clone(),
+                    PlasmaDataBlock::new(
+                        self.bind_integer(data_block.get_leaf_index())?,
+                        self.bind_tokens(&data_block.get_updated_range())?,
+                        self.bind_property(data_block.get_property())?,
+                    ),
+                )
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::Variable;
+    use crate::statements::create_plasma_property;
+    use crate::types::{
+        ForAllSuchThatInput, Integer, NotDeciderInput, PlasmaDataBlock, PreimageExistsInput,
+        Property, Quantifier, QuantifierResultItem, Witness,
+    };
+    use bytes::Bytes;
+    use ethereum_types::H256;
+    use plasma_core::data_structure::abi::{Decodable, Encodable};
+    use plasma_core::data_structure::Range;
+
+    #[test]
+    fn test_bind_nested_property() {
+        let variable = Variable::new("property");
+        let template = Property::NotDecider(Box::new(NotDeciderInput::new(
+            variable.placeholder(),
+            Witness::Bytes(Bytes::new()),
+        )));
+        let property =
+            Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(H256::zero())));
+        let bound = variable
+            .bind_property(&template, &QuantifierResultItem::Property(property.clone()))
+            .unwrap();
+        if let Property::NotDecider(input) = bound {
+            assert_eq!(input.get_property().to_abi(), property.to_abi());
+        } else {
+            panic!("unexpected property");
+        }
+    }
+
+    #[test]
+    fn test_bind_witness() {
+        let variable = Variable::new("block_number");
+        let template = Witness::IncludedInIntervalTreeAtBlock(
+            variable.placeholder(),
+            PlasmaDataBlock::new(
+                variable.placeholder(),
+                Range::new(0, 100),
+                Property::PreimageExistsDecider(Box::new(PreimageExistsInput::new(H256::zero()))),
+            ),
+        );
+        let bound = variable
+            .bind_witness(&template, &QuantifierResultItem::Integer(Integer(3)))
+            .unwrap();
+        if let Witness::IncludedInIntervalTreeAtBlock(proof, data_block) = bound {
+            // bytes aren't bound to integers
+            assert_eq!(proof, variable.placeholder::<Bytes>());
+            assert_eq!(data_block.get_leaf_index(), Integer(3));
+        } else {
+            panic!("unexpected witness");
+        }
+    }
+
+    #[test]
+    fn test_nested_variable_shadows() {
+        let variable = Variable::new("n");
+        let inner = Property::ForAllSuchThatDecider(Box::new(ForAllSuchThatInput::new(
+            Quantifier::NonnegativeIntegerLessThanQuantifier(variable.placeholder()),
+            variable.clone(),
+            Property::NotDecider(Box::new(NotDeciderInput::new(
+                variable.placeholder(),
+                Witness::Bytes(Bytes::new()),
+            ))),
+            None,
+        )));
+        let bound = variable
+            .bind_property(&inner, &QuantifierResultItem::Integer(Integer(5)))
+            .unwrap();
+        if let Property::ForAllSuchThatDecider(input) = bound {
+            if let Quantifier::NonnegativeIntegerLessThanQuantifier(upper_bound) =
+                input.get_quantifier()
+            {
+                assert_eq!(*upper_bound, Integer(5));
+            } else {
+                panic!("unexpected quantifier");
+            }
+            if let Property::ForAllSuchThatDecider(inner) = inner {
+                assert_eq!(
+                    input.get_property_template().to_abi(),
+                    inner.get_property_template().to_abi()
+                );
+            }
+        } else {
+            panic!("unexpected property");
+        }
+    }
+
+    #[test]
+    fn test_encode_and_decode_for_all_such_that() {
+        let property = create_plasma_property(Integer(10), Range::new(0, 100));
+        let encoded = property.to_abi();
+        let decoded = Property::from_abi(&encoded).unwrap();
+        assert_eq!(decoded.to_abi(), encoded);
+        if let Property::ForAllSuchThatDecider(input) = decoded {
+            assert_eq!(input.get_variable(), &Variable::new("block_number"));
+            assert!(input.get_witness_template().is_none());
+        } else {
+            panic!("unexpected property");
+        }
+    }
+
+}
@@ -1,20 +1,29 @@
-use super::core::Property;
+use super::core::{Integer, Property};
+use abi_derive::{AbiDecodable, AbiEncodable, AbiSchema};
 use bytes::Bytes;
-use ethabi::{ParamType, Token};
-use ethereum_types::U256;
-use plasma_core::data_structure::abi::{Decodable, Encodable};
-use plasma_core::data_structure::error::{
-    Error as PlasmaCoreError, ErrorKind as PlasmaCoreErrorKind,
-};
 use plasma_core::data_structure::Range;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "camelCase"))]
 pub struct PlasmaDataBlock {
+    leaf_index: Integer,
     updated_range: Range,
     property: Property,
 }
 
 impl PlasmaDataBlock {
+    pub fn new(leaf_index: Integer, updated_range: Range, property: Property) -> Self {
+        PlasmaDataBlock {
+            leaf_index,
+            updated_range,
+            property,
+        }
+    }
+    /// Returns the index of the leaf in the merkle interval tree of the block
+    pub fn get_leaf_index(&self) -> Integer {
+        self.leaf_index
+    }
     pub fn get_updated_range(&self) -> Range {
         self.updated_range
     }
@@ -23,134 +32,54 @@ impl PlasmaDataBlock {
     }
 }
 
-impl Encodable for PlasmaDataBlock {
-    fn to_abi(&self) -> Vec<u8> {
-        ethabi::encode(&self.to_tuple())
-    }
-    fn to_tuple(&self) -> Vec<Token> {
-        vec![
-            Token::Tuple(self.updated_range.to_tuple()),
-            Token::Tuple(self.property.to_tuple()),
-        ]
-    }
-}
-
-impl Decodable for PlasmaDataBlock {
-    type Ok = PlasmaDataBlock;
-    fn from_tuple(tuple: &[Token]) -> Result<Self, PlasmaCoreError> {
-        let updated_range = tuple[0].clone().to_tuple();
-        let property = tuple[1].clone().to_bytes();
-        if let (Some(updated_range), Some(property)) = (updated_range, property) {
-            Ok(PlasmaDataBlock {
-                updated_range: Range::from_tuple(&updated_range).unwrap(),
-                property: Property::from_abi(&property).unwrap(),
-            })
-        } else {
-            Err(PlasmaCoreError::from(PlasmaCoreErrorKind::AbiDecode))
-        }
-    }
-    fn from_abi(data: &[u8]) -> Result<Self, PlasmaCoreError> {
-        let decoded = ethabi::decode(
-            &[
-                ParamType::Tuple(vec![ParamType::Uint(64), ParamType::Uint(64)]),
-                ParamType::Bytes,
-            ],
-            data,
-        )
-        .map_err(|_e| PlasmaCoreError::from(PlasmaCoreErrorKind::AbiDecode))?;
-        Self::from_tuple(&decoded)
-    }
-}
-
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, AbiDecodable, AbiEncodable, AbiSchema)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub enum Witness {
-    Bytes(Bytes),
+    Bytes(
+        #[cfg_attr(
+            feature = "json",
+            serde(with = "plasma_core::data_structure::serde_hex")
+        )]
+        Bytes,
+    ),
     // inclusion proof and plasma data block
-    IncludedInIntervalTreeAtBlock(Bytes, PlasmaDataBlock),
+    IncludedInIntervalTreeAtBlock(
+        #[cfg_attr(
+            feature = "json",
+            serde(with = "plasma_core::data_structure::serde_hex")
+        )]
+        Bytes,
+        PlasmaDataBlock,
+    ),
 }
 
-impl Witness {
-    pub fn to_abi_part(&self) -> Vec<u8> {
-        ethabi::encode(&self.to_tuple_part())
-    }
-    pub fn to_tuple_part(&self) -> Vec<Token> {
-        match self {
-            Witness::Bytes(bytes) => vec![Token::Bytes(bytes.to_vec())],
-            Witness::IncludedInIntervalTreeAtBlock(inclusion_proof, plasma_data_block) => vec![
-                Token::Bytes(inclusion_proof.to_vec()),
-                Token::Bytes(plasma_data_block.to_abi()),
-            ],
-        }
-    }
-    fn from_tuple_part(id: u64, tuple: &[Token]) -> Result<Self, PlasmaCoreError> {
-        if id == 0 {
-            let bytes = tuple[0].clone().to_bytes();
-            if let Some(bytes) = bytes {
-                Ok(Witness::Bytes(Bytes::from(bytes)))
-            } else {
-                Err(PlasmaCoreError::from(PlasmaCoreErrorKind::AbiDecode))
-            }
-        } else {
-            let inclusion_proof = tuple[0].clone().to_bytes();
-            let plasma_data_block = tuple[1].clone().to_bytes();
-            if let (Some(inclusion_proof), Some(plasma_data_block)) =
-                (inclusion_proof, plasma_data_block)
-            {
-                Ok(Witness::IncludedInIntervalTreeAtBlock(
-                    Bytes::from(inclusion_proof),
-                    PlasmaDataBlock::from_abi(&plasma_data_block).unwrap(),
-                ))
-            } else {
-                Err(PlasmaCoreError::from(PlasmaCoreErrorKind::AbiDecode))
-            }
-        }
-    }
-    fn from_abi_part(id: U256, data: &[u8]) -> Result<Self, PlasmaCoreError> {
-        let decoded = ethabi::decode(&Self::get_param_types(id.as_u64()), data)
-            .map_err(|_e| PlasmaCoreError::from(PlasmaCoreErrorKind::AbiDecode))?;
-        Self::from_tuple_part(id.as_u64(), &decoded)
-    }
-    pub fn get_number(&self) -> U256 {
-        match self {
-            Witness::Bytes(_) => U256::from(0),
-            Witness::IncludedInIntervalTreeAtBlock(_, _) => U256::from(1),
-        }
-    }
-    fn get_param_types(id: u64) -> Vec<ParamType> {
-        if id == 0 {
-            vec![ParamType::Bytes]
-        } else {
-            vec![ParamType::Bytes, ParamType::Bytes]
-        }
-    }
-}
+#[cfg(test)]
+mod tests {
 
-impl Encodable for Witness {
-    fn to_abi(&self) -> Vec<u8> {
-        ethabi::encode(&self.to_tuple())
-    }
-    fn to_tuple(&self) -> Vec<Token> {
-        vec![
-            Token::Uint(self.get_number()),
-            Token::Bytes(self.to_abi_part()),
-        ]
-    }
-}
+    use super::{PlasmaDataBlock, Witness};
+    use crate::types::{IncludedInIntervalTreeAtBlockInput, Integer, Property};
+    use bytes::Bytes;
+    use ethabi::Token;
+    use plasma_core::data_structure::abi::{Decodable, Encodable};
+    use plasma_core::data_structure::Range;
 
-impl Decodable for Witness {
-    type Ok = Witness;
-    fn from_tuple(tuple: &[Token]) -> Result<Self, PlasmaCoreError> {
-        let witness_id = tuple[0].clone().to_uint();
-        let witness_data = tuple[1].clone().to_bytes();
-        if let (Some(witness_id), Some(witness_data)) = (witness_id, witness_data) {
-            Ok(Witness::from_abi_part(witness_id, &witness_data).unwrap())
-        } else {
-            Err(PlasmaCoreError::from(PlasmaCoreErrorKind::AbiDecode))
-        }
+    #[test]
+    fn test_encode_and_decode_included_in_interval_tree_witness() {
+        let property = Property::IncludedInIntervalTreeAtBlockDecider(
+            IncludedInIntervalTreeAtBlockInput::new(Integer(1), Range::new(0, 100)),
+        );
+        let witness = Witness::IncludedInIntervalTreeAtBlock(
+            Bytes::from(&b"proof"[..]),
+            PlasmaDataBlock::new(Integer(0), Range::new(0, 100), property),
+        );
+        let decoded = Witness::from_abi(&witness.to_abi()).unwrap();
+        assert_eq!(decoded.to_abi(), witness.to_abi());
     }
-    fn from_abi(data: &[u8]) -> Result<Self, PlasmaCoreError> {
-        let decoded = ethabi::decode(&[ParamType::Uint(256), ParamType::Bytes], data)
-            .map_err(|_e| PlasmaCoreError::from(PlasmaCoreErrorKind::AbiDecode))?;
-        Self::from_tuple(&decoded)
+
+    #[test]
+    fn test_decode_unknown_witness_id() {
+        let encoded = ethabi::encode(&[Token::Uint(2.into()), Token::Bytes(vec![])]);
+        assert!(Witness::from_abi(&encoded).is_err());
     }
+
 }
@@ -0,0 +1,320 @@
+use crate::error::{Error, ErrorKind};
+use crate::types::{
+    ImplicationProofElement, Property, Quantifier, QuantifierResultItem, Variable, Witness,
+};
+use ethabi::{ParamType, Token};
+use plasma_core::data_structure::abi::Encodable;
+
+/// Verifies the implication proof off-chain with the rules of `verifyImplication` of
+/// the Universal Decision Contract. The first element must be the root premise,
+/// and each element must imply the property of the next element.
+///
+/// - `And` implies either side.
+/// - `Or` implies the side whose index is the witness, encoded as `uint`.
+/// - `ForAllSuchThat` and `ThereExistsSuchThat` imply the template bound with the quantified
+///   result in the witness, encoded like witnesses of `ThereExistsSuchThat`.
+/// - Other properties don't imply anything, so they can only be the last element.
+pub fn verify_implication(
+    root_premise: &Property,
+    implication_proof: &[ImplicationProofElement],
+) -> Result<(), Error> {
+    let first = implication_proof.first().ok_or_else(invalid_proof)?;
+    if !is_same(first.get_implication(), root_premise) {
+        return Err(invalid_proof());
+    }
+    for pair in implication_proof.windows(2) {
+        verify_step(&pair[0], pair[1].get_implication())?;
+    }
+    Ok(())
+}
+
+/// Verifies that the premise implies the property
+fn verify_step(premise: &ImplicationProofElement, implication: &Property) -> Result<(), Error> {
+    let witness = premise.get_implication_witness();
+    let implied = match premise.get_implication() {
+        Property::AndDecider(input) => {
+            is_same(input.get_left(), implication) || is_same(input.get_right(), implication)
+        }
+        Property::OrDecider(input) => match decode_index(witness)? {
+            0 => is_same(input.get_left(), implication),
+            1 => is_same(input.get_right(), implication),
+            _ => false,
+        },
+        Property::ForAllSuchThatDecider(input) => is_same(
+            &bind_quantified(
+                input.get_quantifier(),
+                input.get_variable(),
+                input.get_property_template(),
+                witness,
+            )?,
+            implication,
+        ),
+        Property::ThereExistsSuchThatDecider(input) => is_same(
+            &bind_quantified(
+                input.get_quantifier(),
+                input.get_variable(),
+                input.get_property_template(),
+                witness,
+            )?,
+            implication,
+        ),
+        _ => false,
+    };
+    if implied {
+        Ok(())
+    } else {
+        Err(invalid_proof())
+    }
+}
+
+/// Binds the template with the quantified result in the witness
+fn bind_quantified(
+    quantifier: &Quantifier,
+    variable: &Variable,
+    template: &Property,
+    witness: &Option<Witness>,
+) -> Result<Property, Error> {
+    let item = match witness {
+        Some(Witness::Bytes(bytes)) => {
+            QuantifierResultItem::decode(quantifier, bytes).map_err(|_| invalid_proof())?
+        }
+        _ => return Err(invalid_proof()),
+    };
+    if !is_quantified(quantifier, &item) {
+        return Err(invalid_proof());
+    }
+    variable.bind_property(template, &item)
+}
+
+/// Returns false if the result is out of the quantifier.
+/// Results of `BlockRangeQuantifier` must be inclusions at its block within its coin range.
+fn is_quantified(quantifier: &Quantifier, item: &QuantifierResultItem) -> bool {
+    match (quantifier, item) {
+        (Quantifier::IntegerRangeQuantifier(range), QuantifierResultItem::Integer(n)) => {
+            range.get_start() <= n.0 && n.0 < range.get_end()
+        }
+        (
+            Quantifier::NonnegativeIntegerLessThanQuantifier(upper_bound),
+            QuantifierResultItem::Integer(n),
+        ) => n < upper_bound,
+        (Quantifier::SignedByQuantifier(signer), QuantifierResultItem::Message(message)) => {
+            message.get_signers().contains(signer)
+        }
+        (
+            Quantifier::BlockRangeQuantifier(block_range),
+            QuantifierResultItem::Property(Property::IncludedInIntervalTreeAtBlockDecider(input)),
+        ) => {
+            let range = block_range.get_coin_range();
+            let coin_range = input.get_coin_range();
+            input.get_block_number() == block_range.get_block_number()
+                && range.get_start() <= coin_range.get_start()
+                && coin_range.get_end() <= range.get_end()
+        }
+        _ => false,
+    }
+}
+
+fn decode_index(witness: &Option<Witness>) -> Result<u64, Error> {
+    if let Some(Witness::Bytes(bytes)) = witness {
+        if let Ok(mut tokens) = ethabi::decode(&[ParamType::Uint(256)], bytes) {
+            if let Token::Uint(index) = tokens.remove(0) {
+                if index < 2.into() {
+                    return Ok(index.low_u64());
+                }
+            }
+        }
+    }
+    Err(invalid_proof())
+}
+
+fn is_same(a: &Property, b: &Property) -> bool {
+    a.to_abi() == b.to_abi()
+}
+
+fn invalid_proof() -> Error {
+    Error::from(ErrorKind::InvalidImplicationProof)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::verify_implication;
+    use crate::dsl::parse_property;
+    use crate::property_executor::PropertyExecutor;
+    use crate::types::{ImplicationProofElement, Property, Witness};
+    use bytes::Bytes;
+    use ethabi::Token;
+    use plasma_db::impls::kvs::CoreDbLevelDbImpl;
+
+    const MESSAGE: &str = "Message(\"channel\", 0x0000000000000000000000000000000000000000, \
+                           0x0000000000000000000000000000000000000000, 3, [], 0x, 0x)";
+
+    fn lower_nonce(nonce: &str) -> Property {
+        parse_property(&format!("HasLowerNonce({}, {})", MESSAGE, nonce)).unwrap()
+    }
+
+    fn encode_uint(n: u64) -> Option<Witness> {
+        Some(Witness::Bytes(Bytes::from(ethabi::encode(&[Token::Uint(
+            n.into(),
+        )]))))
+    }
+
+    #[test]
+    fn test_verify_and_or() {
+        let root = parse_property(&format!(
+            "And(Or(HasLowerNonce({m}, 1), HasLowerNonce({m}, 5)), HasLowerNonce({m}, 6))",
+            m = MESSAGE
+        ))
+        .unwrap();
+        let or = parse_property(&format!(
+            "Or(HasLowerNonce({m}, 1), HasLowerNonce({m}, 5))",
+            m = MESSAGE
+        ))
+        .unwrap();
+        let proof = vec![
+            ImplicationProofElement::new(root.clone(), None),
+            ImplicationProofElement::new(or.clone(), encode_uint(1)),
+            ImplicationProofElement::new(lower_nonce("5"), None),
+        ];
+        assert!(verify_implication(&root, &proof).is_ok());
+        // the witness of Or chooses the other side
+        let proof = vec![
+            ImplicationProofElement::new(root.clone(), None),
+            ImplicationProofElement::new(or, encode_uint(0)),
+            ImplicationProofElement::new(lower_nonce("5"), None),
+        ];
+        assert!(verify_implication(&root, &proof).is_err());
+        // the root premise is missing
+        assert!(verify_implication(&root, &proof[1..]).is_err());
+        assert!(verify_implication(&root, &[]).is_err());
+    }
+
+    #[test]
+    fn test_verify_for_all() {
+        let root =
+            parse_property(&format!("ForAll n in 4..10: HasLowerNonce({}, n)", MESSAGE)).unwrap();
+        let proof = vec![
+            ImplicationProofElement::new(root.clone(), encode_uint(7)),
+            ImplicationProofElement::new(lower_nonce("7"), None),
+        ];
+        assert!(verify_implication(&root, &proof).is_ok());
+        // the result isn't quantified
+        let proof = vec![
+            ImplicationProofElement::new(root.clone(), encode_uint(2)),
+            ImplicationProofElement::new(lower_nonce("2"), None),
+        ];
+        assert!(verify_implication(&root, &proof).is_err());
+        // atomic properties don't imply anything
+        let proof = vec![
+            ImplicationProofElement::new(lower_nonce("7"), None),
+            ImplicationProofElement::new(lower_nonce("7"), None),
+        ];
+        assert!(verify_implication(&lower_nonce("7"), &proof).is_err());
+    }
+
+    #[test]
+    fn test_verify_there_exists_decision() {
+        let root = parse_property(&format!(
+            "ThereExists n in 0..10: HasLowerNonce({}, n)",
+            MESSAGE
+        ))
+        .unwrap();
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let decided = decider.decide(&root, None).unwrap();
+        assert!(verify_implication(&root, decided.get_implication_proof()).is_ok());
+    }
+
+    /// Decides the property and verifies the implication proof of the decision
+    fn decide_and_verify(property: &str, outcome: bool, proof_len: usize) {
+        let root = parse_property(&property.replace("{m}", MESSAGE)).unwrap();
+        let decider: PropertyExecutor<CoreDbLevelDbImpl> = Default::default();
+        let decided = decider.decide(&root, None).unwrap();
+        assert_eq!(decided.get_outcome(), outcome);
+        assert_eq!(decided.get_implication_proof().len(), proof_len);
+        assert!(verify_implication(&root, decided.get_implication_proof()).is_ok());
+    }
+
+    #[test]
+    fn test_verify_and_decision() {
+        decide_and_verify("And(HasLowerNonce({m}, 5), HasLowerNonce({m}, 6))", true, 1);
+        // the false side follows
+        decide_and_verify(
+            "And(HasLowerNonce({m}, 5), Not(HasLowerNonce({m}, 6)))",
+            false,
+            2,
+        );
+        // the false side follows even though its decider returns an empty proof
+        decide_and_verify(
+            "And(HasLowerNonce({m}, 5), HasLowerNonce({m}, 1))",
+            false,
+            2,
+        );
+        decide_and_verify(
+            "And(And(HasLowerNonce({m}, 5), Not(HasLowerNonce({m}, 6))), HasLowerNonce({m}, 7))",
+            false,
+            3,
+        );
+    }
+
+    #[test]
+    fn test_verify_or_decision() {
+        // the true side follows with its index
+        decide_and_verify("Or(HasLowerNonce({m}, 1), HasLowerNonce({m}, 5))", true, 2);
+        decide_and_verify("Or(HasLowerNonce({m}, 1), HasLowerNonce({m}, 2))", false, 1);
+        decide_and_verify(
+            "Or(HasLowerNonce({m}, 1), Or(HasLowerNonce({m}, 5), HasLowerNonce({m}, 2)))",
+            true,
+            3,
+        );
+    }
+
+    #[test]
+    fn test_verify_not_decision() {
+        decide_and_verify("Not(HasLowerNonce({m}, 1))", true, 1);
+        decide_and_verify("Not(HasLowerNonce({m}, 5))", false, 1);
+        decide_and_verify(
+            "Or(HasLowerNonce({m}, 1), Not(HasLowerNonce({m}, 2)))",
+            true,
+            2,
+        );
+    }
+
+    #[test]
+    fn test_verify_for_all_decision() {
+        decide_and_verify("ForAll n in 4..10: HasLowerNonce({m}, n)", true, 1);
+        // the counterexample follows
+        decide_and_verify("ForAll n in 2..5: Not(HasLowerNonce({m}, n))", false, 2);
+        decide_and_verify("ForAll n in 2..5: HasLowerNonce({m}, n)", false, 2);
+        decide_and_verify(
+            "ForAll k < 2: ForAll n in 2..5: Not(HasLowerNonce({m}, n))",
+            false,
+            3,
+        );
+    }
+
+    #[test]
+    fn test_verify_block_range() {
+        let root = parse_property(&format!(
+            "ForAll p in BlockRange(5, 0..100): Or(p, HasLowerNonce({}, 1))",
+            MESSAGE
+        ))
+        .unwrap();
+        let verify = |item: &str| {
+            let or = parse_property(&format!("Or({}, HasLowerNonce({}, 1))", item, MESSAGE));
+            let item = parse_property(item).unwrap();
+            let witness = Witness::Bytes(Bytes::from(ethabi::encode(&[item.clone().into()])));
+            let proof = vec![
+                ImplicationProofElement::new(root.clone(), Some(witness)),
+                ImplicationProofElement::new(or.unwrap(), encode_uint(0)),
+                ImplicationProofElement::new(item, None),
+            ];
+            verify_implication(&root, &proof)
+        };
+        assert!(verify("IncludedInIntervalTreeAtBlock(5, 10..20)").is_ok());
+        // other properties, blocks and ranges aren't quantified
+        assert!(verify(&format!("HasLowerNonce({}, 5)", MESSAGE)).is_err());
+        assert!(verify("IncludedInIntervalTreeAtBlock(6, 10..20)").is_err());
+        assert!(verify("IncludedInIntervalTreeAtBlock(5, 90..110)").is_err());
+    }
+
+}
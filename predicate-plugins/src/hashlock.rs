@@ -0,0 +1,373 @@
+use crate::error::{Error, ErrorKind};
+use crate::{PredicateParameters, PredicatePlugin};
+use bytes::Bytes;
+use ethabi::{ParamType, Token};
+use ethereum_types::{Address, H256};
+use ovm::deciders::preimage_exists_decider::Verifier;
+use plasma_core::data_structure::abi::{to_u64, Decodable};
+use plasma_core::data_structure::{StateObject, StateUpdate, Transaction};
+
+/// State of a conditional payment, which is the data of the state object
+pub struct HashlockState {
+    sender: Address,
+    recipient: Address,
+    hash: H256,
+    deadline: u64,
+}
+
+impl HashlockState {
+    /// Creates new state, the recipient can spend it with the preimage of `hash`
+    /// before `deadline`, and the sender can spend it after that
+    pub fn new(sender: Address, recipient: Address, hash: H256, deadline: u64) -> Self {
+        HashlockState {
+            sender,
+            recipient,
+            hash,
+            deadline,
+        }
+    }
+    pub fn get_sender(&self) -> Address {
+        self.sender
+    }
+    pub fn get_recipient(&self) -> Address {
+        self.recipient
+    }
+    pub fn get_hash(&self) -> H256 {
+        self.hash
+    }
+    pub fn get_deadline(&self) -> u64 {
+        self.deadline
+    }
+    /// Make state object data
+    pub fn encode(&self) -> Bytes {
+        Bytes::from(ethabi::encode(&[
+            Token::Address(self.sender),
+            Token::Address(self.recipient),
+            Token::FixedBytes(self.hash.as_bytes().to_vec()),
+            Token::Uint(self.deadline.into()),
+        ]))
+    }
+    /// Parse state object data
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        ethabi::decode(
+            &[
+                ParamType::Address,
+                ParamType::Address,
+                ParamType::FixedBytes(32),
+                ParamType::Uint(256),
+            ],
+            data,
+        )
+        .ok()
+        .and_then(|decoded| {
+            let sender = decoded[0].clone().to_address();
+            let recipient = decoded[1].clone().to_address();
+            let hash = decoded[2].clone().to_fixed_bytes();
+            let deadline = to_u64(decoded[3].clone());
+            if let (Some(sender), Some(recipient), Some(hash), Some(deadline)) =
+                (sender, recipient, hash, deadline)
+            {
+                Some(HashlockState::new(
+                    sender,
+                    recipient,
+                    H256::from_slice(&hash),
+                    deadline,
+                ))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Parameters of hashlock predicate
+pub struct HashlockPredicateParameters {
+    state_object: StateObject,
+    preimage: Bytes,
+    origin_block: u64,
+    max_block: u64,
+}
+
+impl HashlockPredicateParameters {
+    /// Creates new parameters object, `preimage` is empty to refund
+    pub fn new(
+        state_object: StateObject,
+        preimage: Bytes,
+        origin_block: u64,
+        max_block: u64,
+    ) -> Self {
+        HashlockPredicateParameters {
+            state_object,
+            preimage,
+            origin_block,
+            max_block,
+        }
+    }
+    pub fn get_state_object(&self) -> &StateObject {
+        &self.state_object
+    }
+    pub fn get_preimage(&self) -> &Bytes {
+        &self.preimage
+    }
+    pub fn get_origin_block(&self) -> u64 {
+        self.origin_block
+    }
+    pub fn get_max_block(&self) -> u64 {
+        self.max_block
+    }
+    /// Parse parameters from ABI
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        ethabi::decode(
+            &[
+                ParamType::Tuple(vec![ParamType::Address, ParamType::Bytes]),
+                ParamType::Bytes,
+                ParamType::Uint(16),
+                ParamType::Uint(16),
+            ],
+            data,
+        )
+        .ok()
+        .and_then(|decoded| {
+            let state_object_tuple = decoded[0].clone().to_tuple();
+            let preimage = decoded[1].clone().to_bytes();
+            let origin_block = to_u64(decoded[2].clone());
+            let max_block = to_u64(decoded[3].clone());
+            if let (Some(state_object_tuple), Some(preimage), Some(origin_block), Some(max_block)) =
+                (state_object_tuple, preimage, origin_block, max_block)
+            {
+                StateObject::from_tuple(&state_object_tuple)
+                    .ok()
+                    .map(|state_object| {
+                        HashlockPredicateParameters::new(
+                            state_object,
+                            Bytes::from(preimage),
+                            origin_block,
+                            max_block,
+                        )
+                    })
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl PredicateParameters for HashlockPredicateParameters {
+    /// Make parameters for hashlock predicate
+    fn encode(&self) -> Bytes {
+        Bytes::from(ethabi::encode(&[
+            Token::Tuple(vec![
+                Token::Address(self.get_state_object().get_predicate()),
+                Token::Bytes(self.get_state_object().get_data().to_vec()),
+            ]),
+            Token::Bytes(self.preimage.to_vec()),
+            Token::Uint(self.origin_block.into()),
+            Token::Uint(self.max_block.into()),
+        ]))
+    }
+}
+
+/// Hashlock predicate for conditional payments such as atomic swaps.
+/// The next owner must be the recipient with the preimage before the deadline,
+/// or the sender without it after the deadline. The deadline is compared with
+/// the pending block of the chain, and the spender only bounds it by `max_block`.
+/// The hash is `Verifier::static_hash` of `PreimageExistsDecider`, so the same preimage
+/// unlocks both.
+pub struct HashlockPredicate {}
+
+impl Default for HashlockPredicate {
+    fn default() -> Self {
+        HashlockPredicate {}
+    }
+}
+
+impl PredicatePlugin for HashlockPredicate {
+    fn execute_state_transition(
+        &self,
+        input: &StateUpdate,
+        transaction: &Transaction,
+        pending_block_number: u64,
+    ) -> Result<StateUpdate, Error> {
+        let parameters = HashlockPredicateParameters::decode(transaction.get_parameters())
+            .ok_or(ErrorKind::InvalidParameters)?;
+        let state = HashlockState::decode(input.get_state_object().get_data())
+            .ok_or(ErrorKind::InvalidState)?;
+        if input.get_block_number() > parameters.get_origin_block()
+            || pending_block_number > parameters.get_max_block()
+        {
+            return Err(Error::from(ErrorKind::InvalidBlockNumber));
+        }
+        let (next_owner, before_deadline) = if parameters.get_preimage().is_empty() {
+            (state.get_sender(), false)
+        } else {
+            if Verifier::static_hash(parameters.get_preimage()) != state.get_hash() {
+                return Err(Error::from(ErrorKind::InvalidPreimage));
+            }
+            (state.get_recipient(), true)
+        };
+        if (pending_block_number < state.get_deadline()) != before_deadline {
+            return Err(Error::from(ErrorKind::InvalidBlockNumber));
+        }
+        if parameters.get_state_object().get_data() != &Bytes::from(next_owner.as_bytes()) {
+            return Err(Error::from(ErrorKind::InvalidNextOwner));
+        }
+        Ok(StateUpdate::new(
+            parameters.get_state_object().clone(),
+            transaction.get_range(),
+            pending_block_number,
+            transaction.get_plasma_contract_address(),
+        ))
+    }
+
+    /// Returns the recipient and the sender
+    fn query_state(&self, state_update: &StateUpdate, _parameters: &[u8]) -> Vec<Bytes> {
+        match HashlockState::decode(state_update.get_state_object().get_data()) {
+            Some(state) => vec![
+                Bytes::from(state.get_recipient().as_bytes()),
+                Bytes::from(state.get_sender().as_bytes()),
+            ],
+            None => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HashlockPredicate, HashlockPredicateParameters, HashlockState};
+    use crate::error::{Error, ErrorKind};
+    use crate::{PredicateParameters, PredicatePlugin};
+    use bytes::Bytes;
+    use ethabi::Token;
+    use ethereum_types::{Address, H256, U256};
+    use ovm::deciders::preimage_exists_decider::Verifier;
+    use plasma_core::data_structure::{Range, StateObject, StateUpdate, Transaction, Witness};
+
+    fn secret_hash() -> H256 {
+        Verifier::static_hash(&Bytes::from(&b"secret"[..]))
+    }
+
+    fn transition(
+        preimage: &[u8],
+        next_owner: Address,
+        pending_block_number: u64,
+        max_block: u64,
+    ) -> Result<StateUpdate, Error> {
+        let predicate_address = Address::zero();
+        let alice_address = Address::from_low_u64_be(1);
+        let bob_address = Address::from_low_u64_be(2);
+        let state = HashlockState::new(alice_address, bob_address, secret_hash(), 20);
+        let state_update = StateUpdate::new(
+            StateObject::new(predicate_address, state.encode()),
+            Range::new(0, 1000),
+            10,
+            Address::zero(),
+        );
+        let parameters = HashlockPredicateParameters::new(
+            StateObject::new(predicate_address, Bytes::from(next_owner.as_bytes())),
+            Bytes::from(preimage),
+            10,
+            max_block,
+        );
+        let transaction = Transaction::new(
+            Address::zero(),
+            Range::new(0, 1000),
+            parameters.encode(),
+            &Witness::new(H256::zero(), H256::zero(), 0),
+        );
+        let predicate: HashlockPredicate = Default::default();
+        predicate.execute_state_transition(&state_update, &transaction, pending_block_number)
+    }
+
+    fn assert_error(result: Result<StateUpdate, Error>, kind: ErrorKind) {
+        assert_eq!(
+            format!("{}", result.unwrap_err().kind()),
+            format!("{}", kind)
+        );
+    }
+
+    #[test]
+    fn test_claim_and_refund() {
+        let alice_address = Address::from_low_u64_be(1);
+        let bob_address = Address::from_low_u64_be(2);
+        let claimed = transition(b"secret", bob_address, 15, 15).unwrap();
+        assert_eq!(
+            claimed.get_state_object().get_data(),
+            &Bytes::from(bob_address.as_bytes())
+        );
+        let refunded = transition(b"", alice_address, 20, 20).unwrap();
+        assert_eq!(
+            refunded.get_state_object().get_data(),
+            &Bytes::from(alice_address.as_bytes())
+        );
+    }
+
+    #[test]
+    fn test_claim_with_wrong_preimage() {
+        let result = transition(b"wrong", Address::from_low_u64_be(2), 15, 15);
+        assert_error(result, ErrorKind::InvalidPreimage);
+    }
+
+    #[test]
+    fn test_transition_to_wrong_owner() {
+        let result = transition(b"secret", Address::from_low_u64_be(1), 15, 15);
+        assert_error(result, ErrorKind::InvalidNextOwner);
+        let result = transition(b"", Address::from_low_u64_be(2), 20, 20);
+        assert_error(result, ErrorKind::InvalidNextOwner);
+    }
+
+    #[test]
+    fn test_refund_before_deadline() {
+        let result = transition(b"", Address::from_low_u64_be(1), 15, 15);
+        assert_error(result, ErrorKind::InvalidBlockNumber);
+        let result = transition(b"", Address::from_low_u64_be(1), 15, 30);
+        assert_error(result, ErrorKind::InvalidBlockNumber);
+    }
+
+    #[test]
+    fn test_claim_after_deadline() {
+        let result = transition(b"secret", Address::from_low_u64_be(2), 25, 15);
+        assert_error(result, ErrorKind::InvalidBlockNumber);
+        let result = transition(b"secret", Address::from_low_u64_be(2), 25, 30);
+        assert_error(result, ErrorKind::InvalidBlockNumber);
+    }
+
+    #[test]
+    fn test_transition_with_invalid_parameters() {
+        let state = HashlockState::new(Address::zero(), Address::zero(), secret_hash(), 20);
+        let state_update = StateUpdate::new(
+            StateObject::new(Address::zero(), state.encode()),
+            Range::new(0, 1000),
+            10,
+            Address::zero(),
+        );
+        let transaction = Transaction::new(
+            Address::zero(),
+            Range::new(0, 1000),
+            Bytes::from(&b"invalid"[..]),
+            &Witness::new(H256::zero(), H256::zero(), 0),
+        );
+        let predicate: HashlockPredicate = Default::default();
+        let result = predicate.execute_state_transition(&state_update, &transaction, 15);
+        assert_error(result, ErrorKind::InvalidParameters);
+    }
+
+    #[test]
+    fn test_decode_overflowing_blocks() {
+        let max = U256::from(u64::max_value()) + 1;
+        let state = ethabi::encode(&[
+            Token::Address(Address::from_low_u64_be(1)),
+            Token::Address(Address::from_low_u64_be(2)),
+            Token::FixedBytes(secret_hash().as_bytes().to_vec()),
+            Token::Uint(max),
+        ]);
+        assert!(HashlockState::decode(&state).is_none());
+        let parameters = ethabi::encode(&[
+            Token::Tuple(vec![Token::Address(Address::zero()), Token::Bytes(vec![])]),
+            Token::Bytes(vec![]),
+            Token::Uint(10.into()),
+            Token::Uint(max),
+        ]);
+        assert!(HashlockPredicateParameters::decode(&parameters).is_none());
+    }
+
+}
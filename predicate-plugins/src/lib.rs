@@ -1,8 +1,12 @@
+pub mod error;
+pub mod hashlock;
 pub mod ownership;
 pub mod parameters;
 pub mod predicate;
 pub mod predicate_manager;
 
+pub use error::{Error, ErrorKind};
+pub use hashlock::{HashlockPredicate, HashlockPredicateParameters, HashlockState};
 pub use ownership::{OwnershipPredicate, OwnershipPredicateParameters};
 pub use parameters::PredicateParameters;
 pub use predicate::PredicatePlugin;
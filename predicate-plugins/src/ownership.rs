@@ -1,7 +1,8 @@
+use crate::error::{Error, ErrorKind};
 use crate::{PredicateParameters, PredicatePlugin};
 use bytes::Bytes;
 use ethabi::{ParamType, Token};
-use plasma_core::data_structure::abi::Decodable;
+use plasma_core::data_structure::abi::{to_u64, Decodable};
 use plasma_core::data_structure::{StateObject, StateUpdate, Transaction};
 
 /// Parameters of ownership predicate
@@ -42,19 +43,15 @@ impl OwnershipPredicateParameters {
         .ok()
         .and_then(|decoded| {
             let state_object_tuple = decoded[0].clone().to_tuple();
-            let origin_block = decoded[1].clone().to_uint();
-            let max_block = decoded[2].clone().to_uint();
+            let origin_block = to_u64(decoded[1].clone());
+            let max_block = to_u64(decoded[2].clone());
             if let (Some(state_object_tuple), Some(origin_block), Some(max_block)) =
                 (state_object_tuple, origin_block, max_block)
             {
                 StateObject::from_tuple(&state_object_tuple)
                     .ok()
                     .map(|state_object| {
-                        OwnershipPredicateParameters::new(
-                            state_object,
-                            origin_block.as_u64(),
-                            max_block.as_u64(),
-                        )
+                        OwnershipPredicateParameters::new(state_object, origin_block, max_block)
                     })
             } else {
                 None
@@ -91,21 +88,23 @@ impl PredicatePlugin for OwnershipPredicate {
         &self,
         input: &StateUpdate,
         transaction: &Transaction,
-    ) -> StateUpdate {
+        pending_block_number: u64,
+    ) -> Result<StateUpdate, Error> {
         // should parse transaction.parameters
         // make new state update
-        let parameters =
-            OwnershipPredicateParameters::decode(transaction.get_parameters()).unwrap();
-        // Where does the function get pending block number from?
-        let pending_block_number = parameters.get_max_block();
-        assert!(input.get_block_number() <= parameters.get_origin_block());
-        assert!(pending_block_number <= parameters.get_max_block());
-        StateUpdate::new(
+        let parameters = OwnershipPredicateParameters::decode(transaction.get_parameters())
+            .ok_or(ErrorKind::InvalidParameters)?;
+        if input.get_block_number() > parameters.get_origin_block()
+            || pending_block_number > parameters.get_max_block()
+        {
+            return Err(Error::from(ErrorKind::InvalidBlockNumber));
+        }
+        Ok(StateUpdate::new(
             parameters.get_state_object().clone(),
             transaction.get_range(),
             pending_block_number,
             transaction.get_plasma_contract_address(),
-        )
+        ))
     }
 
     fn query_state(&self, state_update: &StateUpdate, _parameters: &[u8]) -> Vec<Bytes> {
@@ -117,6 +116,7 @@ impl PredicatePlugin for OwnershipPredicate {
 #[cfg(test)]
 mod tests {
     use super::{OwnershipPredicate, OwnershipPredicateParameters};
+    use crate::error::ErrorKind;
     use crate::{PredicateParameters, PredicatePlugin};
     use bytes::Bytes;
     use ethereum_types::{Address, H256};
@@ -152,7 +152,9 @@ mod tests {
         );
 
         let predicate: OwnershipPredicate = Default::default();
-        let next_state_update = predicate.execute_state_transition(&state_update, &transaction);
+        let next_state_update = predicate
+            .execute_state_transition(&state_update, &transaction, 15)
+            .unwrap();
         assert_eq!(
             next_state_update.get_range().get_start(),
             transaction.get_range().get_start()
@@ -163,4 +165,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_execute_state_transition_after_max_block() {
+        let state_update = StateUpdate::new(
+            StateObject::new(Address::zero(), Bytes::from(&b"data"[..])),
+            Range::new(0, 1000),
+            10,
+            Address::zero(),
+        );
+        let parameters = OwnershipPredicateParameters::new(
+            StateObject::new(Address::zero(), Bytes::from(Address::zero().as_bytes())),
+            10,
+            20,
+        );
+        let transaction = Transaction::new(
+            Address::zero(),
+            Range::new(0, 1000),
+            parameters.encode(),
+            &Witness::new(H256::zero(), H256::zero(), 0),
+        );
+        let predicate: OwnershipPredicate = Default::default();
+        let result = predicate.execute_state_transition(&state_update, &transaction, 21);
+        match result.unwrap_err().kind() {
+            ErrorKind::InvalidBlockNumber => {}
+            _ => panic!("unexpected error"),
+        }
+    }
+
 }
@@ -1,13 +1,17 @@
+use crate::error::Error;
 use bytes::Bytes;
 use plasma_core::data_structure::{StateUpdate, Transaction};
 
 /// Base class of predicate plugin
 pub trait PredicatePlugin {
+    /// Executes the transaction in the block `pending_block_number`, which is given by
+    /// the chain instead of the parameters signed by the spender
     fn execute_state_transition(
         &self,
         input: &StateUpdate,
         transaction: &Transaction,
-    ) -> StateUpdate;
+        pending_block_number: u64,
+    ) -> Result<StateUpdate, Error>;
 
     fn query_state(&self, state_update: &StateUpdate, parameters: &[u8]) -> Vec<Bytes>;
 }
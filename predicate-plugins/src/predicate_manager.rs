@@ -1,3 +1,4 @@
+use crate::hashlock::HashlockPredicate;
 use crate::ownership::OwnershipPredicate;
 use crate::predicate::PredicatePlugin;
 use ethereum_types::Address;
@@ -6,8 +7,18 @@ use ethereum_types::Address;
 pub struct PredicateManager {}
 
 impl PredicateManager {
-    pub fn get_plugin(_address: Address) -> Box<dyn PredicatePlugin> {
-        let predicate: OwnershipPredicate = Default::default();
-        Box::new(predicate)
+    /// Address of `HashlockPredicate`
+    pub fn get_hashlock_predicate_address() -> Address {
+        Address::from_low_u64_be(1)
+    }
+    /// Returns `HashlockPredicate` for its address and `OwnershipPredicate` otherwise
+    pub fn get_plugin(address: Address) -> Box<dyn PredicatePlugin> {
+        if address == Self::get_hashlock_predicate_address() {
+            let predicate: HashlockPredicate = Default::default();
+            Box::new(predicate)
+        } else {
+            let predicate: OwnershipPredicate = Default::default();
+            Box::new(predicate)
+        }
     }
 }